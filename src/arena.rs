@@ -0,0 +1,334 @@
+use crate::capsule::Capsule;
+use crate::items::{Ball, Wall};
+use crate::joint::{Joint, PrismaticJoint, RevoluteJoint};
+use crate::polygon::Polygon;
+use crate::sensor::Sensor;
+use std::cmp::Ordering;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::marker::PhantomData;
+
+/// Handle to a [`Ball`] in a grid
+pub type BallHandle = Handle<Ball>;
+/// Handle to a [`Wall`] in a grid
+pub type WallHandle = Handle<Wall>;
+/// Handle to a [`Polygon`] in a grid
+pub type PolygonHandle = Handle<Polygon>;
+/// Handle to a [`Capsule`] in a grid
+pub type CapsuleHandle = Handle<Capsule>;
+/// Handle to a [`Sensor`] in a grid
+pub type SensorHandle = Handle<Sensor>;
+/// Handle to a [`Joint`] in a grid
+pub type JointHandle = Handle<Joint>;
+/// Handle to a [`RevoluteJoint`] in a grid
+pub type RevoluteHandle = Handle<RevoluteJoint>;
+/// Handle to a [`PrismaticJoint`] in a grid
+pub type PrismaticHandle = Handle<PrismaticJoint>;
+
+/// A generational handle to a value stored in an [`Arena`].
+///
+/// A handle is the index of a slot plus the generation the slot had when the value
+/// was inserted. Removing the value bumps the slot's generation, so the old handle
+/// stops resolving even after the slot is reused for a new value. Handles are typed,
+/// so a ball handle cannot be used to look up a wall.
+pub struct Handle<T> {
+    /// Index of the slot in the arena
+    index: u32,
+    /// Generation of the slot when the value was inserted
+    generation: u32,
+    /// Ties the handle to the type of value it refers to
+    marker: PhantomData<fn() -> T>,
+}
+
+impl<T> Handle<T> {
+    /// A handle that never refers to a value, used by bodies not yet added to a grid.
+    pub const DANGLING: Handle<T> = Handle { index: u32::MAX, generation: 0, marker: PhantomData };
+
+    /// Returns the index of the handle's slot.
+    ///
+    /// Slots are reused, so the index alone does not identify a value over time.
+    pub fn index(&self) -> usize {
+        self.index as usize
+    }
+
+    /// Returns the generation of the handle's slot.
+    pub fn generation(&self) -> u32 {
+        self.generation
+    }
+}
+
+// The trait implementations are written out by hand, since deriving them would
+// require `T` to implement them as well.
+impl<T> Clone for Handle<T> {
+    fn clone(&self) -> Handle<T> {
+        *self
+    }
+}
+
+impl<T> Copy for Handle<T> {}
+
+impl<T> PartialEq for Handle<T> {
+    fn eq(&self, other: &Handle<T>) -> bool {
+        self.index == other.index && self.generation == other.generation
+    }
+}
+
+impl<T> Eq for Handle<T> {}
+
+impl<T> Hash for Handle<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.index.hash(state);
+        self.generation.hash(state);
+    }
+}
+
+impl<T> PartialOrd for Handle<T> {
+    fn partial_cmp(&self, other: &Handle<T>) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T> Ord for Handle<T> {
+    fn cmp(&self, other: &Handle<T>) -> Ordering {
+        (self.index, self.generation).cmp(&(other.index, other.generation))
+    }
+}
+
+impl<T> fmt::Debug for Handle<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}v{}", self.index, self.generation)
+    }
+}
+
+impl<T> Default for Handle<T> {
+    fn default() -> Handle<T> {
+        Handle::DANGLING
+    }
+}
+
+/// A slot of an [`Arena`], pointing at its value in the dense storage while occupied.
+struct Slot {
+    /// Current generation of the slot, bumped whenever its value is removed
+    generation: u32,
+    /// Position of the slot's value in the dense storage, or `None` if the slot is free
+    dense: Option<u32>,
+}
+
+/// A slotmap-style arena storing values densely and handing out generational handles.
+///
+/// Values live in one contiguous vector, so iterating takes time proportional to the
+/// number of live values. A removed value's place is filled by the last value, and
+/// its slot is reused by the next insertion with a bumped generation, so memory is
+/// reused and stale handles are detected.
+pub struct Arena<T> {
+    /// Slots indexed by handle, pointing into the dense storage
+    slots: Vec<Slot>,
+    /// Live values, densely packed
+    values: Vec<T>,
+    /// Handle of each value in `values`
+    handles: Vec<Handle<T>>,
+    /// Indices of free slots, reused before new slots are added
+    free: Vec<u32>,
+}
+
+impl<T> Arena<T> {
+    /// Creates a new, empty arena.
+    pub fn new() -> Arena<T> {
+        Arena {
+            slots: Vec::new(),
+            values: Vec::new(),
+            handles: Vec::new(),
+            free: Vec::new(),
+        }
+    }
+
+    /// Returns the number of live values.
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Returns whether the arena holds no values.
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Inserts a value, reusing a free slot if there is one.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The value to store
+    ///
+    /// # Returns
+    ///
+    /// The handle of the new value
+    pub fn insert(&mut self, value: T) -> Handle<T> {
+        let dense = Some(self.values.len() as u32);
+        let index = match self.free.pop() {
+            Some(index) => {
+                self.slots[index as usize].dense = dense;
+                index
+            },
+            None => {
+                self.slots.push(Slot { generation: 0, dense });
+                (self.slots.len() - 1) as u32
+            },
+        };
+        let handle = Handle { index, generation: self.slots[index as usize].generation, marker: PhantomData };
+        self.values.push(value);
+        self.handles.push(handle);
+        handle
+    }
+
+    /// Returns the position of a handle's value in the dense storage, if it is live.
+    fn dense_index(&self, handle: Handle<T>) -> Option<usize> {
+        let slot = self.slots.get(handle.index as usize)?;
+        if slot.generation != handle.generation {
+            return None;
+        }
+        slot.dense.map(|dense| dense as usize)
+    }
+
+    /// Returns whether a handle refers to a live value.
+    pub fn contains(&self, handle: Handle<T>) -> bool {
+        self.dense_index(handle).is_some()
+    }
+
+    /// Returns the value of a handle, or `None` if it was removed.
+    pub fn get(&self, handle: Handle<T>) -> Option<&T> {
+        self.dense_index(handle).map(|dense| &self.values[dense])
+    }
+
+    /// Returns the value of a handle mutably, or `None` if it was removed.
+    pub fn get_mut(&mut self, handle: Handle<T>) -> Option<&mut T> {
+        self.dense_index(handle).map(|dense| &mut self.values[dense])
+    }
+
+    /// Returns the values of two different handles mutably.
+    ///
+    /// # Returns
+    ///
+    /// Both values, or `None` if either was removed or both handles are the same
+    pub fn get_pair_mut(&mut self, a: Handle<T>, b: Handle<T>) -> Option<(&mut T, &mut T)> {
+        let (a, b) = (self.dense_index(a)?, self.dense_index(b)?);
+        let [a, b] = self.values.get_disjoint_mut([a, b]).ok()?;
+        Some((a, b))
+    }
+
+    /// Removes the value of a handle.
+    ///
+    /// The last value is moved into its place, and the slot is freed for reuse with
+    /// a new generation, so the handle (and copies of it) no longer resolve.
+    ///
+    /// # Returns
+    ///
+    /// The removed value, or `None` if it was already removed
+    pub fn remove(&mut self, handle: Handle<T>) -> Option<T> {
+        let dense = self.dense_index(handle)?;
+        let slot = &mut self.slots[handle.index as usize];
+        slot.dense = None;
+        slot.generation = slot.generation.wrapping_add(1);
+        self.free.push(handle.index);
+        let value = self.values.swap_remove(dense);
+        self.handles.swap_remove(dense);
+        // Point the slot of the value that moved into the gap at its new place
+        if let Some(moved) = self.handles.get(dense) {
+            self.slots[moved.index as usize].dense = Some(dense as u32);
+        }
+        Some(value)
+    }
+
+    /// Returns an iterator over the handles of all live values.
+    pub fn handles(&self) -> impl Iterator<Item = Handle<T>> + '_ {
+        self.handles.iter().copied()
+    }
+
+    /// Returns an iterator over all live values.
+    pub fn values(&self) -> impl Iterator<Item = &T> {
+        self.values.iter()
+    }
+
+    /// Returns an iterator over all live values and their handles.
+    pub fn iter(&self) -> impl Iterator<Item = (Handle<T>, &T)> {
+        self.handles.iter().copied().zip(self.values.iter())
+    }
+
+    /// Returns an iterator over all live values, mutably, and their handles.
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (Handle<T>, &mut T)> {
+        self.handles.iter().copied().zip(self.values.iter_mut())
+    }
+}
+
+impl<T> Default for Arena<T> {
+    fn default() -> Arena<T> {
+        Arena::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn inserted_values_are_found_by_their_handles() {
+        let mut arena = Arena::new();
+        let a = arena.insert("a");
+        let b = arena.insert("b");
+        assert_ne!(a, b);
+        assert_eq!(arena.len(), 2);
+        assert_eq!(arena.get(a), Some(&"a"));
+        assert_eq!(arena.get(b), Some(&"b"));
+        *arena.get_mut(b).unwrap() = "c";
+        assert_eq!(arena.get(b), Some(&"c"));
+    }
+
+    #[test]
+    fn removed_slots_are_reused_with_a_new_generation() {
+        let mut arena = Arena::new();
+        let a = arena.insert(1);
+        assert_eq!(arena.remove(a), Some(1));
+        assert_eq!(arena.remove(a), None);
+        assert!(arena.is_empty());
+        let b = arena.insert(2);
+        assert_eq!(b.index(), a.index());
+        assert_eq!(b.generation(), a.generation() + 1);
+        assert_eq!(arena.get(b), Some(&2));
+    }
+
+    #[test]
+    fn stale_handles_resolve_to_nothing() {
+        let mut arena = Arena::new();
+        let stale = arena.insert(1);
+        arena.remove(stale);
+        arena.insert(2);
+        assert!(!arena.contains(stale));
+        assert_eq!(arena.get(stale), None);
+        assert_eq!(arena.get_mut(stale), None);
+        assert_eq!(arena.get(Handle::DANGLING), None);
+    }
+
+    #[test]
+    fn removing_keeps_the_other_handles_valid() {
+        let mut arena = Arena::new();
+        let handles: Vec<_> = (0..4).map(|i| arena.insert(i)).collect();
+        // The last value is moved into the removed value's place
+        arena.remove(handles[1]);
+        for (i, handle) in handles.iter().enumerate().filter(|(i, _)| *i != 1) {
+            assert_eq!(arena.get(*handle), Some(&i));
+        }
+        assert_eq!(arena.handles().collect::<Vec<_>>(), [handles[0], handles[3], handles[2]]);
+        assert_eq!(arena.values().copied().collect::<Vec<_>>(), [0, 3, 2]);
+    }
+
+    #[test]
+    fn pairs_need_two_different_live_handles() {
+        let mut arena = Arena::new();
+        let a = arena.insert(1);
+        let b = arena.insert(2);
+        assert!(arena.get_pair_mut(a, a).is_none());
+        let (x, y) = arena.get_pair_mut(b, a).unwrap();
+        std::mem::swap(x, y);
+        assert_eq!((arena.get(a), arena.get(b)), (Some(&2), Some(&1)));
+        arena.remove(b);
+        assert!(arena.get_pair_mut(a, b).is_none());
+    }
+}
@@ -0,0 +1,74 @@
+use crate::collision::Shape;
+use crate::filter::CollisionFilter;
+use crate::library::Vec2;
+use crate::material::Material;
+
+/// Common interface of physics objects that take part in impulse-based collision response.
+///
+/// Dynamic bodies ([`Ball`](crate::Ball), [`Polygon`](crate::Polygon), [`Capsule`](crate::Capsule)) move in response
+/// to impulses. Static bodies ([`Wall`](crate::Wall)) report an inverse mass of zero and
+/// ignore impulses and translations, so the same resolution code handles both.
+pub trait RigidBody {
+    /// Returns the body's center of mass in world space.
+    fn position(&self) -> Vec2;
+
+    /// Returns the velocity of a point on the body, including the effect of spin.
+    ///
+    /// # Arguments
+    ///
+    /// * `offset` - Point relative to the body's center of mass [x, y]
+    fn velocity_at(&self, offset: Vec2) -> Vec2;
+
+    /// Returns the body's orientation in radians (positive = clockwise on screen).
+    fn angle(&self) -> f32;
+
+    /// Returns the body's angular velocity in radians per second.
+    fn angular_velocity(&self) -> f32;
+
+    /// Returns the inverse of the body's mass, or 0.0 for an immovable body.
+    fn inv_mass(&self) -> f32;
+
+    /// Returns the inverse of the body's moment of inertia, or 0.0 if it cannot spin.
+    fn inv_inertia(&self) -> f32;
+
+    /// Applies an impulse at a point on the body, changing both its velocity and spin.
+    ///
+    /// # Arguments
+    ///
+    /// * `impulse` - The impulse vector to apply [jx, jy]
+    /// * `offset` - Point of application relative to the center of mass [x, y]
+    fn apply_impulse_at(&mut self, impulse: Vec2, offset: Vec2);
+
+    /// Applies an angular impulse, changing only the body's spin.
+    ///
+    /// # Arguments
+    ///
+    /// * `impulse` - The angular impulse to apply (positive = clockwise on screen)
+    fn apply_angular_impulse(&mut self, impulse: f32);
+
+    /// Moves the body without changing its velocity (used to resolve penetration).
+    ///
+    /// # Arguments
+    ///
+    /// * `delta` - Displacement to apply [dx, dy]
+    fn translate(&mut self, delta: Vec2);
+
+    /// Turns the body without changing its spin (used to resolve penetration).
+    ///
+    /// # Arguments
+    ///
+    /// * `delta` - Angle to turn by in radians (positive = clockwise on screen)
+    fn rotate(&mut self, delta: f32);
+
+    /// Returns the body's surface material.
+    fn material(&self) -> Material;
+
+    /// Returns the body's collision layers.
+    fn filter(&self) -> CollisionFilter;
+
+    /// Returns the body's collision shape in world space.
+    fn shape(&self) -> Shape;
+
+    /// Returns the axis-aligned bounding box of the body as (min, max) corners.
+    fn aabb(&self) -> (Vec2, Vec2);
+}
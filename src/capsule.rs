@@ -0,0 +1,306 @@
+use crate::arena::{CapsuleHandle, Handle};
+use crate::body::RigidBody;
+use crate::color::Color;
+use crate::collision::Shape;
+use crate::filter::CollisionFilter;
+use crate::items::DEFAULT_DENSITY;
+#[cfg(feature = "sdl")]
+use crate::items::to_abgr;
+use crate::integrator::Integrator;
+use crate::library::Vec2;
+use crate::material::{CombineRule, Material};
+#[cfg(feature = "sdl")]
+use sdl2::render::{Canvas, RenderTarget};
+#[cfg(feature = "sdl")]
+use sdl2::gfx::primitives::DrawRenderer;
+
+/// Represents a capsule (thick segment) rigid body in the physics simulation.
+///
+/// A capsule is a line segment inflated by a radius, giving a rod with rounded ends.
+/// It is the dynamic counterpart of a [`Wall`](crate::Wall) and shares its collision
+/// logic: the segment is stored as a half length along the capsule's local x axis,
+/// rotated by `angle` around the center of mass.
+pub struct Capsule {
+    /// Handle of this capsule in its grid (dangling until the capsule is added)
+    pub id: CapsuleHandle,
+    /// Current position of the center of mass in world space [x, y]
+    pub position: Vec2,
+    /// Current velocity vector [vx, vy]
+    pub velocity: Vec2,
+    /// Current orientation in radians
+    pub angle: f32,
+    /// Current angular velocity in radians per second (positive = clockwise on screen)
+    pub angular_velocity: f32,
+    /// Half the length of the core segment
+    pub half_length: f32,
+    /// Radius the core segment is inflated by
+    pub radius: f32,
+    /// Color used to render the capsule
+    pub color: Color,
+    /// Friction coefficient (limits the tangential impulse in collisions)
+    pub friction: f32,
+    /// Restitution coefficient (affects normal velocity bounce in collisions)
+    pub restitution: f32,
+    /// Rule for combining this capsule's friction with another object's
+    pub friction_combine: CombineRule,
+    /// Rule for combining this capsule's restitution with another object's
+    pub restitution_combine: CombineRule,
+    /// Material identifier used to look up pair overrides in a [`MaterialTable`](crate::MaterialTable) (0 = none)
+    pub material_id: u32,
+    /// Collision layers deciding which bodies the capsule collides with
+    pub filter: CollisionFilter,
+    /// Mass of the capsule (a non-positive mass makes the capsule immovable)
+    pub mass: f32,
+    /// Moment of inertia about the center of mass (a non-positive inertia prevents spinning)
+    pub inertia: f32,
+    /// IDs of the grid sections this capsule's bounding box overlaps [x_unit, y_unit]
+    pub unit_ids: Vec<[usize; 2]>,
+}
+
+impl Capsule {
+    /// Creates a new capsule between two points.
+    ///
+    /// Mass and moment of inertia are derived from the area of the rectangle and the
+    /// two half circles making up the capsule.
+    ///
+    /// # Arguments
+    ///
+    /// * `a` - Starting point of the core segment [x, y]
+    /// * `b` - Ending point of the core segment [x, y]
+    /// * `radius` - Radius the segment is inflated by (half the capsule's thickness)
+    /// * `velocity` - Optional initial velocity [vx, vy] (default: [0.0, 0.0])
+    /// * `color` - Optional color (default: CYAN)
+    /// * `friction` - Optional friction coefficient (default: 0.1)
+    /// * `restitution` - Optional restitution coefficient (default: 0.1)
+    /// * `density` - Optional mass per square pixel (default: [`DEFAULT_DENSITY`])
+    ///
+    /// # Returns
+    ///
+    /// A new Capsule instance centered between `a` and `b`
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        a: Vec2,
+        b: Vec2,
+        radius: f32,
+        velocity: Option<Vec2>,
+        color: Option<Color>,
+        friction: Option<f32>,
+        restitution: Option<f32>,
+        density: Option<f32>,
+    ) -> Capsule {
+        let density = density.unwrap_or(DEFAULT_DENSITY);
+        let axis = b - a;
+        let length = axis.length();
+        let half_length = length / 2.0;
+
+        // Rectangle part
+        let box_mass = length * 2.0 * radius * density;
+        let box_inertia = box_mass * (4.0 * radius * radius + length * length) / 12.0;
+        // Two half circles, shifted out to the ends by the parallel axis theorem
+        let cap_mass = std::f32::consts::PI * radius * radius * density;
+        let cap_centroid = 4.0 * radius / (3.0 * std::f32::consts::PI);
+        let cap_inertia = cap_mass
+            * (0.5 * radius * radius + half_length * half_length + 2.0 * half_length * cap_centroid);
+
+        Capsule {
+            id: Handle::DANGLING,
+            position: a.lerp(b, 0.5),
+            velocity: velocity.unwrap_or(Vec2::ZERO),
+            angle: axis.y.atan2(axis.x),
+            angular_velocity: 0.0,
+            half_length,
+            radius,
+            color: color.unwrap_or(Color::CYAN),
+            friction: friction.unwrap_or(0.1),
+            restitution: restitution.unwrap_or(0.1),
+            friction_combine: CombineRule::default(),
+            restitution_combine: CombineRule::default(),
+            material_id: 0,
+            filter: CollisionFilter::default(),
+            mass: box_mass + cap_mass,
+            inertia: box_inertia + cap_inertia,
+            unit_ids: Vec::new(),
+        }
+    }
+
+    /// Returns the endpoints of the capsule's core segment in world space.
+    pub fn endpoints(&self) -> [Vec2; 2] {
+        let half = Vec2::new(self.half_length, 0.0).rotate(self.angle);
+        [self.position - half, self.position + half]
+    }
+
+    /// Returns the axis-aligned bounding box of the capsule as (min, max) corners.
+    pub fn aabb(&self) -> (Vec2, Vec2) {
+        let [a, b] = self.endpoints();
+        let r = Vec2::new(self.radius, self.radius);
+        (Vec2::new(a.x.min(b.x), a.y.min(b.y)) - r, Vec2::new(a.x.max(b.x), a.y.max(b.y)) + r)
+    }
+
+    /// Draws the capsule on the canvas as a thick line with rounded ends.
+    ///
+    /// # Arguments
+    ///
+    /// * `canvas` - The SDL2 canvas to draw on
+    #[cfg(feature = "sdl")]
+    pub fn draw<T: RenderTarget>(&self, canvas:&mut Canvas<T>) {
+        let [a, b] = self.endpoints();
+        let color = to_abgr(self.color);
+        let _ = canvas.thick_line(
+            a.x as i16, a.y as i16, b.x as i16, b.y as i16,
+            (self.radius * 2.0) as u8, color);
+        let _ = canvas.filled_circle(a.x as i16, a.y as i16, self.radius as i16, color);
+        let _ = canvas.filled_circle(b.x as i16, b.y as i16, self.radius as i16, color);
+    }
+
+    /// Updates the capsule's position and orientation based on its velocities.
+    ///
+    /// Applies velocity to position using: `position += velocity * dt`
+    /// and angular velocity to orientation using: `angle += angular_velocity * dt`
+    /// Also clamps velocity to prevent extreme speeds. This is an
+    /// [`integrate`](Self::integrate) step without any acceleration.
+    ///
+    /// # Arguments
+    ///
+    /// * `delta` - Optional time delta in seconds (default: 1.0)
+    /// * `integrator` - Optional numerical method (default: [`Integrator::ExplicitEuler`])
+    pub fn move_capsule(&mut self, delta: Option<f32>, integrator: Option<Integrator>) {
+        self.integrate(integrator.unwrap_or_default(), |_, _| Vec2::ZERO, delta.unwrap_or(1.0));
+    }
+
+    /// Applies a force to the capsule, modifying its velocity.
+    ///
+    /// With a constant force every integrator gives `velocity += force * dt`;
+    /// the position is left for [`move_capsule`](Self::move_capsule) to update.
+    ///
+    /// # Arguments
+    ///
+    /// * `force` - The force vector to apply [fx, fy]
+    /// * `delta` - Optional time delta in seconds (default: 1.0)
+    /// * `integrator` - Optional numerical method (default: [`Integrator::ExplicitEuler`])
+    pub fn apply_force(&mut self, force: Vec2, delta: Option<f32>, integrator: Option<Integrator>) {
+        let dt = delta.unwrap_or(1.0);
+        // Only the velocity half of the integrator's step
+        (_, self.velocity) = integrator.unwrap_or_default().step(self.position, self.velocity, dt, |_, _| force);
+    }
+
+    /// Advances the capsule by one time step with the given integrator (see
+    /// [`Integrator::advance`]).
+    ///
+    /// # Arguments
+    ///
+    /// * `integrator` - Numerical method to use
+    /// * `acceleration` - Acceleration as a function of position and velocity
+    /// * `dt` - Time delta in seconds
+    pub fn integrate(&mut self, integrator: Integrator, acceleration: impl Fn(Vec2, Vec2) -> Vec2, dt: f32) {
+        integrator.advance(&mut self.position, &mut self.velocity, &mut self.angle, self.angular_velocity, dt, acceleration);
+    }
+}
+
+impl RigidBody for Capsule {
+    fn position(&self) -> Vec2 {
+        self.position
+    }
+
+    fn velocity_at(&self, offset: Vec2) -> Vec2 {
+        self.velocity + offset.perp() * self.angular_velocity
+    }
+
+    fn angle(&self) -> f32 {
+        self.angle
+    }
+
+    fn angular_velocity(&self) -> f32 {
+        self.angular_velocity
+    }
+
+    fn inv_mass(&self) -> f32 {
+        if self.mass > 0.0 { 1.0 / self.mass } else { 0.0 }
+    }
+
+    fn inv_inertia(&self) -> f32 {
+        if self.inertia > 0.0 { 1.0 / self.inertia } else { 0.0 }
+    }
+
+    fn apply_impulse_at(&mut self, impulse: Vec2, offset: Vec2) {
+        self.velocity += impulse * self.inv_mass();
+        self.angular_velocity += offset.cross(impulse) * self.inv_inertia();
+    }
+
+    fn apply_angular_impulse(&mut self, impulse: f32) {
+        self.angular_velocity += impulse * self.inv_inertia();
+    }
+
+    fn translate(&mut self, delta: Vec2) {
+        if self.inv_mass() > 0.0 {
+            self.position += delta;
+        }
+    }
+
+    fn rotate(&mut self, delta: f32) {
+        if self.inv_inertia() > 0.0 {
+            self.angle += delta;
+        }
+    }
+
+    fn material(&self) -> Material {
+        Material {
+            friction: self.friction,
+            restitution: self.restitution,
+            friction_combine: self.friction_combine,
+            restitution_combine: self.restitution_combine,
+            id: self.material_id,
+        }
+    }
+
+    fn filter(&self) -> CollisionFilter {
+        self.filter
+    }
+
+    fn shape(&self) -> Shape {
+        let [a, b] = self.endpoints();
+        Shape::Segment { a, b, radius: self.radius }
+    }
+
+    fn aabb(&self) -> (Vec2, Vec2) {
+        Capsule::aabb(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f32::consts::PI;
+
+    #[test]
+    fn zero_length_capsule_is_a_disk() {
+        let capsule = Capsule::new(Vec2::new(50.0, 50.0), Vec2::new(50.0, 50.0), 10.0, None, None, None, None, Some(2.0));
+        let mass = PI * 100.0 * 2.0;
+        assert!((capsule.mass - mass).abs() < 1e-2);
+        assert!((capsule.inertia - mass * 100.0 / 2.0).abs() < 1.0);
+    }
+
+    #[test]
+    fn mass_and_inertia_scale_with_density() {
+        let light = Capsule::new(Vec2::new(0.0, 0.0), Vec2::new(40.0, 0.0), 5.0, None, None, None, None, Some(1.0));
+        let heavy = Capsule::new(Vec2::new(0.0, 0.0), Vec2::new(40.0, 0.0), 5.0, None, None, None, None, Some(3.0));
+        // A 40 x 10 rectangle plus a disk of radius 5
+        assert!((light.mass - (400.0 + PI * 25.0)).abs() < 1e-3);
+        assert!((heavy.mass - 3.0 * light.mass).abs() < 1e-2);
+        assert!((heavy.inertia - 3.0 * light.inertia).abs() < 1.0);
+        // A longer rod of the same mass per length is harder to spin
+        let long = Capsule::new(Vec2::new(0.0, 0.0), Vec2::new(80.0, 0.0), 5.0, None, None, None, None, Some(1.0));
+        assert!(long.inertia > 4.0 * light.inertia);
+    }
+
+    #[test]
+    fn endpoints_follow_the_segment() {
+        let capsule = Capsule::new(Vec2::new(10.0, 20.0), Vec2::new(10.0, 60.0), 4.0, None, None, None, None, None);
+        assert_eq!(capsule.position, Vec2::new(10.0, 40.0));
+        let [a, b] = capsule.endpoints();
+        assert!((a - Vec2::new(10.0, 20.0)).length() < 1e-4);
+        assert!((b - Vec2::new(10.0, 60.0)).length() < 1e-4);
+        let (min, max) = capsule.aabb();
+        assert!((min - Vec2::new(6.0, 16.0)).length() < 1e-4);
+        assert!((max - Vec2::new(14.0, 64.0)).length() < 1e-4);
+    }
+}
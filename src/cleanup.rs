@@ -0,0 +1,232 @@
+use crate::grid::Grid;
+use crate::items::PhysItem;
+use crate::library::Vec2;
+use crate::sensor::SensorShape;
+use std::collections::{HashMap, HashSet};
+
+/// A rule deciding when a dynamic body (ball, polygon or capsule) is despawned.
+///
+/// Rules only look at a body's center, so a body half way out of a kill zone or
+/// the grid stays until its center crosses. Walls and sensors are never despawned.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CleanupRule {
+    /// Remove bodies whose center left the grid
+    OutOfBounds,
+    /// Remove bodies whose center is inside a region (a rectangle or circle; a
+    /// segment has no inside)
+    KillZone(SensorShape),
+    /// Remove bodies that have existed for longer than this many seconds
+    MaxAge(f32),
+    /// Remove bodies that have moved slower than `speed` pixels per second for
+    /// `duration` seconds in a row
+    Resting {
+        /// Speed in pixels per second below which a body counts as resting
+        speed: f32,
+        /// Time in seconds a body must rest before it is removed
+        duration: f32,
+    },
+}
+
+/// The set of despawn rules a world applies at the start of every step.
+///
+/// A body is removed as soon as any rule matches it; with no rules nothing is ever
+/// removed. The default policy only removes bodies that leave the grid. The policy
+/// keeps track of how long each body has existed and rested, so one policy should
+/// only be applied to one grid.
+#[derive(Clone, Debug)]
+pub struct CleanupPolicy {
+    /// Rules deciding which bodies are removed
+    pub rules: Vec<CleanupRule>,
+    /// Seconds each body has existed since the policy first saw it
+    ages: HashMap<PhysItem, f32>,
+    /// Seconds each body has rested, keyed by the index of the resting rule and the body
+    rest_times: HashMap<(usize, PhysItem), f32>,
+}
+
+impl CleanupPolicy {
+    /// Creates a new cleanup policy.
+    ///
+    /// # Arguments
+    ///
+    /// * `rules` - Rules deciding which bodies are removed
+    ///
+    /// # Returns
+    ///
+    /// A new CleanupPolicy instance
+    pub fn new(rules: Vec<CleanupRule>) -> CleanupPolicy {
+        CleanupPolicy {
+            rules,
+            ages: HashMap::new(),
+            rest_times: HashMap::new(),
+        }
+    }
+
+    /// Creates a policy that never removes anything.
+    pub fn none() -> CleanupPolicy {
+        CleanupPolicy::new(Vec::new())
+    }
+
+    /// Returns how long a body has existed, as far as the policy knows.
+    ///
+    /// # Arguments
+    ///
+    /// * `item` - The body to look up
+    ///
+    /// # Returns
+    ///
+    /// The age in seconds, or `None` if the policy has not seen the body yet
+    pub fn age(&self, item: PhysItem) -> Option<f32> {
+        self.ages.get(&item).copied()
+    }
+
+    /// Ages every dynamic body by `dt` and removes those matching a rule.
+    ///
+    /// # Arguments
+    ///
+    /// * `grid` - The grid holding the bodies
+    /// * `dt` - Time since the last cleanup in seconds
+    ///
+    /// # Returns
+    ///
+    /// The removed bodies, balls first, then polygons, then capsules
+    pub fn apply(&mut self, grid: &mut Grid, dt: f32) -> Vec<PhysItem> {
+        let items: Vec<PhysItem> = grid.balls()
+            .map(|b| PhysItem::Ball(b.id))
+            .chain(grid.polygons().map(|p| PhysItem::Polygon(p.id)))
+            .chain(grid.capsules().map(|c| PhysItem::Capsule(c.id)))
+            .collect();
+        // Forget bodies that were removed by other means
+        let present: HashSet<PhysItem> = items.iter().copied().collect();
+        self.ages.retain(|item, _| present.contains(item));
+        self.rest_times.retain(|(_, item), _| present.contains(item));
+
+        let mut removed = Vec::new();
+        for item in items {
+            let Some(body) = grid.body(item) else { continue };
+            let (position, speed) = (body.position(), body.velocity_at(Vec2::ZERO).length());
+            let age = self.ages.entry(item).or_insert(0.0);
+            *age += dt;
+            let age = *age;
+
+            let mut remove = false;
+            for (i, rule) in self.rules.iter().enumerate() {
+                remove |= match *rule {
+                    CleanupRule::OutOfBounds => !grid.contains_point(position),
+                    CleanupRule::KillZone(shape) => shape.contains(position),
+                    CleanupRule::MaxAge(max_age) => age > max_age,
+                    CleanupRule::Resting { speed: threshold, duration } => {
+                        // Every resting rule keeps its own timer, since thresholds differ
+                        if speed < threshold {
+                            let time = self.rest_times.entry((i, item)).or_insert(0.0);
+                            *time += dt;
+                            *time >= duration
+                        } else {
+                            self.rest_times.remove(&(i, item));
+                            false
+                        }
+                    },
+                };
+            }
+
+            if remove {
+                match item {
+                    PhysItem::Ball(handle) => { grid.remove_ball(handle); },
+                    PhysItem::Polygon(handle) => { grid.remove_polygon(handle); },
+                    PhysItem::Capsule(handle) => { grid.remove_capsule(handle); },
+                    _ => continue,
+                }
+                self.ages.remove(&item);
+                self.rest_times.retain(|(_, other), _| *other != item);
+                removed.push(item);
+            }
+        }
+        removed
+    }
+}
+
+impl Default for CleanupPolicy {
+    fn default() -> CleanupPolicy {
+        CleanupPolicy::new(vec![CleanupRule::OutOfBounds])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::items::{Ball, Wall};
+    use crate::world::World;
+
+    const DT: f32 = 1.0 / 60.0;
+
+    fn world_with(rules: Vec<CleanupRule>) -> World {
+        let mut world = World::new(Grid::new(50, 50, 800, 800));
+        world.cleanup = CleanupPolicy::new(rules);
+        world
+    }
+
+    #[test]
+    fn out_of_bounds_bodies_are_removed_and_reported() {
+        let mut world = world_with(vec![CleanupRule::OutOfBounds]);
+        world.gravity = Vec2::ZERO;
+        let a = world.add_ball(Ball::new(Vec2::new(20.0, 400.0), Some(Vec2::new(-600.0, 0.0)), None, None, None, None, None));
+        let b = world.add_ball(Ball::new(Vec2::new(400.0, 400.0), None, None, None, None, None, None));
+        for _ in 0..10 {
+            world.step(DT);
+        }
+        assert_eq!(world.drain_removed().collect::<Vec<_>>(), [PhysItem::Ball(a)]);
+        assert!(world.ball(a).is_none() && world.ball(b).is_some());
+        assert_eq!(world.drain_removed().count(), 0);
+    }
+
+    #[test]
+    fn kill_zone_removes_bodies_entering_it() {
+        let zone = SensorShape::Rectangle { min: Vec2::new(0.0, 500.0), max: Vec2::new(400.0, 600.0) };
+        let mut world = world_with(vec![CleanupRule::KillZone(zone)]);
+        // One ball falls into the zone, the other past it
+        let a = world.add_ball(Ball::new(Vec2::new(200.0, 400.0), None, None, None, None, None, None));
+        let b = world.add_ball(Ball::new(Vec2::new(600.0, 400.0), None, None, None, None, None, None));
+        for _ in 0..60 {
+            world.step(DT);
+        }
+        assert_eq!(world.drain_removed().collect::<Vec<_>>(), [PhysItem::Ball(a)]);
+        assert!(world.ball(b).is_some());
+    }
+
+    #[test]
+    fn max_age_removes_old_bodies() {
+        let mut world = world_with(vec![CleanupRule::MaxAge(0.5)]);
+        world.gravity = Vec2::ZERO;
+        let a = world.add_ball(Ball::new(Vec2::new(400.0, 400.0), None, None, None, None, None, None));
+        for _ in 0..25 {
+            world.step(DT);
+        }
+        let age = world.cleanup.age(PhysItem::Ball(a)).unwrap();
+        assert!((age - 25.0 * DT).abs() < 1e-4, "age = {age}");
+        for _ in 0..10 {
+            world.step(DT);
+        }
+        assert!(world.ball(a).is_none());
+        assert_eq!(world.cleanup.age(PhysItem::Ball(a)), None);
+        assert_eq!(world.drain_removed().collect::<Vec<_>>(), [PhysItem::Ball(a)]);
+    }
+
+    #[test]
+    fn resting_bodies_are_removed_after_the_duration() {
+        let mut world = world_with(vec![CleanupRule::Resting { speed: 5.0, duration: 1.0 }]);
+        world.add_wall(Wall::new(Vec2::new(0.0, 700.0), Vec2::new(800.0, 700.0), None, None, None, None));
+        // One ball sits on the floor, the other keeps falling through open space
+        let a = world.add_ball(Ball::new(Vec2::new(200.0, 685.0), None, None, None, None, None, None));
+        let b = world.add_ball(Ball::new(Vec2::new(600.0, 0.0), None, None, None, None, None, None));
+        for _ in 0..50 {
+            world.step(DT);
+        }
+        assert!(world.ball(a).is_some(), "removed before resting for a second");
+        for _ in 0..20 {
+            world.step(DT);
+        }
+        assert_eq!(world.drain_removed().collect::<Vec<_>>(), [PhysItem::Ball(a)]);
+        assert!(world.ball(b).is_some());
+        // Walls are never despawned
+        assert_eq!(world.grid().wall_count(), 1);
+    }
+}
@@ -0,0 +1,685 @@
+use crate::library::Vec2;
+
+/// A single point of contact between two shapes.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ContactPoint {
+    /// Contact point in world space [x, y]
+    pub point: Vec2,
+    /// Penetration depth along the manifold normal (positive = overlapping)
+    pub depth: f32,
+}
+
+/// The result of a narrowphase test between two shapes `a` and `b`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Manifold {
+    /// Unit collision normal pointing from shape `a` towards shape `b`
+    pub normal: Vec2,
+    /// One or two contact points
+    pub points: Vec<ContactPoint>,
+}
+
+impl Manifold {
+    /// Returns the same contact seen from the other shape (normal reversed).
+    pub fn flipped(mut self) -> Manifold {
+        self.normal = -self.normal;
+        self
+    }
+
+    /// Returns the deepest penetration among the contact points.
+    pub fn max_depth(&self) -> f32 {
+        self.points.iter().fold(0.0, |depth, p| depth.max(p.depth))
+    }
+}
+
+/// Computes the outward unit normal of every edge of a convex vertex loop.
+///
+/// Vertices must have positive winding (`cross(v[i], v[i + 1])` summing to a positive
+/// area). A two-vertex loop describes a segment and yields its two opposite normals.
+///
+/// # Arguments
+///
+/// * `vertices` - Vertices of the convex shape in world space
+pub fn edge_normals(vertices: &[Vec2]) -> Vec<Vec2> {
+    let count = vertices.len();
+    (0..count)
+        .map(|i| {
+            let edge = vertices[(i + 1) % count] - vertices[i];
+            Vec2::new(edge.y, -edge.x).normalize()
+        })
+        .collect()
+}
+
+/// Finds the edge of `a` along whose normal `b` is furthest away (separating axis test).
+///
+/// # Returns
+///
+/// The largest separation found and the index of the edge of `a` that produced it
+fn max_separation(a: &[Vec2], a_normals: &[Vec2], b: &[Vec2]) -> (f32, usize) {
+    let mut best = (f32::NEG_INFINITY, 0);
+    for (i, normal) in a_normals.iter().enumerate() {
+        let separation = b
+            .iter()
+            .map(|v| normal.dot(*v - a[i]))
+            .fold(f32::INFINITY, f32::min);
+        if separation > best.0 {
+            best = (separation, i);
+        }
+    }
+    best
+}
+
+/// Clips a segment to the half-plane `dot(normal, x) <= offset`.
+///
+/// # Returns
+///
+/// The part of the segment inside the half-plane (zero, one or two points)
+fn clip_segment(points: &[Vec2], normal: Vec2, offset: f32) -> Vec<Vec2> {
+    let mut clipped = Vec::new();
+    if points.len() < 2 {
+        return clipped;
+    }
+    let dist0 = normal.dot(points[0]) - offset;
+    let dist1 = normal.dot(points[1]) - offset;
+    if dist0 <= 0.0 {
+        clipped.push(points[0]);
+    }
+    if dist1 <= 0.0 {
+        clipped.push(points[1]);
+    }
+    // The segment crosses the plane, keep the intersection point
+    if dist0 * dist1 < 0.0 {
+        let t = dist0 / (dist0 - dist1);
+        clipped.push(points[0].lerp(points[1], t));
+    }
+    clipped
+}
+
+/// Tests two convex shapes for overlap using the separating axis theorem.
+///
+/// Each shape is a convex vertex loop inflated by a radius, so a two-vertex loop with a
+/// radius describes a thick segment such as a [`Wall`](crate::Wall). The contact points
+/// are found by clipping the incident edge against the reference edge, giving a
+/// two-point manifold for resting faces.
+///
+/// # Arguments
+///
+/// * `a` - World-space vertices of the first shape (at least two)
+/// * `a_radius` - Radius the first shape is inflated by
+/// * `b` - World-space vertices of the second shape (at least two)
+/// * `b_radius` - Radius the second shape is inflated by
+///
+/// # Returns
+///
+/// A manifold with the normal pointing from `a` to `b`, or `None` if they are apart
+pub fn collide_polygons(a: &[Vec2], a_radius: f32, b: &[Vec2], b_radius: f32) -> Option<Manifold> {
+    let total_radius = a_radius + b_radius;
+    let a_normals = edge_normals(a);
+    let b_normals = edge_normals(b);
+
+    let (separation_a, edge_a) = max_separation(a, &a_normals, b);
+    if separation_a > total_radius {
+        return None;
+    }
+    let (separation_b, edge_b) = max_separation(b, &b_normals, a);
+    if separation_b > total_radius {
+        return None;
+    }
+
+    // Use the face of least penetration as the reference face, preferring `a`
+    // so the choice does not flicker between nearly equal separations
+    let flip = separation_b > separation_a + 1.0e-3;
+    let (reference, ref_normals, ref_edge, ref_radius, incident, inc_normals, inc_radius) = if flip {
+        (b, &b_normals, edge_b, b_radius, a, &a_normals, a_radius)
+    } else {
+        (a, &a_normals, edge_a, a_radius, b, &b_normals, b_radius)
+    };
+    let normal = ref_normals[ref_edge];
+
+    // The incident edge is the one most anti-parallel to the reference normal
+    let mut inc_edge = 0;
+    let mut min_dot = f32::INFINITY;
+    for (i, inc_normal) in inc_normals.iter().enumerate() {
+        let dot = normal.dot(*inc_normal);
+        if dot < min_dot {
+            min_dot = dot;
+            inc_edge = i;
+        }
+    }
+    let incident_points = [incident[inc_edge], incident[(inc_edge + 1) % incident.len()]];
+
+    // Clip the incident edge to the side planes of the (inflated) reference edge
+    let v1 = reference[ref_edge];
+    let v2 = reference[(ref_edge + 1) % reference.len()];
+    let tangent = (v2 - v1).normalize();
+    let clipped = clip_segment(&incident_points, -tangent, -tangent.dot(v1) + ref_radius);
+    let clipped = clip_segment(&clipped, tangent, tangent.dot(v2) + ref_radius);
+    if clipped.is_empty() {
+        return None;
+    }
+
+    // Keep the clipped points that are within reach of the reference face
+    let front = normal.dot(v1);
+    let points: Vec<ContactPoint> = clipped
+        .iter()
+        .filter_map(|point| {
+            let separation = normal.dot(*point) - front;
+            if separation > total_radius {
+                return None;
+            }
+            // Place the contact halfway between the two inflated surfaces
+            let point = *point + normal * (0.5 * (ref_radius - separation - inc_radius));
+            Some(ContactPoint { point, depth: total_radius - separation })
+        })
+        .collect();
+    if points.is_empty() {
+        return None;
+    }
+
+    let manifold = Manifold { normal, points };
+    Some(if flip { manifold.flipped() } else { manifold })
+}
+
+/// Tests a circle against a convex shape.
+///
+/// # Arguments
+///
+/// * `center` - Center of the circle in world space
+/// * `radius` - Radius of the circle
+/// * `vertices` - World-space vertices of the convex shape (at least two)
+/// * `poly_radius` - Radius the convex shape is inflated by
+///
+/// # Returns
+///
+/// A manifold with the normal pointing from the circle to the shape, or `None`
+pub fn collide_circle_polygon(center: Vec2, radius: f32, vertices: &[Vec2], poly_radius: f32) -> Option<Manifold> {
+    let total_radius = radius + poly_radius;
+    let normals = edge_normals(vertices);
+
+    // Find the edge the center is furthest in front of
+    let mut separation = f32::NEG_INFINITY;
+    let mut edge = 0;
+    for (i, normal) in normals.iter().enumerate() {
+        let s = normal.dot(center - vertices[i]);
+        if s > total_radius {
+            return None;
+        }
+        if s > separation {
+            separation = s;
+            edge = i;
+        }
+    }
+
+    let v1 = vertices[edge];
+    let v2 = vertices[(edge + 1) % vertices.len()];
+    // Normal from the shape to the circle and distance between the two cores
+    let (normal, dist, surface) = if separation <= 0.0 {
+        // Center is inside the shape: push out through the nearest face
+        (normals[edge], separation, center - normals[edge] * separation)
+    } else if (center - v1).dot(v2 - v1) <= 0.0 {
+        // Closest to the first vertex
+        ((center - v1).normalize(), (center - v1).length(), v1)
+    } else if (center - v2).dot(v1 - v2) <= 0.0 {
+        // Closest to the second vertex
+        ((center - v2).normalize(), (center - v2).length(), v2)
+    } else {
+        // Closest to the face
+        (normals[edge], separation, center - normals[edge] * separation)
+    };
+    if dist > total_radius {
+        return None;
+    }
+
+    let point = surface + normal * (0.5 * (poly_radius + dist - radius));
+    Some(Manifold {
+        normal: -normal,
+        points: vec![ContactPoint { point, depth: total_radius - dist }],
+    })
+}
+
+/// Tests two circles against each other.
+///
+/// # Arguments
+///
+/// * `a_center` - Center of the first circle in world space
+/// * `a_radius` - Radius of the first circle
+/// * `b_center` - Center of the second circle in world space
+/// * `b_radius` - Radius of the second circle
+///
+/// # Returns
+///
+/// A manifold with the normal pointing from `a` to `b`, or `None` if they are apart
+pub fn collide_circles(a_center: Vec2, a_radius: f32, b_center: Vec2, b_radius: f32) -> Option<Manifold> {
+    let vec = b_center - a_center;
+    let dist = vec.length();
+    let total_radius = a_radius + b_radius;
+    if dist > total_radius {
+        return None;
+    }
+    // Concentric circles have no preferred direction, so push them apart vertically
+    let normal = if dist > 0.0 { vec / dist } else { Vec2::new(0.0, 1.0) };
+    // Place the contact halfway between the two surfaces
+    let point = a_center + normal * (0.5 * (a_radius + dist - b_radius));
+    Some(Manifold {
+        normal,
+        points: vec![ContactPoint { point, depth: total_radius - dist }],
+    })
+}
+
+/// Tests a circle against a thick segment (a capsule).
+///
+/// Projects the circle's center onto the segment to find the closest point. Past
+/// either end, the collision is with the rounded endpoint and uses the radial normal
+/// from the endpoint to the center; otherwise it uses the segment's normal.
+///
+/// # Arguments
+///
+/// * `center` - Center of the circle in world space
+/// * `radius` - Radius of the circle
+/// * `a` - Starting point of the segment
+/// * `b` - Ending point of the segment
+/// * `seg_radius` - Radius the segment is inflated by (half its thickness)
+///
+/// # Returns
+///
+/// A manifold with the normal pointing from the circle to the segment, or `None`
+pub fn collide_circle_segment(center: Vec2, radius: f32, a: Vec2, b: Vec2, seg_radius: f32) -> Option<Manifold> {
+    let direction = (b - a).normalize();
+    let length = (b - a).length();
+    // Find vector from segment start to the center
+    let mut vec = center - a;
+    // Project the center onto the segment direction to find the closest point
+    let position = vec.dot(direction);
+
+    // If the center is past the end of the segment, check collision with the endpoint
+    if position > length {
+        vec = center - b;
+    }
+
+    let normal: Vec2; // Normal from the segment towards the circle
+    let dist: f32;    // Distance from the segment core to the center
+    if position < 0.0 || position > length {
+        // Endpoint collision: use radial normal from endpoint to circle center
+        normal = vec.normalize();
+        dist = vec.length();
+    } else {
+        // Segment collision: use the segment's normal, flipped to face the circle
+        let side = direction.perp();
+        let signed = vec.dot(side);
+        normal = if signed >= 0.0 { side } else { -side };
+        dist = signed.abs();
+    }
+
+    // Early exit if the circle is too far from the segment
+    let total_radius = radius + seg_radius;
+    if dist > total_radius {
+        return None;
+    }
+
+    // Place the contact halfway between the two surfaces
+    let surface = center - normal * dist;
+    let point = surface + normal * (0.5 * (seg_radius + dist - radius));
+    Some(Manifold {
+        normal: -normal,
+        points: vec![ContactPoint { point, depth: total_radius - dist }],
+    })
+}
+
+/// Tests a circle against a wall.
+///
+/// Along its length the wall is a segment inflated by `wall_radius`, as in
+/// [`collide_circle_segment`]. Past either end only the endpoint itself counts, so a
+/// circle reaches the end of a wall at its own radius, as balls always have.
+///
+/// # Arguments
+///
+/// * `center` - Center of the circle in world space
+/// * `radius` - Radius of the circle
+/// * `a` - Starting point of the wall
+/// * `b` - Ending point of the wall
+/// * `wall_radius` - Half the wall's width
+///
+/// # Returns
+///
+/// A manifold with the normal pointing from the circle to the wall, or `None`
+pub fn collide_circle_wall(center: Vec2, radius: f32, a: Vec2, b: Vec2, wall_radius: f32) -> Option<Manifold> {
+    let position = (center - a).dot((b - a).normalize());
+    if position < 0.0 {
+        collide_circles(center, radius, a, 0.0)
+    } else if position > (b - a).length() {
+        collide_circles(center, radius, b, 0.0)
+    } else {
+        collide_circle_segment(center, radius, a, b, wall_radius)
+    }
+}
+
+/// Tests two thick segments (capsules) against each other.
+///
+/// Each endpoint of one segment is tested against the other segment with
+/// [`collide_circle_segment`], so two capsules lying side by side get a two-point
+/// manifold. Crossing segments, where no endpoint is close enough, fall back to
+/// [`collide_polygons`].
+///
+/// # Arguments
+///
+/// * `a` - Endpoints of the first segment
+/// * `a_radius` - Radius the first segment is inflated by
+/// * `b` - Endpoints of the second segment
+/// * `b_radius` - Radius the second segment is inflated by
+///
+/// # Returns
+///
+/// A manifold with the normal pointing from `a` to `b`, or `None` if they are apart
+pub fn collide_segments(a: [Vec2; 2], a_radius: f32, b: [Vec2; 2], b_radius: f32) -> Option<Manifold> {
+    let mut candidates: Vec<Manifold> = Vec::new();
+    for point in a {
+        if let Some(manifold) = collide_circle_segment(point, a_radius, b[0], b[1], b_radius) {
+            candidates.push(manifold);
+        }
+    }
+    for point in b {
+        if let Some(manifold) = collide_circle_segment(point, b_radius, a[0], a[1], a_radius) {
+            candidates.push(manifold.flipped());
+        }
+    }
+
+    // Use the deepest contact's normal and keep the others that agree with it
+    let Some(deepest) = candidates.iter().max_by(|m, n| m.max_depth().total_cmp(&n.max_depth())) else {
+        return collide_polygons(&a, a_radius, &b, b_radius).filter(|_| segments_cross(a, b));
+    };
+    let normal = deepest.normal;
+    let points = candidates
+        .iter()
+        .filter(|m| m.normal.dot(normal) > 0.95)
+        .flat_map(|m| m.points.iter().copied())
+        .take(2)
+        .collect();
+    Some(Manifold { normal, points })
+}
+
+/// Returns true if the cores of two segments intersect.
+fn segments_cross(a: [Vec2; 2], b: [Vec2; 2]) -> bool {
+    let side = |p: Vec2, q: [Vec2; 2]| (q[1] - q[0]).cross(p - q[0]);
+    side(a[0], b) * side(a[1], b) <= 0.0 && side(b[0], a) * side(b[1], a) <= 0.0
+}
+
+/// The collision geometry of a body in world space.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Shape {
+    /// A circle (balls)
+    Circle {
+        /// Center of the circle [x, y]
+        center: Vec2,
+        /// Radius of the circle
+        radius: f32,
+    },
+    /// A segment inflated by a radius (walls and capsules)
+    Segment {
+        /// Starting point of the segment [x, y]
+        a: Vec2,
+        /// Ending point of the segment [x, y]
+        b: Vec2,
+        /// Radius the segment is inflated by (half its thickness)
+        radius: f32,
+    },
+    /// A convex polygon (polygons)
+    Polygon {
+        /// Vertices with positive winding [x, y]
+        vertices: Vec<Vec2>,
+    },
+}
+
+/// Tests two shapes against each other with the narrowphase test for their kinds.
+///
+/// # Arguments
+///
+/// * `a` - The first shape
+/// * `b` - The second shape
+///
+/// # Returns
+///
+/// A manifold with the normal pointing from `a` to `b`, or `None` if they are apart
+pub fn collide(a: &Shape, b: &Shape) -> Option<Manifold> {
+    match (a, b) {
+        (Shape::Circle { center: a, radius: a_radius }, Shape::Circle { center: b, radius: b_radius }) => {
+            collide_circles(*a, *a_radius, *b, *b_radius)
+        },
+        (Shape::Circle { center, radius }, Shape::Segment { a, b, radius: seg_radius }) => {
+            collide_circle_segment(*center, *radius, *a, *b, *seg_radius)
+        },
+        (Shape::Circle { center, radius }, Shape::Polygon { vertices }) => {
+            collide_circle_polygon(*center, *radius, vertices, 0.0)
+        },
+        (Shape::Segment { a, b, radius: a_radius }, Shape::Segment { a: c, b: d, radius: b_radius }) => {
+            collide_segments([*a, *b], *a_radius, [*c, *d], *b_radius)
+        },
+        (Shape::Segment { a, b, radius }, Shape::Polygon { vertices }) => {
+            collide_polygons(&[*a, *b], *radius, vertices, 0.0)
+        },
+        (Shape::Polygon { vertices }, Shape::Segment { a, b, radius }) => {
+            collide_polygons(vertices, 0.0, &[*a, *b], *radius)
+        },
+        (Shape::Polygon { vertices: a }, Shape::Polygon { vertices: b }) => collide_polygons(a, 0.0, b, 0.0),
+        // Circles are always tested from their own side
+        (_, Shape::Circle { .. }) => collide(b, a).map(Manifold::flipped),
+    }
+}
+
+/// Finds when a moving circle first touches a stationary circle (time of impact).
+///
+/// Solves `|start + motion * t - center| = radius + other_radius` for the smallest `t`.
+///
+/// # Arguments
+///
+/// * `start` - Center of the moving circle at the start of the sweep
+/// * `motion` - Displacement of the moving circle over the sweep
+/// * `radius` - Radius of the moving circle
+/// * `center` - Center of the stationary circle
+/// * `other_radius` - Radius of the stationary circle
+///
+/// # Returns
+///
+/// The fraction of `motion` in [0, 1] at which the circles touch, or `None` if they
+/// do not touch during the sweep or already overlap at its start
+pub fn sweep_circle_circle(start: Vec2, motion: Vec2, radius: f32, center: Vec2, other_radius: f32) -> Option<f32> {
+    let total_radius = radius + other_radius;
+    let offset = start - center;
+    let a = motion.length_squared();
+    let b = 2.0 * offset.dot(motion);
+    let c = offset.length_squared() - total_radius * total_radius;
+    // Overlapping contacts are left to the discrete collision pass
+    if c < 0.0 || a == 0.0 {
+        return None;
+    }
+    let discriminant = b * b - 4.0 * a * c;
+    if discriminant < 0.0 {
+        return None;
+    }
+    let t = (-b - discriminant.sqrt()) / (2.0 * a);
+    if (0.0..=1.0).contains(&t) { Some(t) } else { None }
+}
+
+/// Finds when a moving circle first touches a stationary thick segment (time of impact).
+///
+/// The sweep is tested against both sides of the segment, offset by the combined
+/// radius, and against its rounded endpoints.
+///
+/// # Arguments
+///
+/// * `start` - Center of the moving circle at the start of the sweep
+/// * `motion` - Displacement of the moving circle over the sweep
+/// * `radius` - Radius of the moving circle
+/// * `a` - Starting point of the segment
+/// * `b` - Ending point of the segment
+/// * `seg_radius` - Radius the segment is inflated by
+///
+/// # Returns
+///
+/// The fraction of `motion` in [0, 1] at which the circle touches the segment, or
+/// `None` if it does not touch during the sweep or already overlaps at its start
+pub fn sweep_circle_segment(start: Vec2, motion: Vec2, radius: f32, a: Vec2, b: Vec2, seg_radius: f32) -> Option<f32> {
+    if collide_circle_segment(start, radius, a, b, seg_radius).is_some() {
+        return None;
+    }
+    sweep_circle_sides(start, motion, radius, a, b, seg_radius, seg_radius)
+}
+
+/// Finds when a moving circle first touches a wall (time of impact).
+///
+/// Like [`sweep_circle_segment`], but the ends of the wall count as points, matching
+/// [`collide_circle_wall`].
+///
+/// # Arguments
+///
+/// * `start` - Center of the moving circle at the start of the sweep
+/// * `motion` - Displacement of the moving circle over the sweep
+/// * `radius` - Radius of the moving circle
+/// * `a` - Starting point of the wall
+/// * `b` - Ending point of the wall
+/// * `wall_radius` - Half the wall's width
+///
+/// # Returns
+///
+/// The fraction of `motion` in [0, 1] at which the circle touches the wall, or
+/// `None` if it does not touch during the sweep or already overlaps at its start
+pub fn sweep_circle_wall(start: Vec2, motion: Vec2, radius: f32, a: Vec2, b: Vec2, wall_radius: f32) -> Option<f32> {
+    if collide_circle_wall(start, radius, a, b, wall_radius).is_some() {
+        return None;
+    }
+    sweep_circle_sides(start, motion, radius, a, b, wall_radius, 0.0)
+}
+
+/// Sweeps a circle against the sides of a thick segment, offset by `side_radius`,
+/// and against its endpoints, inflated by `end_radius`.
+fn sweep_circle_sides(
+    start: Vec2,
+    motion: Vec2,
+    radius: f32,
+    a: Vec2,
+    b: Vec2,
+    side_radius: f32,
+    end_radius: f32,
+) -> Option<f32> {
+    let total_radius = radius + side_radius;
+    let direction = (b - a).normalize();
+    let length = (b - a).length();
+    let mut toi: Option<f32> = None;
+
+    // Sides: the plane parallel to the segment on the circle's side
+    let side = direction.perp();
+    let dist = (start - a).dot(side);
+    let approach = motion.dot(side) * dist.signum();
+    if approach < 0.0 {
+        let t = (dist.abs() - total_radius) / -approach;
+        let along = (start + motion * t - a).dot(direction);
+        if (0.0..=1.0).contains(&t) && (0.0..=length).contains(&along) {
+            toi = Some(t);
+        }
+    }
+
+    // Endpoints
+    for end in [a, b] {
+        if let Some(t) = sweep_circle_circle(start, motion, radius, end, end_radius) {
+            toi = Some(toi.map_or(t, |best| best.min(t)));
+        }
+    }
+    toi
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Polygon;
+
+    fn square_at(center: Vec2) -> Vec<Vec2> {
+        Polygon::rectangle(20.0, 20.0).into_iter().map(|v| v + center).collect()
+    }
+
+    #[test]
+    fn box_on_a_thick_segment_has_two_contacts() {
+        // The floor's surface is 3 px above the bottom of the box
+        let floor = [Vec2::new(-50.0, 12.0), Vec2::new(50.0, 12.0)];
+        let manifold = collide_polygons(&square_at(Vec2::ZERO), 0.0, &floor, 5.0).unwrap();
+        assert!((manifold.normal - Vec2::new(0.0, 1.0)).length() < 1e-5, "normal = {:?}", manifold.normal);
+        assert_eq!(manifold.points.len(), 2);
+        for point in &manifold.points {
+            assert!((point.depth - 3.0).abs() < 1e-4, "depth = {}", point.depth);
+        }
+    }
+
+    #[test]
+    fn separated_boxes_do_not_collide() {
+        assert!(collide_polygons(&square_at(Vec2::ZERO), 0.0, &square_at(Vec2::new(25.0, 0.0)), 0.0).is_none());
+        let manifold = collide_polygons(&square_at(Vec2::ZERO), 0.0, &square_at(Vec2::new(18.0, 0.0)), 0.0).unwrap();
+        assert!((manifold.normal - Vec2::new(1.0, 0.0)).length() < 1e-5);
+        assert!((manifold.max_depth() - 2.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn circle_against_a_box_face_and_corner() {
+        let square = square_at(Vec2::ZERO);
+        // Overlapping the top face by 2 px
+        let manifold = collide_circle_polygon(Vec2::new(0.0, -15.0), 7.0, &square, 0.0).unwrap();
+        assert!((manifold.normal - Vec2::new(0.0, 1.0)).length() < 1e-5);
+        assert!((manifold.max_depth() - 2.0).abs() < 1e-4);
+        // Diagonally off the corner, just out of reach
+        assert!(collide_circle_polygon(Vec2::new(15.0, 15.0), 7.0, &square, 0.0).is_none());
+    }
+
+    #[test]
+    fn capsule_lying_on_a_wall_has_two_contacts() {
+        // The capsule's underside dips 2 px into the wall
+        let capsule = [Vec2::new(-20.0, 0.0), Vec2::new(20.0, 0.0)];
+        let wall = [Vec2::new(-100.0, 8.0), Vec2::new(100.0, 8.0)];
+        let manifold = collide_segments(capsule, 5.0, wall, 5.0).unwrap();
+        assert!((manifold.normal - Vec2::new(0.0, 1.0)).length() < 1e-5, "normal = {:?}", manifold.normal);
+        assert_eq!(manifold.points.len(), 2);
+        for point in &manifold.points {
+            assert!((point.depth - 2.0).abs() < 1e-4, "depth = {}", point.depth);
+        }
+        let apart = [Vec2::new(-100.0, 20.0), Vec2::new(100.0, 20.0)];
+        assert!(collide_segments(capsule, 5.0, apart, 5.0).is_none());
+    }
+
+    #[test]
+    fn crossing_segments_collide() {
+        let a = [Vec2::new(-20.0, 0.0), Vec2::new(20.0, 0.0)];
+        let b = [Vec2::new(0.0, -20.0), Vec2::new(0.0, 20.0)];
+        assert!(collide_segments(a, 2.0, b, 2.0).is_some());
+    }
+
+    #[test]
+    fn sweeps_find_the_time_of_impact() {
+        // A circle of radius 5 moving 100 px right meets a circle of radius 5 at x = 50
+        let t = sweep_circle_circle(Vec2::ZERO, Vec2::new(100.0, 0.0), 5.0, Vec2::new(50.0, 0.0), 5.0).unwrap();
+        assert!((t - 0.4).abs() < 1e-5, "t = {t}");
+        // The same motion meets a thick vertical segment at x = 50 one radius earlier
+        let (a, b) = (Vec2::new(50.0, -20.0), Vec2::new(50.0, 20.0));
+        let t = sweep_circle_segment(Vec2::ZERO, Vec2::new(100.0, 0.0), 5.0, a, b, 5.0).unwrap();
+        assert!((t - 0.4).abs() < 1e-5, "t = {t}");
+        assert!(sweep_circle_segment(Vec2::ZERO, Vec2::new(0.0, 100.0), 5.0, a, b, 5.0).is_none());
+    }
+
+    #[test]
+    fn balls_reach_wall_ends_at_their_own_radius() {
+        // A 10 px wide wall and a ball of radius 10
+        let (a, b) = (Vec2::new(0.0, 0.0), Vec2::new(100.0, 0.0));
+        // Along the wall the ball touches at its radius plus half the width
+        let side = collide_circle_wall(Vec2::new(50.0, -13.0), 10.0, a, b, 5.0).unwrap();
+        assert!((side.normal - Vec2::new(0.0, 1.0)).length() < 1e-5, "normal = {:?}", side.normal);
+        assert!((side.max_depth() - 2.0).abs() < 1e-4, "depth = {}", side.max_depth());
+        // Past the ends only the endpoint counts, as it always has for balls
+        let end = collide_circle_wall(Vec2::new(-8.0, 0.0), 10.0, a, b, 5.0).unwrap();
+        assert!((end.normal - Vec2::new(1.0, 0.0)).length() < 1e-5, "normal = {:?}", end.normal);
+        assert!((end.max_depth() - 2.0).abs() < 1e-4, "depth = {}", end.max_depth());
+        assert!(collide_circle_wall(Vec2::new(-12.0, 0.0), 10.0, a, b, 5.0).is_none());
+        assert!(collide_circle_wall(Vec2::new(109.0, 9.0), 10.0, a, b, 5.0).is_none());
+        // A capsule of the same thickness has rounded ends instead
+        assert!(collide_circle_segment(Vec2::new(-12.0, 0.0), 10.0, a, b, 5.0).is_some());
+
+        // Sweeping past the end of the wall agrees with the contact test
+        let motion = Vec2::new(0.0, 100.0);
+        assert!(sweep_circle_wall(Vec2::new(-12.0, -50.0), motion, 10.0, a, b, 5.0).is_none());
+        let t = sweep_circle_wall(Vec2::new(-6.0, -50.0), motion, 10.0, a, b, 5.0).unwrap();
+        assert!((t - 0.42).abs() < 1e-4, "t = {t}");
+    }
+}
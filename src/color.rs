@@ -0,0 +1,70 @@
+/// An RGBA color used to render physics objects.
+///
+/// The physics core stores colors with its own type so it does not depend on SDL2.
+/// With the `sdl` feature enabled, colors convert into `sdl2::pixels::Color`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Color {
+    /// Red channel
+    pub r: u8,
+    /// Green channel
+    pub g: u8,
+    /// Blue channel
+    pub b: u8,
+    /// Alpha channel (255 = opaque)
+    pub a: u8,
+}
+
+impl Color {
+    /// Opaque white
+    pub const WHITE: Color = Color::rgba(255, 255, 255, 255);
+    /// Opaque black
+    pub const BLACK: Color = Color::rgba(0, 0, 0, 255);
+    /// Opaque mid gray
+    pub const GRAY: Color = Color::rgba(128, 128, 128, 255);
+    /// Opaque red
+    pub const RED: Color = Color::rgba(255, 0, 0, 255);
+    /// Opaque green
+    pub const GREEN: Color = Color::rgba(0, 255, 0, 255);
+    /// Opaque blue
+    pub const BLUE: Color = Color::rgba(0, 0, 255, 255);
+    /// Opaque magenta
+    pub const MAGENTA: Color = Color::rgba(255, 0, 255, 255);
+    /// Opaque yellow
+    pub const YELLOW: Color = Color::rgba(255, 255, 0, 255);
+    /// Opaque cyan
+    pub const CYAN: Color = Color::rgba(0, 255, 255, 255);
+
+    /// Creates an opaque color from red, green and blue channels.
+    pub const fn rgb(r: u8, g: u8, b: u8) -> Color {
+        Color { r, g, b, a: 255 }
+    }
+
+    /// Creates a color from red, green, blue and alpha channels.
+    pub const fn rgba(r: u8, g: u8, b: u8, a: u8) -> Color {
+        Color { r, g, b, a }
+    }
+}
+
+#[cfg(feature = "sdl")]
+impl From<Color> for sdl2::pixels::Color {
+    fn from(color: Color) -> sdl2::pixels::Color {
+        sdl2::pixels::Color::RGBA(color.r, color.g, color.b, color.a)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rgb_is_opaque() {
+        assert_eq!(Color::rgb(255, 0, 0), Color::RED);
+        assert_eq!(Color::rgba(1, 2, 3, 4), Color { r: 1, g: 2, b: 3, a: 4 });
+    }
+
+    #[cfg(feature = "sdl")]
+    #[test]
+    fn converts_to_sdl() {
+        assert_eq!(sdl2::pixels::Color::from(Color::rgba(1, 2, 3, 4)), sdl2::pixels::Color::RGBA(1, 2, 3, 4));
+    }
+}
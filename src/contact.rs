@@ -0,0 +1,266 @@
+use crate::items::PhysItem;
+use crate::library::Vec2;
+use std::collections::HashMap;
+
+/// A point of a persistent contact, with the impulses the solver accumulated at it.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CachedPoint {
+    /// Contact point in world space [x, y]
+    pub point: Vec2,
+    /// Penetration depth along the contact normal (positive = overlapping)
+    pub depth: f32,
+    /// Accumulated impulse along the normal (never negative, contacts only push)
+    pub normal_impulse: f32,
+    /// Accumulated friction impulse along the tangent `normal.perp()`
+    pub tangent_impulse: f32,
+}
+
+/// Two bodies touching during the last step.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Contact {
+    /// First body of the pair
+    pub a: PhysItem,
+    /// Second body of the pair
+    pub b: PhysItem,
+    /// Unit contact normal pointing from `a` to `b`
+    pub normal: Vec2,
+    /// One or two contact points
+    pub points: Vec<CachedPoint>,
+    /// Number of consecutive steps the pair has been touching (1 on the first step)
+    pub age: u32,
+}
+
+impl Contact {
+    /// Returns the deepest penetration among the contact points.
+    pub fn penetration(&self) -> f32 {
+        self.points.iter().fold(0.0, |depth, p| depth.max(p.depth))
+    }
+
+    /// Returns the total normal impulse applied over all contact points.
+    pub fn normal_impulse(&self) -> f32 {
+        self.points.iter().map(|p| p.normal_impulse).sum()
+    }
+
+    /// Returns whether `item` is one of the two bodies of this contact.
+    pub fn involves(&self, item: PhysItem) -> bool {
+        self.a == item || self.b == item
+    }
+}
+
+/// The stage of a contact reported by a [`ContactEvent`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ContactEventKind {
+    /// The bodies started touching this step
+    Begin,
+    /// The bodies were already touching and still are
+    Persist,
+    /// The bodies stopped touching this step (or one of them was removed)
+    End,
+}
+
+/// A change in contact between two bodies, reported once per pair and step.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ContactEvent {
+    /// Whether the contact began, persisted or ended
+    pub kind: ContactEventKind,
+    /// First body of the pair
+    pub a: PhysItem,
+    /// Second body of the pair
+    pub b: PhysItem,
+    /// Unit contact normal pointing from `a` to `b` (the last one seen, for `End`)
+    pub normal: Vec2,
+    /// Total normal impulse applied this step (0 for `End`)
+    pub impulse: f32,
+}
+
+impl ContactEvent {
+    /// Returns whether `item` is one of the two bodies of this event.
+    pub fn involves(&self, item: PhysItem) -> bool {
+        self.a == item || self.b == item
+    }
+
+    /// Returns the body `item` was in contact with.
+    ///
+    /// # Arguments
+    ///
+    /// * `item` - One body of the pair
+    ///
+    /// # Returns
+    ///
+    /// The other body, or `None` if `item` is not part of this event
+    pub fn other(&self, item: PhysItem) -> Option<PhysItem> {
+        if self.a == item {
+            Some(self.b)
+        } else if self.b == item {
+            Some(self.a)
+        } else {
+            None
+        }
+    }
+}
+
+/// The contacts of the last step, kept from one step to the next and keyed by body pair.
+///
+/// The solver reads the previous step's accumulated impulses from here to warm start,
+/// and replaces the contents after every solve. Pairs are stored in the order the
+/// broadphase reports them, but lookups accept either order.
+#[derive(Clone, Debug, Default)]
+pub struct ContactCache {
+    /// Contacts keyed by the `(a, b)` pair
+    contacts: HashMap<(PhysItem, PhysItem), Contact>,
+}
+
+impl ContactCache {
+    /// Creates a new, empty contact cache.
+    pub fn new() -> ContactCache {
+        ContactCache { contacts: HashMap::new() }
+    }
+
+    /// Returns the contact between two bodies, in either order.
+    ///
+    /// # Arguments
+    ///
+    /// * `a` - One body of the pair
+    /// * `b` - The other body of the pair
+    ///
+    /// # Returns
+    ///
+    /// The contact, or `None` if the bodies were not touching during the last step
+    pub fn get(&self, a: PhysItem, b: PhysItem) -> Option<&Contact> {
+        self.contacts.get(&(a, b)).or_else(|| self.contacts.get(&(b, a)))
+    }
+
+    /// Returns an iterator over all contacts.
+    pub fn iter(&self) -> impl Iterator<Item = &Contact> {
+        self.contacts.values()
+    }
+
+    /// Returns an iterator over the contacts `item` takes part in.
+    ///
+    /// # Arguments
+    ///
+    /// * `item` - The body whose contacts to return
+    pub fn involving(&self, item: PhysItem) -> impl Iterator<Item = &Contact> {
+        self.contacts.values().filter(move |c| c.involves(item))
+    }
+
+    /// Returns the number of touching pairs.
+    pub fn len(&self) -> usize {
+        self.contacts.len()
+    }
+
+    /// Returns whether no bodies are touching.
+    pub fn is_empty(&self) -> bool {
+        self.contacts.is_empty()
+    }
+
+    /// Forgets all contacts, so the next step starts without warm starting.
+    pub fn clear(&mut self) {
+        self.contacts.clear();
+    }
+
+    /// Finds the cached point of a pair closest to a new contact point.
+    ///
+    /// # Arguments
+    ///
+    /// * `a` - First body of the pair
+    /// * `b` - Second body of the pair
+    /// * `point` - The new contact point [x, y]
+    /// * `max_distance` - Furthest a cached point may be to count as the same point
+    pub(crate) fn closest_point(&self, a: PhysItem, b: PhysItem, point: Vec2, max_distance: f32) -> Option<&CachedPoint> {
+        self.contacts
+            .get(&(a, b))?
+            .points
+            .iter()
+            .map(|p| (p, (p.point - point).length()))
+            .filter(|(_, distance)| *distance < max_distance)
+            .min_by(|(_, d1), (_, d2)| d1.total_cmp(d2))
+            .map(|(p, _)| p)
+    }
+
+    /// Replaces the cached contacts with those of the current step.
+    ///
+    /// Pairs that were already touching keep counting their age up.
+    ///
+    /// # Arguments
+    ///
+    /// * `contacts` - The contacts found and solved this step (with an age of 0)
+    ///
+    /// # Returns
+    ///
+    /// A begin or persist event for every current contact, followed by an end event
+    /// for every pair that is no longer touching
+    pub(crate) fn update(&mut self, contacts: impl IntoIterator<Item = Contact>) -> Vec<ContactEvent> {
+        let mut previous = std::mem::take(&mut self.contacts);
+        let mut events = Vec::new();
+        for mut contact in contacts {
+            let key = (contact.a, contact.b);
+            contact.age = previous.remove(&key).map_or(0, |c| c.age) + 1;
+            events.push(ContactEvent {
+                kind: if contact.age == 1 { ContactEventKind::Begin } else { ContactEventKind::Persist },
+                a: contact.a,
+                b: contact.b,
+                normal: contact.normal,
+                impulse: contact.normal_impulse(),
+            });
+            self.contacts.insert(key, contact);
+        }
+        // Whatever is left of the previous step has separated
+        let mut ended: Vec<Contact> = previous.into_values().collect();
+        ended.sort_by_key(|c| (c.a, c.b));
+        events.extend(ended.into_iter().map(|c| ContactEvent {
+            kind: ContactEventKind::End,
+            a: c.a,
+            b: c.b,
+            normal: c.normal,
+            impulse: 0.0,
+        }));
+        events
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arena::Arena;
+    use crate::items::{Ball, Wall};
+
+    /// Returns two ball items and a wall item with live handles.
+    fn items() -> (PhysItem, PhysItem, PhysItem) {
+        let mut balls = Arena::new();
+        let mut walls = Arena::new();
+        let ball = || Ball::new(Vec2::new(0.0, 0.0), None, None, None, None, None, None);
+        let wall = walls.insert(Wall::new(Vec2::new(0.0, 0.0), Vec2::new(10.0, 0.0), None, None, None, None));
+        (PhysItem::Ball(balls.insert(ball())), PhysItem::Wall(wall), PhysItem::Ball(balls.insert(ball())))
+    }
+
+    fn contact(a: PhysItem, b: PhysItem, normal_impulse: f32) -> Contact {
+        let point = CachedPoint { point: Vec2::new(10.0, 20.0), depth: 0.5, normal_impulse, tangent_impulse: 0.0 };
+        Contact { a, b, normal: Vec2::new(0.0, 1.0), points: vec![point], age: 0 }
+    }
+
+    #[test]
+    fn persisting_pairs_age_and_separated_pairs_are_evicted() {
+        let (ball, wall, other) = items();
+        let mut cache = ContactCache::new();
+        cache.update([contact(ball, wall, 1.0), contact(ball, other, 2.0)]);
+        cache.update([contact(ball, wall, 3.0)]);
+        assert_eq!(cache.len(), 1);
+        // Lookups work in either order and see the latest impulses
+        let kept = cache.get(wall, ball).unwrap();
+        assert_eq!(kept.age, 2);
+        assert_eq!(kept.normal_impulse(), 3.0);
+        assert!(cache.get(ball, other).is_none());
+        assert_eq!(cache.involving(other).count(), 0);
+    }
+
+    #[test]
+    fn closest_point_matches_within_reach() {
+        let (ball, wall, _) = items();
+        let mut cache = ContactCache::new();
+        cache.update([contact(ball, wall, 1.0)]);
+        let matched = cache.closest_point(ball, wall, Vec2::new(11.0, 20.0), 2.0).unwrap();
+        assert_eq!(matched.normal_impulse, 1.0);
+        assert!(cache.closest_point(ball, wall, Vec2::new(15.0, 20.0), 2.0).is_none());
+    }
+}
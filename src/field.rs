@@ -0,0 +1,200 @@
+use crate::library::Vec2;
+use crate::sensor::SensorShape;
+
+/// Distance in pixels below which a field pulling towards or swirling around a
+/// center has no direction and exerts nothing
+const CENTER_EPSILON: f32 = 1e-3;
+
+/// The region a [`ForceField::Wind`] blows in.
+///
+/// Like a [`SensorShape`] without the segment, since wind needs an inside to blow in.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum WindRegion {
+    /// An axis-aligned rectangle, such as the column above a fan
+    Rectangle {
+        /// Top-left corner [x, y]
+        min: Vec2,
+        /// Bottom-right corner [x, y]
+        max: Vec2,
+    },
+    /// A circle
+    Circle {
+        /// Center of the circle [x, y]
+        center: Vec2,
+        /// Radius of the circle
+        radius: f32,
+    },
+}
+
+impl WindRegion {
+    /// Returns whether a point lies inside the region.
+    ///
+    /// # Arguments
+    ///
+    /// * `point` - The point to test [x, y]
+    pub fn contains(&self, point: Vec2) -> bool {
+        SensorShape::from(*self).contains(point)
+    }
+}
+
+impl From<WindRegion> for SensorShape {
+    fn from(region: WindRegion) -> SensorShape {
+        match region {
+            WindRegion::Rectangle { min, max } => SensorShape::Rectangle { min, max },
+            WindRegion::Circle { center, radius } => SensorShape::Circle { center, radius },
+        }
+    }
+}
+
+/// A force field accelerating every dynamic body (ball, polygon or capsule) by an
+/// amount that depends on where the body is.
+///
+/// Fields give accelerations rather than forces, so like gravity they move heavy
+/// and light bodies alike. They are evaluated at each body's center whenever the
+/// integrator needs the acceleration, so higher-order integrators follow curved
+/// fields more closely. Fields act on the body's center only and never make it spin.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ForceField {
+    /// The same acceleration everywhere, like a second gravity
+    Uniform {
+        /// Acceleration [x, y]
+        acceleration: Vec2,
+    },
+    /// An attractor or repulsor whose pull falls off with the square of the
+    /// distance, like a magnet or a planet
+    Radial {
+        /// Point bodies are pulled towards or pushed away from [x, y]
+        center: Vec2,
+        /// Acceleration at a distance of one pixel; positive attracts, negative repels
+        strength: f32,
+        /// Distance below which the pull stops growing, so bodies passing through the
+        /// center are not flung away
+        min_distance: f32,
+    },
+    /// An attractor or repulsor whose pull falls off linearly to nothing at its radius
+    LinearFalloff {
+        /// Point bodies are pulled towards or pushed away from [x, y]
+        center: Vec2,
+        /// Acceleration at the center; positive attracts, negative repels
+        strength: f32,
+        /// Distance at which the field ends
+        radius: f32,
+    },
+    /// A whirlpool pushing bodies around its center, falling off linearly to nothing
+    /// at its radius
+    Vortex {
+        /// Point bodies swirl around [x, y]
+        center: Vec2,
+        /// Acceleration around the center next to it (positive = clockwise on screen)
+        strength: f32,
+        /// Distance at which the field ends
+        radius: f32,
+    },
+    /// A constant push inside a region, like the air blown by a fan
+    Wind {
+        /// Region the wind blows in
+        region: WindRegion,
+        /// Acceleration inside the region [x, y]
+        acceleration: Vec2,
+    },
+}
+
+impl ForceField {
+    /// Returns the acceleration the field gives a body at a point.
+    ///
+    /// # Arguments
+    ///
+    /// * `position` - Center of the body [x, y]
+    ///
+    /// # Returns
+    ///
+    /// The acceleration [x, y], zero where the field does not reach
+    pub fn acceleration(&self, position: Vec2) -> Vec2 {
+        match *self {
+            ForceField::Uniform { acceleration } => acceleration,
+            ForceField::Radial { center, strength, min_distance } => {
+                let offset = center - position;
+                let distance = offset.length();
+                if distance < CENTER_EPSILON {
+                    return Vec2::ZERO;
+                }
+                let distance_sq = distance.max(min_distance).powi(2);
+                offset / distance * (strength / distance_sq)
+            },
+            ForceField::LinearFalloff { center, strength, radius } => {
+                let offset = center - position;
+                let distance = offset.length();
+                if distance < CENTER_EPSILON || distance >= radius {
+                    return Vec2::ZERO;
+                }
+                offset / distance * (strength * (1.0 - distance / radius))
+            },
+            ForceField::Vortex { center, strength, radius } => {
+                let offset = position - center;
+                let distance = offset.length();
+                if distance < CENTER_EPSILON || distance >= radius {
+                    return Vec2::ZERO;
+                }
+                offset.perp() / distance * (strength * (1.0 - distance / radius))
+            },
+            ForceField::Wind { region, acceleration } => {
+                if region.contains(position) { acceleration } else { Vec2::ZERO }
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: Vec2, expected: Vec2) {
+        assert!((actual - expected).length() < 1e-4, "{actual:?} != {expected:?}");
+    }
+
+    #[test]
+    fn uniform_is_the_same_everywhere() {
+        let field = ForceField::Uniform { acceleration: Vec2::new(0.0, -50.0) };
+        for position in [Vec2::ZERO, Vec2::new(-300.0, 1e4)] {
+            assert_close(field.acceleration(position), Vec2::new(0.0, -50.0));
+        }
+    }
+
+    #[test]
+    fn radial_attracts_or_repels_with_the_inverse_square() {
+        let attractor = ForceField::Radial { center: Vec2::ZERO, strength: 1000.0, min_distance: 5.0 };
+        let repulsor = ForceField::Radial { center: Vec2::ZERO, strength: -1000.0, min_distance: 5.0 };
+        assert_close(attractor.acceleration(Vec2::new(10.0, 0.0)), Vec2::new(-10.0, 0.0));
+        assert_close(attractor.acceleration(Vec2::new(0.0, 20.0)), Vec2::new(0.0, -2.5));
+        assert_close(repulsor.acceleration(Vec2::new(10.0, 0.0)), Vec2::new(10.0, 0.0));
+        // Closer than the minimum distance the pull stops growing, and vanishes at the center
+        assert_close(attractor.acceleration(Vec2::new(2.0, 0.0)), Vec2::new(-40.0, 0.0));
+        assert_close(attractor.acceleration(Vec2::ZERO), Vec2::ZERO);
+    }
+
+    #[test]
+    fn linear_falloff_ends_at_its_radius() {
+        let field = ForceField::LinearFalloff { center: Vec2::ZERO, strength: 100.0, radius: 50.0 };
+        assert_close(field.acceleration(Vec2::new(25.0, 0.0)), Vec2::new(-50.0, 0.0));
+        assert_close(field.acceleration(Vec2::new(0.0, -40.0)), Vec2::new(0.0, 20.0));
+        assert_close(field.acceleration(Vec2::new(50.0, 0.0)), Vec2::ZERO);
+        assert_close(field.acceleration(Vec2::new(60.0, 0.0)), Vec2::ZERO);
+    }
+
+    #[test]
+    fn vortex_turns_clockwise_on_screen() {
+        let field = ForceField::Vortex { center: Vec2::new(100.0, 100.0), strength: 100.0, radius: 100.0 };
+        // Right of the center it pushes down, below the center it pushes left
+        assert_close(field.acceleration(Vec2::new(150.0, 100.0)), Vec2::new(0.0, 50.0));
+        assert_close(field.acceleration(Vec2::new(100.0, 175.0)), Vec2::new(-25.0, 0.0));
+        assert_close(field.acceleration(Vec2::new(250.0, 100.0)), Vec2::ZERO);
+    }
+
+    #[test]
+    fn wind_blows_only_inside_its_region() {
+        let region = WindRegion::Rectangle { min: Vec2::ZERO, max: Vec2::new(100.0, 100.0) };
+        let field = ForceField::Wind { region, acceleration: Vec2::new(30.0, 0.0) };
+        assert_close(field.acceleration(Vec2::new(50.0, 50.0)), Vec2::new(30.0, 0.0));
+        assert_close(field.acceleration(Vec2::new(150.0, 50.0)), Vec2::ZERO);
+    }
+}
@@ -0,0 +1,86 @@
+/// Collision layers of a body, deciding which other bodies it collides with.
+///
+/// Each body belongs to the layers set in `category` and collides with the layers set
+/// in `mask`. Two bodies only collide if each one's mask contains a layer of the
+/// other's category. Bodies sharing a non-zero `group` never collide, whatever their
+/// layers, which is handy for the parts of one object.
+///
+/// Filtered pairs are skipped by the broadphase, so they neither collide nor stop a
+/// swept ball. Sensors ignore filters and detect every ball.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct CollisionFilter {
+    /// Layers this body belongs to (one bit per layer)
+    pub category: u32,
+    /// Layers this body collides with (one bit per layer)
+    pub mask: u32,
+    /// Group index; bodies in the same non-zero group never collide (0 = no group)
+    pub group: u32,
+}
+
+impl CollisionFilter {
+    /// The default filter: layer 1, colliding with every layer, no group.
+    pub const DEFAULT: CollisionFilter = CollisionFilter { category: 1, mask: u32::MAX, group: 0 };
+
+    /// Creates a new collision filter.
+    ///
+    /// # Arguments
+    ///
+    /// * `category` - Layers the body belongs to
+    /// * `mask` - Layers the body collides with
+    /// * `group` - Optional group index whose members never collide (default: 0, no group)
+    ///
+    /// # Returns
+    ///
+    /// A new CollisionFilter instance
+    pub fn new(category: u32, mask: u32, group: Option<u32>) -> CollisionFilter {
+        CollisionFilter {
+            category,
+            mask,
+            group: group.unwrap_or(0),
+        }
+    }
+
+    /// Returns whether bodies with these two filters collide.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - Filter of the other body
+    pub fn should_collide(&self, other: &CollisionFilter) -> bool {
+        if self.group != 0 && self.group == other.group {
+            return false;
+        }
+        self.mask & other.category != 0 && other.mask & self.category != 0
+    }
+}
+
+impl Default for CollisionFilter {
+    fn default() -> CollisionFilter {
+        CollisionFilter::DEFAULT
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn masks_must_accept_each_others_category() {
+        let player = CollisionFilter::new(0b01, 0b11, None);
+        let enemy = CollisionFilter::new(0b10, 0b10, None);
+        // The enemy's mask leaves out the player's layer, so neither sees the other
+        assert!(!player.should_collide(&enemy));
+        assert!(!enemy.should_collide(&player));
+        assert!(enemy.should_collide(&enemy));
+        assert!(player.should_collide(&CollisionFilter::DEFAULT));
+    }
+
+    #[test]
+    fn shared_group_overrides_layers() {
+        let part = CollisionFilter::new(1, u32::MAX, Some(7));
+        assert!(!part.should_collide(&part));
+        assert!(part.should_collide(&CollisionFilter::new(1, u32::MAX, Some(8))));
+        // Group 0 means no group
+        let loose = CollisionFilter::new(1, u32::MAX, Some(0));
+        assert!(loose.should_collide(&loose));
+    }
+}
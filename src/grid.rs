@@ -1,17 +1,44 @@
-use rphys::library::*;
-use crate::items::{PhysItem, Ball, Wall, GRAVITY};
+use crate::library::Vec2;
+use crate::arena::{
+    Arena, BallHandle, CapsuleHandle, JointHandle, PolygonHandle, PrismaticHandle, RevoluteHandle, SensorHandle, WallHandle,
+};
+use crate::capsule::Capsule;
+use crate::body::RigidBody;
+use crate::collision::{
+    collide, collide_circle_wall, collide_circles, sweep_circle_circle, sweep_circle_wall, Manifold, Shape,
+};
+use crate::field::ForceField;
+use crate::integrator::Integrator;
+use crate::items::{PhysItem, Ball, Wall};
+use crate::joint::{body_angle, end_body, local_point, Joint, JointTarget, PrismaticJoint, RevoluteJoint};
+use crate::motion::WallMotion;
+use crate::polygon::Polygon;
+use crate::sensor::{Sensor, SensorEvent, SensorEventKind, SensorShape};
+#[cfg(feature = "sdl")]
 use sdl2::rect::Rect;
+#[cfg(feature = "sdl")]
 use sdl2::render::{Canvas, RenderTarget};
+#[cfg(feature = "sdl")]
 use sdl2::video::Window;
+#[cfg(feature = "sdl")]
 use sdl2::pixels::Color;
+#[cfg(feature = "sdl")]
 use sdl2::ttf::Font;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+
+/// Depth in pixels a swept ball is allowed to sink into what it hits, so the
+/// collision pass following a continuous collision sees the contact
+const CCD_SLOP: f32 = 0.5;
 
 /// A spatial partitioning grid for efficient collision detection.
 ///
 /// The grid divides the simulation space into uniform cells (sections) and tracks
 /// which physics objects are in which cells. This allows collision detection to only
 /// check nearby objects rather than all pairs.
+///
+/// Objects are stored in [`Arena`]s and identified by generational handles, so
+/// iterating takes time proportional to the live objects, the memory of removed
+/// objects is reused, and handles to removed objects no longer resolve.
 pub struct Grid {
     /// Width of each grid cell in pixels
     unit_width: i32,
@@ -21,22 +48,29 @@ pub struct Grid {
     grid: Vec<Vec<Section>>,
     /// Special section for objects outside the grid bounds
     out_of_bounds: Section,
-    /// All walls in the simulation, indexed by unique ID
-    walls: HashMap<usize, Wall>,
-    /// All balls in the simulation, indexed by unique ID
-    balls: HashMap<usize, Ball>,
+    /// All walls in the simulation
+    walls: Arena<Wall>,
+    /// All balls in the simulation
+    balls: Arena<Ball>,
+    /// All polygons in the simulation
+    polygons: Arena<Polygon>,
+    /// All capsules in the simulation
+    capsules: Arena<Capsule>,
+    /// All sensors in the simulation
+    sensors: Arena<Sensor>,
+    /// All joints between balls in the simulation
+    joints: Arena<Joint>,
+    /// All hinges in the simulation
+    revolute_joints: Arena<RevoluteJoint>,
+    /// All sliders in the simulation
+    prismatic_joints: Arena<PrismaticJoint>,
+    /// Pairs of bodies joined by a joint, hinge or slider that keeps them from
+    /// colliding, the lower item first
+    joined_pairs: HashSet<(PhysItem, PhysItem)>,
     /// Number of grid cells horizontally
     x_units: i32,
     /// Number of grid cells vertically
     y_units: i32,
-    /// Current count of active balls
-    ball_cnt: usize,
-    /// Next available ball ID (monotonically increasing)
-    ball_id: usize,
-    /// Current count of active walls
-    wall_cnt: usize,
-    /// Next available wall ID (monotonically increasing)
-    wall_id: usize,
 }
 
 /// A single cell in the spatial partitioning grid.
@@ -51,19 +85,64 @@ pub struct Section {
 }
 
 impl Section {
-    /// Removes a ball from this section by its ID.
+    /// Removes any physics item from this section.
+    ///
+    /// # Arguments
+    ///
+    /// * `item` - The item to remove
+    pub fn remove(&mut self, item: PhysItem) {
+        self.items.retain(|other| *other != item);
+    }
+
+    /// Removes a ball from this section.
     ///
     /// # Arguments
     ///
-    /// * `id` - The unique ID of the ball to remove
-    pub fn remove_ball(&mut self, id: usize) {
+    /// * `handle` - The handle of the ball to remove
+    pub fn remove_ball(&mut self, handle: BallHandle) {
         self.items.retain( |item| {
             match item {
-                PhysItem::Ball(ball_id) => return *ball_id != id,
-                _ => return true
+                PhysItem::Ball(ball) => *ball != handle,
+                _ => true
             }
         });
     }
+
+    /// Removes a wall from this section.
+    ///
+    /// # Arguments
+    ///
+    /// * `handle` - The handle of the wall to remove
+    pub fn remove_wall(&mut self, handle: WallHandle) {
+        self.items.retain(|item| *item != PhysItem::Wall(handle));
+    }
+
+    /// Removes a polygon from this section.
+    ///
+    /// # Arguments
+    ///
+    /// * `handle` - The handle of the polygon to remove
+    pub fn remove_polygon(&mut self, handle: PolygonHandle) {
+        self.items.retain(|item| *item != PhysItem::Polygon(handle));
+    }
+
+    /// Removes a capsule from this section.
+    ///
+    /// # Arguments
+    ///
+    /// * `handle` - The handle of the capsule to remove
+    pub fn remove_capsule(&mut self, handle: CapsuleHandle) {
+        self.items.retain(|item| *item != PhysItem::Capsule(handle));
+    }
+
+    /// Removes a sensor from this section.
+    ///
+    /// # Arguments
+    ///
+    /// * `handle` - The handle of the sensor to remove
+    pub fn remove_sensor(&mut self, handle: SensorHandle) {
+        self.items.retain(|item| *item != PhysItem::Sensor(handle));
+    }
 }
 
 impl Grid {
@@ -81,21 +160,24 @@ impl Grid {
     /// A new Grid instance with all sections initialized
     pub fn new(unit_width: i32, unit_height: i32, window_width: i32, window_height: i32) -> Grid {
         let mut grid = Grid {
-            unit_width: unit_width,
-            unit_height: unit_height,
+            unit_width,
+            unit_height,
             grid: Vec::new(),
             out_of_bounds: Section {
                 id: [usize::MAX, usize::MAX],
                 items: Vec::new(),
             },
-            walls: HashMap::new(),
-            balls: HashMap::new(),
+            walls: Arena::new(),
+            balls: Arena::new(),
+            polygons: Arena::new(),
+            capsules: Arena::new(),
+            sensors: Arena::new(),
+            joints: Arena::new(),
+            revolute_joints: Arena::new(),
+            prismatic_joints: Arena::new(),
+            joined_pairs: HashSet::new(),
             x_units: (window_width + unit_width * 2) / unit_width,
             y_units: (window_height + unit_height * 2) / unit_height,
-            ball_cnt: 0,
-            ball_id: 0,
-            wall_cnt: 0,
-            wall_id: 0,
         };
         // Initialize all grid sections
         for i in 0..(grid.x_units as usize) {
@@ -110,6 +192,230 @@ impl Grid {
         grid
     }
 
+    /// Returns the number of balls currently in the simulation.
+    pub fn ball_count(&self) -> usize {
+        self.balls.len()
+    }
+
+    /// Returns the number of walls currently in the simulation.
+    pub fn wall_count(&self) -> usize {
+        self.walls.len()
+    }
+
+    /// Returns the number of polygons currently in the simulation.
+    pub fn polygon_count(&self) -> usize {
+        self.polygons.len()
+    }
+
+    /// Returns the number of capsules currently in the simulation.
+    pub fn capsule_count(&self) -> usize {
+        self.capsules.len()
+    }
+
+    /// Returns the number of sensors currently in the simulation.
+    pub fn sensor_count(&self) -> usize {
+        self.sensors.len()
+    }
+
+    /// Returns the number of joints currently in the simulation.
+    pub fn joint_count(&self) -> usize {
+        self.joints.len()
+    }
+
+    /// Returns the number of hinges currently in the simulation.
+    pub fn revolute_joint_count(&self) -> usize {
+        self.revolute_joints.len()
+    }
+
+    /// Returns the number of sliders currently in the simulation.
+    pub fn prismatic_joint_count(&self) -> usize {
+        self.prismatic_joints.len()
+    }
+
+    /// Returns a sensor, including the balls currently inside it.
+    ///
+    /// # Arguments
+    ///
+    /// * `handle` - The handle of the sensor
+    ///
+    /// # Returns
+    ///
+    /// The sensor, or `None` if the handle is stale
+    pub fn sensor(&self, handle: SensorHandle) -> Option<&Sensor> {
+        self.sensors.get(handle)
+    }
+
+    /// Returns a ball.
+    ///
+    /// # Arguments
+    ///
+    /// * `handle` - The handle of the ball
+    ///
+    /// # Returns
+    ///
+    /// The ball, or `None` if it has been removed
+    pub fn ball(&self, handle: BallHandle) -> Option<&Ball> {
+        self.balls.get(handle)
+    }
+
+    /// Returns a mutable reference to a ball.
+    ///
+    /// Changes to the position are picked up by the grid sections on the next step;
+    /// use [`set_ball_position`](Grid::set_ball_position) to move a ball immediately.
+    ///
+    /// # Arguments
+    ///
+    /// * `handle` - The handle of the ball
+    pub fn ball_mut(&mut self, handle: BallHandle) -> Option<&mut Ball> {
+        self.balls.get_mut(handle)
+    }
+
+    /// Returns a wall.
+    ///
+    /// Walls are only handed out immutably, since moving one must also update its grid
+    /// sections; use [`set_wall_endpoints`](Grid::set_wall_endpoints) for that.
+    ///
+    /// # Arguments
+    ///
+    /// * `handle` - The handle of the wall
+    pub fn wall(&self, handle: WallHandle) -> Option<&Wall> {
+        self.walls.get(handle)
+    }
+
+    /// Returns a polygon.
+    ///
+    /// # Arguments
+    ///
+    /// * `handle` - The handle of the polygon
+    pub fn polygon(&self, handle: PolygonHandle) -> Option<&Polygon> {
+        self.polygons.get(handle)
+    }
+
+    /// Returns a mutable reference to a polygon.
+    ///
+    /// Changes to the position are picked up by the grid sections on the next step.
+    ///
+    /// # Arguments
+    ///
+    /// * `handle` - The handle of the polygon
+    pub fn polygon_mut(&mut self, handle: PolygonHandle) -> Option<&mut Polygon> {
+        self.polygons.get_mut(handle)
+    }
+
+    /// Returns a capsule.
+    ///
+    /// # Arguments
+    ///
+    /// * `handle` - The handle of the capsule
+    pub fn capsule(&self, handle: CapsuleHandle) -> Option<&Capsule> {
+        self.capsules.get(handle)
+    }
+
+    /// Returns a mutable reference to a capsule.
+    ///
+    /// Changes to the position are picked up by the grid sections on the next step.
+    ///
+    /// # Arguments
+    ///
+    /// * `handle` - The handle of the capsule
+    pub fn capsule_mut(&mut self, handle: CapsuleHandle) -> Option<&mut Capsule> {
+        self.capsules.get_mut(handle)
+    }
+
+    /// Returns an iterator over all balls.
+    pub fn balls(&self) -> impl Iterator<Item = &Ball> {
+        self.balls.values()
+    }
+
+    /// Returns an iterator over all walls.
+    pub fn walls(&self) -> impl Iterator<Item = &Wall> {
+        self.walls.values()
+    }
+
+    /// Returns an iterator over all polygons.
+    pub fn polygons(&self) -> impl Iterator<Item = &Polygon> {
+        self.polygons.values()
+    }
+
+    /// Returns an iterator over all capsules.
+    pub fn capsules(&self) -> impl Iterator<Item = &Capsule> {
+        self.capsules.values()
+    }
+
+    /// Returns an iterator over all sensors.
+    pub fn sensors(&self) -> impl Iterator<Item = &Sensor> {
+        self.sensors.values()
+    }
+
+    /// Returns a joint.
+    ///
+    /// # Arguments
+    ///
+    /// * `handle` - The handle of the joint
+    pub fn joint(&self, handle: JointHandle) -> Option<&Joint> {
+        self.joints.get(handle)
+    }
+
+    /// Returns a mutable reference to a joint, for example to change its length.
+    ///
+    /// # Arguments
+    ///
+    /// * `handle` - The handle of the joint
+    pub fn joint_mut(&mut self, handle: JointHandle) -> Option<&mut Joint> {
+        self.joints.get_mut(handle)
+    }
+
+    /// Returns an iterator over all joints.
+    pub fn joints(&self) -> impl Iterator<Item = &Joint> {
+        self.joints.values()
+    }
+
+    /// Returns a hinge.
+    ///
+    /// # Arguments
+    ///
+    /// * `handle` - The handle of the hinge
+    pub fn revolute_joint(&self, handle: RevoluteHandle) -> Option<&RevoluteJoint> {
+        self.revolute_joints.get(handle)
+    }
+
+    /// Returns a mutable reference to a hinge, for example to switch its motor.
+    ///
+    /// # Arguments
+    ///
+    /// * `handle` - The handle of the hinge
+    pub fn revolute_joint_mut(&mut self, handle: RevoluteHandle) -> Option<&mut RevoluteJoint> {
+        self.revolute_joints.get_mut(handle)
+    }
+
+    /// Returns an iterator over all hinges.
+    pub fn revolute_joints(&self) -> impl Iterator<Item = &RevoluteJoint> {
+        self.revolute_joints.values()
+    }
+
+    /// Returns a slider.
+    ///
+    /// # Arguments
+    ///
+    /// * `handle` - The handle of the slider
+    pub fn prismatic_joint(&self, handle: PrismaticHandle) -> Option<&PrismaticJoint> {
+        self.prismatic_joints.get(handle)
+    }
+
+    /// Returns a mutable reference to a slider, for example to switch its motor.
+    ///
+    /// # Arguments
+    ///
+    /// * `handle` - The handle of the slider
+    pub fn prismatic_joint_mut(&mut self, handle: PrismaticHandle) -> Option<&mut PrismaticJoint> {
+        self.prismatic_joints.get_mut(handle)
+    }
+
+    /// Returns an iterator over all sliders.
+    pub fn prismatic_joints(&self) -> impl Iterator<Item = &PrismaticJoint> {
+        self.prismatic_joints.values()
+    }
+
     /// Gets a mutable reference to a section by grid coordinates.
     ///
     /// Returns the out_of_bounds section if coordinates are invalid.
@@ -139,6 +445,62 @@ impl Grid {
         self.get_section(x_unit, y_unit)
     }
 
+    /// Returns whether a world position lies in one of the grid's sections.
+    ///
+    /// # Arguments
+    ///
+    /// * `position` - World position in pixels [x, y]
+    pub fn contains_point(&self, position: Vec2) -> bool {
+        let x_unit = ((position.x + self.unit_width as f32) / self.unit_width as f32).floor();
+        let y_unit = ((position.y + self.unit_height as f32) / self.unit_height as f32).floor();
+        x_unit >= 0.0 && x_unit < self.x_units as f32 && y_unit >= 0.0 && y_unit < self.y_units as f32
+    }
+
+    /// Finds all grid sections that an axis-aligned box overlaps.
+    ///
+    /// If part of the box lies outside the grid, the out_of_bounds section ID
+    /// `[usize::MAX, usize::MAX]` is included once.
+    ///
+    /// # Arguments
+    ///
+    /// * `min` - Top-left corner of the box [x, y]
+    /// * `max` - Bottom-right corner of the box [x, y]
+    ///
+    /// # Returns
+    ///
+    /// A vector of section IDs that the box overlaps
+    pub fn get_sections_in_box(&mut self, min: Vec2, max: Vec2) -> Vec<[usize; 2]> {
+        // Work in floats, so boxes far outside the grid cannot overflow
+        let unit = |value: f32, size: i32| ((value + size as f32) / size as f32).floor();
+        let (min_x, max_x) = (unit(min.x, self.unit_width), unit(max.x, self.unit_width));
+        let (min_y, max_y) = (unit(min.y, self.unit_height), unit(max.y, self.unit_height));
+        let mut ids = Vec::new();
+        // Only visit the sections inside the grid, however large the box
+        let (first_x, last_x) = (min_x.max(0.0), max_x.min((self.x_units - 1) as f32));
+        let (first_y, last_y) = (min_y.max(0.0), max_y.min((self.y_units - 1) as f32));
+        if first_x <= last_x && first_y <= last_y {
+            for x in first_x as usize..=last_x as usize {
+                for y in first_y as usize..=last_y as usize {
+                    ids.push([x, y]);
+                }
+            }
+        }
+        if min_x < 0.0 || min_y < 0.0 || max_x >= self.x_units as f32 || max_y >= self.y_units as f32 {
+            ids.push(self.out_of_bounds.id);
+        }
+        ids
+    }
+
+    /// Gets a mutable reference to a section by its ID.
+    ///
+    /// Returns the out_of_bounds section for IDs outside the grid.
+    fn section_by_id(&mut self, id: [usize; 2]) -> &mut Section {
+        if id[0] >= self.x_units as usize || id[1] >= self.y_units as usize {
+            return &mut self.out_of_bounds;
+        }
+        &mut self.grid[id[0]][id[1]]
+    }
+
     /// Finds all grid sections that a line segment passes through.
     ///
     /// Uses a DDA-like (Digital Differential Analyzer) algorithm to trace a line
@@ -153,21 +515,19 @@ impl Grid {
     /// # Returns
     ///
     /// A vector of section IDs that the line passes through
-    pub fn get_sections_between_points(&mut self, s: [f32; 2], e: [f32; 2]) -> Vec<[usize; 2]> {
+    pub fn get_sections_between_points(&mut self, s: Vec2, e: Vec2) -> Vec<[usize; 2]> {
         // Normalize direction vector from start to end
-        let vec = normalize(find_vector(s, e));
-        let vx = vec[0];
-        let vy = vec[1];
+        let Vec2 { x: vx, y: vy } = (e - s).normalize();
 
         // Convert starting position to grid coordinates
-        let mut curr_x_unit = (s[0] as i32 + self.unit_width) / self.unit_width;
-        let mut curr_y_unit = (s[1] as i32 + self.unit_height) / self.unit_height;
+        let mut curr_x_unit = (s.x as i32 + self.unit_width) / self.unit_width;
+        let mut curr_y_unit = (s.y as i32 + self.unit_height) / self.unit_height;
         // Position relative to current grid cell
-        let mut relative_x = s[0] % self.unit_width as f32;
-        let mut relative_y = s[1] % self.unit_height as f32;
+        let mut relative_x = s.x.rem_euclid(self.unit_width as f32);
+        let mut relative_y = s.y.rem_euclid(self.unit_height as f32);
 
         let [mut curr_x, mut curr_y] = self.get_section(curr_x_unit, curr_y_unit).id;
-        let [end_x, end_y] = self.get_section_at_position(e[0], e[1]).id;
+        let [end_x, end_y] = self.get_section_at_position(e.x, e.y).id;
 
         // Early return if no direction or already at destination
         if (vx == 0.0 && vy == 0.0) || (curr_x == end_x && curr_y == end_y) {
@@ -210,9 +570,10 @@ impl Grid {
                 ty = f32::INFINITY;
             }
 
-            // Ignore negative or zero times (rounding errors)
-            if tx <= 0.0 { tx = f32::INFINITY; }
-            if ty <= 0.0 { ty = f32::INFINITY; }
+            // A point sitting on (or, from rounding errors, just past) a boundary
+            // crosses it immediately
+            tx = tx.max(0.0);
+            ty = ty.max(0.0);
 
             // Move to whichever boundary is closer
             let t: f32;
@@ -222,13 +583,13 @@ impl Grid {
                 if vx > 0.0 { curr_x_unit += 1 } else { curr_x_unit -= 1 };
 
                 relative_x = if vx > 0.0 { 0.0 } else { self.unit_width as f32 };
-                relative_y = vy * t;
+                relative_y += vy * t;
             } else {
                 // Cross horizontal boundary first
                 t = ty;
                 if vy > 0.0 { curr_y_unit += 1 } else { curr_y_unit -= 1 };
 
-                relative_x = vx * t;
+                relative_x += vx * t;
                 relative_y = if vy > 0.0 { 0.0 } else { self.unit_height as f32 };
             }
 
@@ -256,227 +617,1258 @@ impl Grid {
 
     /// Adds a new ball to the grid.
     ///
-    /// Assigns a unique ID to the ball, adds it to the balls HashMap, and registers
-    /// it in the appropriate grid section based on its position.
+    /// Stores the ball in the balls arena and registers it in the appropriate grid
+    /// section based on its position.
     ///
     /// # Arguments
     ///
     /// * `ball` - The ball to add
-    pub fn add_ball(&mut self, ball: Ball) {
-        let idx = self.ball_id;
-        self.ball_id += 1;
-        self.ball_cnt += 1;
-        self.balls.insert(idx, ball);
-        let [ball_x, ball_y] = self.balls.get(&idx).unwrap().position;
+    ///
+    /// # Returns
+    ///
+    /// The handle of the new ball
+    pub fn add_ball(&mut self, ball: Ball) -> BallHandle {
+        let Vec2 { x: ball_x, y: ball_y } = ball.position;
+        let handle = self.balls.insert(ball);
         let unit = self.get_section_at_position(ball_x, ball_y);
-        unit.items.push(PhysItem::Ball(idx));
+        unit.items.push(PhysItem::Ball(handle));
         let new_id = unit.id;
-        self.balls.get_mut(&idx).unwrap().unit_id = new_id;
-        self.balls.get_mut(&idx).unwrap().id = idx;
+        let ball = self.balls.get_mut(handle).unwrap();
+        ball.unit_id = new_id;
+        ball.id = handle;
+        handle
     }
 
     /// Updates a ball's grid section if it has moved to a new section.
     ///
     /// Removes the ball from its old section and adds it to its new section
-    /// based on its current position.
+    /// based on its current position. Does nothing if the ball has been removed.
     ///
     /// # Arguments
     ///
-    /// * `idx` - The unique ID of the ball to update
-    pub fn move_ball(&mut self, idx: usize) {
-        let ball = self.balls.get(&idx).unwrap();
+    /// * `handle` - The handle of the ball to update
+    pub fn move_ball(&mut self, handle: BallHandle) {
+        let Some(ball) = self.balls.get(handle) else {
+            return;
+        };
         let ball_id = ball.unit_id;
-        let [ball_x, ball_y] = ball.position;
+        let Vec2 { x: ball_x, y: ball_y } = ball.position;
         let unit = self.get_section_at_position(ball_x, ball_y);
         // Only update if ball has moved to a different section
         if unit.id != ball_id {
-            unit.items.push(PhysItem::Ball(idx));
+            unit.items.push(PhysItem::Ball(handle));
             let new_id = unit.id;
             // Remove from old section
             if ball_id[0] >= self.x_units as usize || ball_id[1] >= self.y_units as usize {
-                self.out_of_bounds.remove_ball(idx);
+                self.out_of_bounds.remove_ball(handle);
             } else {
-                self.grid[ball_id[0]][ball_id[1]].remove_ball(idx);
+                self.grid[ball_id[0]][ball_id[1]].remove_ball(handle);
             }
-            self.balls.get_mut(&idx).unwrap().unit_id = new_id;
+            self.balls.get_mut(handle).unwrap().unit_id = new_id;
         }
     }
 
     /// Adds a new wall to the grid.
     ///
-    /// Assigns a unique ID to the wall, adds it to the walls HashMap, and registers
-    /// it in all grid sections that the wall line segment passes through.
+    /// Stores the wall in the walls arena and registers it in all grid sections
+    /// that the wall line segment passes through.
     ///
     /// # Arguments
     ///
     /// * `wall` - The wall to add
-    pub fn add_wall(&mut self, wall: Wall) {
-        let idx = self.wall_id;
-        self.wall_id += 1;
-        self.wall_cnt += 1;
-        self.walls.insert(idx, wall);
-        let wall_a = self.walls.get(&idx).unwrap().a;
-        let wall_b = self.walls.get(&idx).unwrap().b;
-        // Register wall in all sections it passes through
-        for [unit_x, unit_y] in self.get_sections_between_points(wall_a, wall_b) {
-            if unit_x < self.x_units as usize && unit_y < self.y_units as usize {
-                self.grid[unit_x][unit_y].items.push(PhysItem::Wall(idx));
-            }
-        }
-        self.walls.get_mut(&idx).unwrap().id = idx;
+    ///
+    /// # Returns
+    ///
+    /// The handle of the new wall
+    pub fn add_wall(&mut self, wall: Wall) -> WallHandle {
+        let handle = self.walls.insert(wall);
+        self.register_wall(handle);
+        self.walls.get_mut(handle).unwrap().id = handle;
+        handle
     }
 
-    /// Removes balls that are out of bounds or at the bottom of the simulation.
+    /// Registers a wall in all grid sections its line segment passes through.
     ///
-    /// This cleanup is typically called once per frame to remove balls that have
-    /// left the play area or reached the collection zones.
-    pub fn cleanup(&mut self) {
-        // Remove balls that went out of bounds
-        for item in self.out_of_bounds.items.clone() {
-            match item {
-                PhysItem::Ball(idx) => {
-                    self.out_of_bounds.remove_ball(idx);
-                    self.balls.remove(&idx);
-                    self.ball_cnt -= 1;
-                },
-                _ => {continue;},
-            }
-        }
-        // Remove balls at the bottom row (collection zone)
-        let y = (self.y_units - 1) as usize;
-        for x in 0..self.x_units as usize {
-            for item in self.grid[x][y].items.clone() {
-                match item {
-                    PhysItem::Ball(idx) => {
-                        self.grid[x][y].remove_ball(idx);
-                        self.balls.remove(&idx);
-                        self.ball_cnt -= 1;
-                    },
-                    _ => {continue;},
-                }
-            }
+    /// # Arguments
+    ///
+    /// * `handle` - The handle of the wall to register
+    fn register_wall(&mut self, handle: WallHandle) {
+        let wall = self.walls.get(handle).unwrap();
+        let (wall_a, wall_b) = (wall.a, wall.b);
+        let unit_ids: Vec<[usize; 2]> = self
+            .get_sections_between_points(wall_a, wall_b)
+            .into_iter()
+            .filter(|[unit_x, unit_y]| *unit_x < self.x_units as usize && *unit_y < self.y_units as usize)
+            .collect();
+        for [unit_x, unit_y] in &unit_ids {
+            self.grid[*unit_x][*unit_y].items.push(PhysItem::Wall(handle));
         }
+        self.walls.get_mut(handle).unwrap().unit_ids = unit_ids;
     }
 
-    /// Handles all collisions between balls and between balls and walls.
+    /// Adds a new polygon to the grid.
     ///
-    /// Uses the spatial partitioning grid to efficiently check only nearby objects.
-    /// For each ball, checks the 3x3 grid of sections around it for potential collisions.
-    pub fn handle_collisions(&mut self) {
-        for idx in 0..self.ball_id {
-            let ball = match self.balls.get(&idx) {
-                Some(b) => b,
-                None => continue,
-            };
-            // Get ball's current grid position
-            let x_unit = (ball.position[0] as i32 + self.unit_width) / self.unit_width;
-            let y_unit = (ball.position[1] as i32 + self.unit_height) / self.unit_height;
-            // Track which items we've already checked to avoid duplicate collisions
-            let mut handled = vec![idx];
+    /// Stores the polygon in the polygons arena and registers it in every grid
+    /// section its bounding box overlaps.
+    ///
+    /// # Arguments
+    ///
+    /// * `polygon` - The polygon to add
+    ///
+    /// # Returns
+    ///
+    /// The handle of the new polygon
+    pub fn add_polygon(&mut self, polygon: Polygon) -> PolygonHandle {
+        let handle = self.polygons.insert(polygon);
+        self.polygons.get_mut(handle).unwrap().id = handle;
+        self.register_body(PhysItem::Polygon(handle));
+        handle
+    }
 
-            // Check 3x3 grid of sections around the ball
-            for x in (x_unit - 1)..(x_unit + 2) {
-                for y in (y_unit - 1)..(y_unit + 2) {
-                    if x >= 0 && x < self.x_units && y >= 0 && y < self.y_units {
-                        for item in self.grid[x as usize][y as usize].items.clone() {
-                            match item {
-                                PhysItem::Ball(o_idx) => {
-                                    if !handled.contains(&o_idx) {
-                                        // Get mutable references to both balls
-                                        let [Some(ball), Some(other)] = self.balls.get_disjoint_mut([&idx, &o_idx]) else {
-                                            continue;
-                                        };
-                                        ball.ball_collision(other);
-                                        handled.push(o_idx);
-                                    }
-                                },
-                                PhysItem::Wall(o_idx) => {
-                                    if !handled.contains(&o_idx) {
-                                        let other = self.walls.get(&o_idx).unwrap();
-                                        let ball = self.balls.get_mut(&idx).unwrap();
-                                        ball.wall_collision(other);
-                                        handled.push(o_idx);
-                                    }
-                                },
-                            }
-                        }
-                    }
-                }
-            }
-        }
+    /// Updates a polygon's grid sections if its bounding box now overlaps different ones.
+    ///
+    /// Does nothing if the polygon has been removed.
+    ///
+    /// # Arguments
+    ///
+    /// * `handle` - The handle of the polygon to update
+    pub fn move_polygon(&mut self, handle: PolygonHandle) {
+        self.move_body(PhysItem::Polygon(handle));
     }
 
-    /// Renders all physics objects and updates ball physics for this frame.
+    /// Adds a new capsule to the grid.
     ///
-    /// Updates ball positions, applies gravity, handles rendering, and updates
-    /// grid sections as balls move.
+    /// Stores the capsule in the capsules arena and registers it in every grid
+    /// section its bounding box overlaps.
     ///
     /// # Arguments
     ///
-    /// * `canvas` - The SDL2 canvas to draw on
-    /// * `dt` - Time delta in seconds since last frame
-    pub fn draw_frame<T: RenderTarget>(&mut self, canvas:&mut Canvas<T>, dt: f32) {
-        // Draw all walls
-        for idx in 0..self.wall_id {
-            let wall = match self.walls.get_mut(&idx) {
-                Some(w) => w,
-                None => continue,
-            };
-            wall.draw(canvas);
+    /// * `capsule` - The capsule to add
+    ///
+    /// # Returns
+    ///
+    /// The handle of the new capsule
+    pub fn add_capsule(&mut self, capsule: Capsule) -> CapsuleHandle {
+        let handle = self.capsules.insert(capsule);
+        self.capsules.get_mut(handle).unwrap().id = handle;
+        self.register_body(PhysItem::Capsule(handle));
+        handle
+    }
+
+    /// Updates a capsule's grid sections if its bounding box now overlaps different ones.
+    ///
+    /// Does nothing if the capsule has been removed.
+    ///
+    /// # Arguments
+    ///
+    /// * `handle` - The handle of the capsule to update
+    pub fn move_capsule(&mut self, handle: CapsuleHandle) {
+        self.move_body(PhysItem::Capsule(handle));
+    }
+
+    /// Returns the sections a body registered by its bounding box is in.
+    ///
+    /// Balls, which are registered by their center, walls and sensors have no
+    /// bounding box registration.
+    fn body_unit_ids(&mut self, item: PhysItem) -> Option<&mut Vec<[usize; 2]>> {
+        match item {
+            PhysItem::Polygon(handle) => self.polygons.get_mut(handle).map(|p| &mut p.unit_ids),
+            PhysItem::Capsule(handle) => self.capsules.get_mut(handle).map(|c| &mut c.unit_ids),
+            _ => None,
         }
+    }
 
-        // Update and draw all balls
-        for idx in 0..self.ball_id {
-            let ball = match self.balls.get_mut(&idx) {
-                Some(b) => b,
-                None => continue,
-            };
-            ball.move_ball(Some(dt));
-            ball.draw(canvas);
-            ball.apply_force(GRAVITY, Some(dt));
-            // Update which grid section the ball is in
-            self.move_ball(idx);
+    /// Registers a body in every grid section its bounding box overlaps.
+    ///
+    /// # Arguments
+    ///
+    /// * `item` - The polygon or capsule to register
+    fn register_body(&mut self, item: PhysItem) {
+        let (min, max) = self.body(item).unwrap().aabb();
+        let unit_ids = self.get_sections_in_box(min, max);
+        for id in &unit_ids {
+            self.section_by_id(*id).items.push(item);
         }
+        *self.body_unit_ids(item).unwrap() = unit_ids;
     }
 
-    /// Updates and renders the Plinko collection box counts.
+    /// Updates the grid sections of a body after it has moved.
     ///
-    /// Counts balls that have reached the bottom and updates the display showing
-    /// how many balls have landed in each collection box.
+    /// Balls are moved to the section of their center, polygons and capsules to the
+    /// sections their bounding box overlaps. Walls, which are re-registered whenever
+    /// they move, and sensors are left as they are, as are removed bodies.
     ///
     /// # Arguments
     ///
-    /// * `canvas` - The SDL2 canvas to draw on
-    /// * `boxes` - Vector tracking ball count for each collection box
-    /// * `font` - Font to use for rendering numbers
-    /// * `box_size` - Width of each collection box in pixels
-    /// * `window_height` - Height of the window in pixels
-    pub fn update_boxes(&self, canvas: &mut Canvas<Window>, boxes: &mut Vec<i32>, font: &Font, box_size: u32, window_height: u32) {
-        // Count balls that have reached the bottom
-        for idx in 0..self.ball_id {
-            let position = match self.balls.get(&idx) {
-                Some(b) => b.position,
-                None => continue,
-            };
-            if position[1] > window_height as f32 {
-                let box_pos = position[0] as i32 / box_size as i32;
-                if box_pos >= 0 && box_pos < boxes.len() as i32 {
-                    boxes[box_pos as usize] += 1;
-                }
+    /// * `item` - The body to update
+    pub fn move_body(&mut self, item: PhysItem) {
+        match item {
+            PhysItem::Ball(handle) => {
+                self.move_ball(handle);
+                return;
+            },
+            PhysItem::Wall(_) | PhysItem::Sensor(_) => return,
+            PhysItem::Polygon(_) | PhysItem::Capsule(_) => {},
+        }
+        let Some(body) = self.body(item) else {
+            return;
+        };
+        let (min, max) = body.aabb();
+        let new_ids = self.get_sections_in_box(min, max);
+        let unit_ids = self.body_unit_ids(item).unwrap();
+        // Only update if the body's sections have changed
+        if new_ids != *unit_ids {
+            let old_ids = std::mem::replace(unit_ids, new_ids.clone());
+            for id in &old_ids {
+                self.section_by_id(*id).remove(item);
+            }
+            for id in &new_ids {
+                self.section_by_id(*id).items.push(item);
             }
         }
-        // Render the count for each box
-        for i in 0..boxes.len() {
-            let num_str = boxes[i].to_string();
-            let text_surface = font.render(&num_str).blended(Color::RGB(255, 255, 255)).unwrap();
-            let texture_creator = canvas.texture_creator();
-            let text_texture = texture_creator.create_texture_from_surface(&text_surface).unwrap();
-            let texture_query = text_texture.query();
-            let x = box_size as i32 * i as i32 + box_size as i32 / 3;
-            let y = window_height as i32 - 60;
-            let target_rect = Rect::new(x, y, texture_query.width, texture_query.height);
-            // Rotate text 90 degrees for vertical display
-            canvas.copy_ex(&text_texture, None, Some(target_rect), -90.0, None, false, false).unwrap();
+    }
+
+    /// Adds a new sensor to the grid.
+    ///
+    /// Stores the sensor in the sensors arena and registers it in every grid
+    /// section it covers: the sections a segment passes through, or those
+    /// overlapped by the bounding box of a rectangle or circle.
+    ///
+    /// # Arguments
+    ///
+    /// * `sensor` - The sensor to add
+    ///
+    /// # Returns
+    ///
+    /// The handle of the new sensor
+    pub fn add_sensor(&mut self, sensor: Sensor) -> SensorHandle {
+        let unit_ids = match sensor.shape {
+            SensorShape::Segment { a, b } => self.get_sections_between_points(a, b),
+            _ => {
+                let (min, max) = sensor.aabb();
+                self.get_sections_in_box(min, max)
+            },
+        };
+        // Balls outside the grid are never tested, so neither are sensors there
+        let unit_ids: Vec<[usize; 2]> = unit_ids
+            .into_iter()
+            .filter(|[x, y]| *x < self.x_units as usize && *y < self.y_units as usize)
+            .collect();
+        let handle = self.sensors.insert(sensor);
+        for id in &unit_ids {
+            self.section_by_id(*id).items.push(PhysItem::Sensor(handle));
+        }
+        let sensor = self.sensors.get_mut(handle).unwrap();
+        sensor.unit_ids = unit_ids;
+        sensor.id = handle;
+        handle
+    }
+
+    /// Adds a new joint to the grid.
+    ///
+    /// Joints are not registered in any section, since the balls they connect are.
+    ///
+    /// # Arguments
+    ///
+    /// * `joint` - The joint to add
+    ///
+    /// # Returns
+    ///
+    /// The handle of the new joint, or `None` if either ball was already removed
+    pub fn add_joint(&mut self, joint: Joint) -> Option<JointHandle> {
+        let b_exists = match joint.b {
+            JointTarget::Ball(b) => self.balls.contains(b),
+            JointTarget::Anchor(_) => true,
+        };
+        if !self.balls.contains(joint.a) || !b_exists {
+            return None;
+        }
+        let (a, b) = (PhysItem::Ball(joint.a), joint.b);
+        let handle = self.joints.insert(joint);
+        self.joints.get_mut(handle).unwrap().id = handle;
+        if let JointTarget::Ball(b) = b {
+            self.update_joined_pair(a, Some(PhysItem::Ball(b)));
+        }
+        Some(handle)
+    }
+
+    /// Removes a joint from the grid.
+    ///
+    /// # Arguments
+    ///
+    /// * `handle` - The handle of the joint to remove
+    ///
+    /// # Returns
+    ///
+    /// The removed joint, or `None` if it was already removed
+    pub fn remove_joint(&mut self, handle: JointHandle) -> Option<Joint> {
+        let joint = self.joints.remove(handle)?;
+        if let JointTarget::Ball(b) = joint.b {
+            self.update_joined_pair(PhysItem::Ball(joint.a), Some(PhysItem::Ball(b)));
+        }
+        Some(joint)
+    }
+
+    /// Adds a new hinge to the grid.
+    ///
+    /// The anchor is fixed to the bodies where they are now, and the current angle
+    /// between them becomes the joint's zero angle.
+    ///
+    /// # Arguments
+    ///
+    /// * `joint` - The hinge to add
+    ///
+    /// # Returns
+    ///
+    /// The handle of the new hinge, or `None` if either body was already removed
+    pub fn add_revolute_joint(&mut self, mut joint: RevoluteJoint) -> Option<RevoluteHandle> {
+        let a = self.body(joint.a)?;
+        let b = end_body(self, joint.b)?;
+        joint.local_anchor_a = local_point(Some(a), joint.anchor);
+        joint.local_anchor_b = local_point(b, joint.anchor);
+        joint.reference_angle = a.angle() - body_angle(b);
+        let (a, b) = (joint.a, joint.b);
+        let handle = self.revolute_joints.insert(joint);
+        self.revolute_joints.get_mut(handle).unwrap().id = handle;
+        self.update_joined_pair(a, b);
+        Some(handle)
+    }
+
+    /// Removes a hinge from the grid.
+    ///
+    /// # Arguments
+    ///
+    /// * `handle` - The handle of the hinge to remove
+    ///
+    /// # Returns
+    ///
+    /// The removed hinge, or `None` if it was already removed
+    pub fn remove_revolute_joint(&mut self, handle: RevoluteHandle) -> Option<RevoluteJoint> {
+        let joint = self.revolute_joints.remove(handle)?;
+        self.update_joined_pair(joint.a, joint.b);
+        Some(joint)
+    }
+
+    /// Adds a new slider to the grid.
+    ///
+    /// The anchor and axis are fixed to the bodies where they are now, so the
+    /// translation and relative angle start at zero.
+    ///
+    /// # Arguments
+    ///
+    /// * `joint` - The slider to add
+    ///
+    /// # Returns
+    ///
+    /// The handle of the new slider, or `None` if either body was already removed
+    pub fn add_prismatic_joint(&mut self, mut joint: PrismaticJoint) -> Option<PrismaticHandle> {
+        let a = self.body(joint.a)?;
+        let b = end_body(self, joint.b)?;
+        joint.local_anchor_a = local_point(Some(a), joint.anchor);
+        joint.local_anchor_b = local_point(b, joint.anchor);
+        joint.local_axis = joint.axis.rotate(-body_angle(b));
+        joint.reference_angle = a.angle() - body_angle(b);
+        let (a, b) = (joint.a, joint.b);
+        let handle = self.prismatic_joints.insert(joint);
+        self.prismatic_joints.get_mut(handle).unwrap().id = handle;
+        self.update_joined_pair(a, b);
+        Some(handle)
+    }
+
+    /// Removes a slider from the grid.
+    ///
+    /// # Arguments
+    ///
+    /// * `handle` - The handle of the slider to remove
+    ///
+    /// # Returns
+    ///
+    /// The removed slider, or `None` if it was already removed
+    pub fn remove_prismatic_joint(&mut self, handle: PrismaticHandle) -> Option<PrismaticJoint> {
+        let joint = self.prismatic_joints.remove(handle)?;
+        self.update_joined_pair(joint.a, joint.b);
+        Some(joint)
+    }
+
+    /// Records whether two bodies are still joined by a joint, hinge or slider that
+    /// keeps them from colliding, after one was added or removed.
+    ///
+    /// # Arguments
+    ///
+    /// * `a` - The first body of the joint
+    /// * `b` - The second body of the joint, or `None` for the world
+    fn update_joined_pair(&mut self, a: PhysItem, b: Option<PhysItem>) {
+        let Some(b) = b else {
+            return;
+        };
+        let joins = |joint_a: PhysItem, joint_b: Option<PhysItem>| {
+            (joint_a, joint_b) == (a, Some(b)) || (joint_a, joint_b) == (b, Some(a))
+        };
+        let ball = |target: JointTarget| match target {
+            JointTarget::Ball(ball) => Some(PhysItem::Ball(ball)),
+            JointTarget::Anchor(_) => None,
+        };
+        let joined = self.joints.values().any(|j| !j.collide_connected && joins(PhysItem::Ball(j.a), ball(j.b)))
+            || self.revolute_joints.values().any(|j| !j.collide_connected && joins(j.a, j.b))
+            || self.prismatic_joints.values().any(|j| !j.collide_connected && joins(j.a, j.b));
+        let pair = (a.min(b), a.max(b));
+        if joined {
+            self.joined_pairs.insert(pair);
+        } else {
+            self.joined_pairs.remove(&pair);
+        }
+    }
+
+    /// Removes every joint, hinge and slider attached to a body.
+    ///
+    /// # Arguments
+    ///
+    /// * `item` - The body whose joints to remove
+    fn remove_joints_of(&mut self, item: PhysItem) {
+        if let PhysItem::Ball(ball) = item {
+            let joints: Vec<JointHandle> = self.joints.iter()
+                .filter(|(_, joint)| joint.involves(ball))
+                .map(|(joint, _)| joint)
+                .collect();
+            for joint in joints {
+                self.joints.remove(joint);
+            }
+        }
+        let hinges: Vec<RevoluteHandle> = self.revolute_joints.iter()
+            .filter(|(_, joint)| joint.involves(item))
+            .map(|(joint, _)| joint)
+            .collect();
+        for hinge in hinges {
+            self.revolute_joints.remove(hinge);
+        }
+        let sliders: Vec<PrismaticHandle> = self.prismatic_joints.iter()
+            .filter(|(_, joint)| joint.involves(item))
+            .map(|(joint, _)| joint)
+            .collect();
+        for slider in sliders {
+            self.prismatic_joints.remove(slider);
+        }
+        self.joined_pairs.retain(|&(a, b)| a != item && b != item);
+    }
+
+    /// Removes an item from the sections it was registered in.
+    ///
+    /// # Arguments
+    ///
+    /// * `item` - The item to remove
+    /// * `unit_ids` - The sections it was registered in
+    fn unregister(&mut self, item: PhysItem, unit_ids: &[[usize; 2]]) {
+        for id in unit_ids {
+            self.section_by_id(*id).remove(item);
+        }
+    }
+
+    /// Removes a ball from the grid, along with the joints attached to it.
+    ///
+    /// # Arguments
+    ///
+    /// * `handle` - The handle of the ball to remove
+    ///
+    /// # Returns
+    ///
+    /// The removed ball, or `None` if it was already removed
+    pub fn remove_ball(&mut self, handle: BallHandle) -> Option<Ball> {
+        let ball = self.balls.remove(handle)?;
+        self.section_by_id(ball.unit_id).remove_ball(handle);
+        self.remove_joints_of(PhysItem::Ball(handle));
+        Some(ball)
+    }
+
+    /// Removes a wall from the grid, along with the hinges and sliders attached to it.
+    ///
+    /// # Arguments
+    ///
+    /// * `handle` - The handle of the wall to remove
+    ///
+    /// # Returns
+    ///
+    /// The removed wall, or `None` if it was already removed
+    pub fn remove_wall(&mut self, handle: WallHandle) -> Option<Wall> {
+        let wall = self.walls.remove(handle)?;
+        self.unregister(PhysItem::Wall(handle), &wall.unit_ids);
+        self.remove_joints_of(PhysItem::Wall(handle));
+        Some(wall)
+    }
+
+    /// Removes a polygon from the grid, along with the hinges and sliders attached to it.
+    ///
+    /// # Arguments
+    ///
+    /// * `handle` - The handle of the polygon to remove
+    ///
+    /// # Returns
+    ///
+    /// The removed polygon, or `None` if it was already removed
+    pub fn remove_polygon(&mut self, handle: PolygonHandle) -> Option<Polygon> {
+        let polygon = self.polygons.remove(handle)?;
+        self.unregister(PhysItem::Polygon(handle), &polygon.unit_ids);
+        self.remove_joints_of(PhysItem::Polygon(handle));
+        Some(polygon)
+    }
+
+    /// Removes a capsule from the grid, along with the hinges and sliders attached to it.
+    ///
+    /// # Arguments
+    ///
+    /// * `handle` - The handle of the capsule to remove
+    ///
+    /// # Returns
+    ///
+    /// The removed capsule, or `None` if it was already removed
+    pub fn remove_capsule(&mut self, handle: CapsuleHandle) -> Option<Capsule> {
+        let capsule = self.capsules.remove(handle)?;
+        self.unregister(PhysItem::Capsule(handle), &capsule.unit_ids);
+        self.remove_joints_of(PhysItem::Capsule(handle));
+        Some(capsule)
+    }
+
+    /// Removes a sensor from the grid.
+    ///
+    /// The balls inside it do not get [`Exit`](SensorEventKind::Exit) events.
+    ///
+    /// # Arguments
+    ///
+    /// * `handle` - The handle of the sensor to remove
+    ///
+    /// # Returns
+    ///
+    /// The removed sensor, or `None` if it was already removed
+    pub fn remove_sensor(&mut self, handle: SensorHandle) -> Option<Sensor> {
+        let sensor = self.sensors.remove(handle)?;
+        self.unregister(PhysItem::Sensor(handle), &sensor.unit_ids);
+        Some(sensor)
+    }
+
+    /// Teleports a ball to a new position and updates its grid section.
+    ///
+    /// The ball keeps its velocity. Its previous position is reset as well, so it
+    /// is not drawn sliding across the screen from where it was.
+    ///
+    /// # Arguments
+    ///
+    /// * `handle` - The handle of the ball to move
+    /// * `position` - New position [x, y]
+    ///
+    /// # Returns
+    ///
+    /// Whether the ball still exists
+    pub fn set_ball_position(&mut self, handle: BallHandle, position: impl Into<Vec2>) -> bool {
+        let Some(ball) = self.balls.get_mut(handle) else {
+            return false;
+        };
+        ball.position = position.into();
+        ball.prev_position = ball.position;
+        self.move_ball(handle);
+        true
+    }
+
+    /// Moves a wall's endpoints and re-registers it in the sections it now passes through.
+    ///
+    /// A kinematic wall carries on along its path from the new place: its path is
+    /// shifted so that it passes through the new endpoints at the current time.
+    ///
+    /// # Arguments
+    ///
+    /// * `handle` - The handle of the wall to move
+    /// * `a` - New starting point of the wall [x, y]
+    /// * `b` - New ending point of the wall [x, y]
+    ///
+    /// # Returns
+    ///
+    /// Whether the wall still exists
+    pub fn set_wall_endpoints(&mut self, handle: WallHandle, a: impl Into<Vec2>, b: impl Into<Vec2>) -> bool {
+        let Some(wall) = self.walls.get_mut(handle) else {
+            return false;
+        };
+        wall.set_endpoints(a, b);
+        // Restart the path from here, so the next step does not snap the wall back
+        wall.origin = match wall.motion {
+            Some(motion) => motion.origin([wall.a, wall.b], wall.time),
+            None => [wall.a, wall.b],
+        };
+        self.reregister_wall(handle);
+        true
+    }
+
+    /// Makes a wall follow a scripted path from where it is now, or stops it.
+    ///
+    /// # Arguments
+    ///
+    /// * `handle` - The handle of the wall
+    /// * `motion` - Path to follow, or `None` to leave the wall static where it is
+    ///
+    /// # Returns
+    ///
+    /// Whether the wall still exists
+    pub fn set_wall_motion(&mut self, handle: WallHandle, motion: Option<WallMotion>) -> bool {
+        let Some(wall) = self.walls.get_mut(handle) else {
+            return false;
+        };
+        wall.set_motion(motion);
+        true
+    }
+
+    /// Advances every kinematic wall along its path and re-registers it in the
+    /// sections it now passes through.
+    ///
+    /// # Arguments
+    ///
+    /// * `dt` - Time delta in seconds
+    pub fn move_walls(&mut self, dt: f32) {
+        // Re-registering a wall updates the grid sections, so collect the handles first
+        let walls: Vec<WallHandle> = self.walls
+            .iter()
+            .filter(|(_, wall)| wall.motion.is_some())
+            .map(|(handle, _)| handle)
+            .collect();
+        for handle in walls {
+            self.walls.get_mut(handle).unwrap().advance(dt);
+            self.reregister_wall(handle);
+        }
+    }
+
+    /// Removes a wall from the sections it was registered in and registers it in the
+    /// sections its line segment passes through now.
+    ///
+    /// # Arguments
+    ///
+    /// * `handle` - The handle of the wall to re-register
+    fn reregister_wall(&mut self, handle: WallHandle) {
+        let unit_ids = std::mem::take(&mut self.walls.get_mut(handle).unwrap().unit_ids);
+        self.unregister(PhysItem::Wall(handle), &unit_ids);
+        self.register_wall(handle);
+    }
+
+    /// Sweeps a ball along its motion and finds the first wall or ball it would hit.
+    ///
+    /// Candidate sections are gathered along the sweep with
+    /// [`get_sections_between_points`](Grid::get_sections_between_points), together with
+    /// their neighbours so thick walls and large balls next to the path are found. Other
+    /// balls are treated as stationary for the sweep. Things the ball already touches at
+    /// the start, and hits it barely moves towards, are ignored: the collision pass
+    /// handles them, and stopping there would hold back balls resting or sliding on a wall.
+    ///
+    /// # Arguments
+    ///
+    /// * `handle` - The handle of the ball to sweep
+    /// * `start` - Where the ball's center starts the sweep [x, y]
+    /// * `motion` - Displacement of the ball over the step [dx, dy]
+    ///
+    /// # Returns
+    ///
+    /// The fraction of `motion` the ball can travel before touching something (1.0 if
+    /// the path is clear)
+    pub fn sweep_ball(&mut self, handle: BallHandle, start: Vec2, motion: Vec2) -> f32 {
+        let radius = self.balls.get(handle).unwrap().radius as f32;
+        // Sweep a slightly smaller circle so the ball ends up just touching
+        let swept_radius = radius - CCD_SLOP;
+        let mut toi: f32 = 1.0;
+        // Track which items we've already checked to avoid repeated sweeps
+        let mut handled = vec![PhysItem::Ball(handle)];
+
+        for [x, y] in self.get_sections_between_points(start, start + motion) {
+            if x >= self.x_units as usize || y >= self.y_units as usize {
+                continue;
+            }
+            // Check the 3x3 grid of sections around each section on the path
+            for x in (x as i32 - 1)..(x as i32 + 2) {
+                for y in (y as i32 - 1)..(y as i32 + 2) {
+                    if x < 0 || x >= self.x_units || y < 0 || y >= self.y_units {
+                        continue;
+                    }
+                    for item in &self.grid[x as usize][y as usize].items {
+                        if handled.contains(item) {
+                            continue;
+                        }
+                        handled.push(*item);
+                        if !self.can_collide(PhysItem::Ball(handle), *item) {
+                            continue;
+                        }
+                        let t = match item {
+                            PhysItem::Wall(other) => {
+                                let wall = self.walls.get(*other).unwrap();
+                                if wall.lets_through(start, -motion) {
+                                    continue;
+                                }
+                                let (a, b, wall_radius) = (wall.a, wall.b, wall.radius());
+                                let touch = |center| collide_circle_wall(center, radius + CCD_SLOP, a, b, wall_radius);
+                                sweep_circle_wall(start, motion, swept_radius, a, b, wall_radius)
+                                    .filter(|&t| is_sweep_hit(motion, touch(start), touch(start + motion * t)))
+                            },
+                            PhysItem::Ball(other) => {
+                                let other = self.balls.get(*other).unwrap();
+                                let (center, other_radius) = (other.position, other.radius as f32);
+                                let touch = |at| collide_circles(at, radius + CCD_SLOP, center, other_radius);
+                                sweep_circle_circle(start, motion, swept_radius, center, other_radius)
+                                    .filter(|&t| is_sweep_hit(motion, touch(start), touch(start + motion * t)))
+                            },
+                            _ => None,
+                        };
+                        if let Some(t) = t {
+                            toi = toi.min(t);
+                        }
+                    }
+                }
+            }
+        }
+        toi
+    }
+
+    /// Finds every pair of touching objects.
+    ///
+    /// Uses the spatial partitioning grid to efficiently check only nearby objects:
+    /// each ball, polygon and capsule checks the sections around it (see
+    /// [`neighbourhood`](Grid::neighbourhood)) and tests each object found there with
+    /// the narrowphase for their [`Shape`]s. Each touching pair is reported once;
+    /// bodies passing through one-way walls are left out.
+    ///
+    /// # Returns
+    ///
+    /// The touching pairs `(a, b, manifold)`, with the manifold normal pointing from `a` to `b`
+    pub fn find_contacts(&mut self) -> Vec<(PhysItem, PhysItem, Manifold)> {
+        let mut contacts = Vec::new();
+
+        // Finding the sections needs the grid mutably, so collect the bodies first
+        let bodies: Vec<PhysItem> = self.balls.handles().map(PhysItem::Ball)
+            .chain(self.polygons.handles().map(PhysItem::Polygon))
+            .chain(self.capsules.handles().map(PhysItem::Capsule))
+            .collect();
+        for item in bodies {
+            let shape = self.body(item).unwrap().shape();
+            // Track which items we've already checked to avoid duplicate collisions
+            // (balls and walls are numbered separately, so track the items themselves)
+            let mut handled = vec![item];
+
+            for [x, y] in self.neighbourhood(item) {
+                for other in &self.grid[x][y].items {
+                    if handled.contains(other) {
+                        continue;
+                    }
+                    handled.push(*other);
+                    match other {
+                        // Sensors detect balls without colliding
+                        PhysItem::Sensor(_) => continue,
+                        // Walls never look for contacts themselves
+                        PhysItem::Wall(_) => {},
+                        // Each pair of bodies is reported once, from the lower item:
+                        // balls before polygons before capsules, then by handle
+                        _ if *other < item => continue,
+                        _ => {},
+                    }
+                    if !self.can_collide(item, *other) {
+                        continue;
+                    }
+                    let manifold = match (&shape, other) {
+                        // Balls treat the ends of walls as points
+                        (Shape::Circle { center, radius }, PhysItem::Wall(wall)) => {
+                            let wall = self.walls.get(*wall).unwrap();
+                            collide_circle_wall(*center, *radius, wall.a, wall.b, wall.radius())
+                        },
+                        _ => collide(&shape, &self.body(*other).unwrap().shape()),
+                    };
+                    if let Some(manifold) = manifold {
+                        contacts.push((item, *other, manifold));
+                    }
+                }
+            }
+        }
+        // Bodies behind one-way walls pass through them
+        contacts.retain(|(a, b, manifold)| !self.lets_through(*a, *b, manifold));
+        contacts
+    }
+
+    /// Returns the grid sections a body looks for contacts in.
+    ///
+    /// A ball checks the 3x3 grid of sections around its center. A polygon or capsule
+    /// checks the sections around its bounding box, grown by one section so thick walls
+    /// registered in neighbouring sections are found too.
+    ///
+    /// # Arguments
+    ///
+    /// * `item` - The body looking for contacts
+    fn neighbourhood(&mut self, item: PhysItem) -> Vec<[usize; 2]> {
+        let Some(body) = self.body(item) else {
+            return Vec::new();
+        };
+        let ids: Vec<[i32; 2]> = match item {
+            PhysItem::Ball(_) => {
+                let center = body.position();
+                // Get ball's current grid position
+                let x_unit = (center.x as i32 + self.unit_width) / self.unit_width;
+                let y_unit = (center.y as i32 + self.unit_height) / self.unit_height;
+                (x_unit - 1..x_unit + 2)
+                    .flat_map(|x| (y_unit - 1..y_unit + 2).map(move |y| [x, y]))
+                    .collect()
+            },
+            _ => {
+                let (min, max) = body.aabb();
+                let margin = Vec2::new(self.unit_width as f32, self.unit_height as f32);
+                self.get_sections_in_box(min - margin, max + margin)
+                    .into_iter()
+                    .map(|[x, y]| [x as i32, y as i32])
+                    .collect()
+            },
+        };
+        // Objects outside the grid are only in the out of bounds section, which is never checked
+        ids.into_iter()
+            .filter(|&[x, y]| x >= 0 && x < self.x_units && y >= 0 && y < self.y_units)
+            .map(|[x, y]| [x as usize, y as usize])
+            .collect()
+    }
+
+    /// Returns whether a touching pair is a one-way wall and a body it lets through.
+    ///
+    /// # Arguments
+    ///
+    /// * `a` - First object of the pair
+    /// * `b` - Second object of the pair
+    /// * `manifold` - Contact manifold of the pair, with the normal pointing from `a` to `b`
+    fn lets_through(&self, a: PhysItem, b: PhysItem, manifold: &Manifold) -> bool {
+        // Orient the normal from the wall towards the other body
+        let (wall, other, normal) = match (a, b) {
+            (PhysItem::Wall(wall), other) => (wall, other, manifold.normal),
+            (other, PhysItem::Wall(wall)) => (wall, other, -manifold.normal),
+            _ => return false,
+        };
+        match (self.walls.get(wall), self.body(other)) {
+            (Some(wall), Some(other)) => wall.lets_through(other.position(), normal),
+            _ => false,
+        }
+    }
+
+    /// Returns whether the collision filters of two objects let them collide.
+    ///
+    /// Sensors and missing objects never collide, and neither do two bodies joined by
+    /// a joint, hinge or slider unless it has
+    /// [`collide_connected`](RevoluteJoint::collide_connected) set.
+    fn can_collide(&self, a: PhysItem, b: PhysItem) -> bool {
+        let filtered = match (self.body(a), self.body(b)) {
+            (Some(a), Some(b)) => a.filter().should_collide(&b.filter()),
+            _ => false,
+        };
+        filtered && !self.joined_pairs.contains(&(a.min(b), a.max(b)))
+    }
+
+    /// Returns a physics object as a rigid body.
+    ///
+    /// # Arguments
+    ///
+    /// * `item` - The object to look up
+    pub fn body(&self, item: PhysItem) -> Option<&dyn RigidBody> {
+        match item {
+            PhysItem::Ball(handle) => self.balls.get(handle).map(|b| b as &dyn RigidBody),
+            PhysItem::Wall(handle) => self.walls.get(handle).map(|w| w as &dyn RigidBody),
+            PhysItem::Polygon(handle) => self.polygons.get(handle).map(|p| p as &dyn RigidBody),
+            PhysItem::Capsule(handle) => self.capsules.get(handle).map(|c| c as &dyn RigidBody),
+            PhysItem::Sensor(_) => None,
+        }
+    }
+
+    /// Returns a physics object as a mutable rigid body.
+    ///
+    /// # Arguments
+    ///
+    /// * `item` - The object to look up
+    pub fn body_mut(&mut self, item: PhysItem) -> Option<&mut dyn RigidBody> {
+        match item {
+            PhysItem::Ball(handle) => self.balls.get_mut(handle).map(|b| b as &mut dyn RigidBody),
+            PhysItem::Wall(handle) => self.walls.get_mut(handle).map(|w| w as &mut dyn RigidBody),
+            PhysItem::Polygon(handle) => self.polygons.get_mut(handle).map(|p| p as &mut dyn RigidBody),
+            PhysItem::Capsule(handle) => self.capsules.get_mut(handle).map(|c| c as &mut dyn RigidBody),
+            PhysItem::Sensor(_) => None,
+        }
+    }
+
+    /// Returns two different physics objects as mutable rigid bodies.
+    ///
+    /// # Arguments
+    ///
+    /// * `a` - The first object
+    /// * `b` - The second object
+    ///
+    /// # Returns
+    ///
+    /// Both bodies, or `None` if either does not exist or `a` and `b` are the same object
+    pub fn body_pair_mut(&mut self, a: PhysItem, b: PhysItem) -> Option<(&mut dyn RigidBody, &mut dyn RigidBody)> {
+        // Two objects of the same kind come from the same arena
+        match (a, b) {
+            (PhysItem::Ball(a), PhysItem::Ball(b)) => {
+                let (a, b) = self.balls.get_pair_mut(a, b)?;
+                return Some((a, b));
+            },
+            (PhysItem::Wall(a), PhysItem::Wall(b)) => {
+                let (a, b) = self.walls.get_pair_mut(a, b)?;
+                return Some((a, b));
+            },
+            (PhysItem::Polygon(a), PhysItem::Polygon(b)) => {
+                let (a, b) = self.polygons.get_pair_mut(a, b)?;
+                return Some((a, b));
+            },
+            (PhysItem::Capsule(a), PhysItem::Capsule(b)) => {
+                let (a, b) = self.capsules.get_pair_mut(a, b)?;
+                return Some((a, b));
+            },
+            _ => {},
+        }
+        // Objects of different kinds live in different arenas, each of which is
+        // handed out at most once
+        let mut balls = Some(&mut self.balls);
+        let mut walls = Some(&mut self.walls);
+        let mut polygons = Some(&mut self.polygons);
+        let mut capsules = Some(&mut self.capsules);
+        let mut take = |item: PhysItem| -> Option<&mut dyn RigidBody> {
+            match item {
+                PhysItem::Ball(handle) => balls.take()?.get_mut(handle).map(|b| b as &mut dyn RigidBody),
+                PhysItem::Wall(handle) => walls.take()?.get_mut(handle).map(|w| w as &mut dyn RigidBody),
+                PhysItem::Polygon(handle) => polygons.take()?.get_mut(handle).map(|p| p as &mut dyn RigidBody),
+                PhysItem::Capsule(handle) => capsules.take()?.get_mut(handle).map(|c| c as &mut dyn RigidBody),
+                PhysItem::Sensor(_) => None,
+            }
+        };
+        let a = take(a)?;
+        let b = take(b)?;
+        Some((a, b))
+    }
+
+    /// Finds the balls overlapping every sensor and reports which entered or left.
+    ///
+    /// Like [`find_contacts`](Grid::find_contacts), each ball only looks at the sensors
+    /// registered in the 3x3 grid of sections around it. The balls found replace each
+    /// sensor's [`balls`](Sensor::balls); balls that were removed count as leaving.
+    ///
+    /// # Returns
+    ///
+    /// The enter and exit events, grouped by sensor and ordered by ball handle
+    pub fn update_sensors(&mut self) -> Vec<SensorEvent> {
+        let mut inside: HashMap<SensorHandle, Vec<BallHandle>> = HashMap::new();
+        for (handle, ball) in self.balls.iter() {
+            let (center, radius) = (ball.position, ball.radius as f32);
+            let x_unit = (center.x as i32 + self.unit_width) / self.unit_width;
+            let y_unit = (center.y as i32 + self.unit_height) / self.unit_height;
+            let mut handled = Vec::new();
+
+            for x in (x_unit - 1)..(x_unit + 2) {
+                for y in (y_unit - 1)..(y_unit + 2) {
+                    if x < 0 || x >= self.x_units || y < 0 || y >= self.y_units {
+                        continue;
+                    }
+                    for item in &self.grid[x as usize][y as usize].items {
+                        let PhysItem::Sensor(sensor) = *item else { continue };
+                        if handled.contains(&sensor) {
+                            continue;
+                        }
+                        handled.push(sensor);
+                        if self.sensors.get(sensor).unwrap().overlaps_circle(center, radius) {
+                            inside.entry(sensor).or_default().push(handle);
+                        }
+                    }
+                }
+            }
+        }
+
+        let mut events = Vec::new();
+        for (handle, sensor) in self.sensors.iter_mut() {
+            let mut balls = inside.remove(&handle).unwrap_or_default();
+            balls.sort();
+            let mut changes: Vec<SensorEvent> = balls
+                .iter()
+                .filter(|ball| !sensor.balls.contains(ball))
+                .map(|&ball| SensorEvent { kind: SensorEventKind::Enter, sensor: handle, ball })
+                .chain(
+                    sensor.balls
+                        .iter()
+                        .filter(|ball| !balls.contains(ball))
+                        .map(|&ball| SensorEvent { kind: SensorEventKind::Exit, sensor: handle, ball }),
+                )
+                .collect();
+            changes.sort_by_key(|e| e.ball);
+            events.extend(changes);
+            sensor.balls = balls;
+        }
+        events
+    }
+
+    /// Advances every kinematic wall, ball, polygon and capsule by one time step without
+    /// rendering anything.
+    ///
+    /// Kinematic walls are moved along their paths first (see
+    /// [`move_walls`](Grid::move_walls)), so the bodies are swept against where the
+    /// walls are at the end of the step. Then each body is moved under gravity plus the
+    /// force fields with the chosen integrator, and the grid sections it belongs to are
+    /// updated. With continuous collision detection enabled, a ball moving further
+    /// than its radius in one step is swept along its path and stopped where it first
+    /// touches a wall or ball, so it cannot tunnel through them. The ball keeps the
+    /// velocity of the full step, so the collision pass resolves the impact at full speed.
+    ///
+    /// # Arguments
+    ///
+    /// * `gravity` - Acceleration applied to every body [x, y]
+    /// * `fields` - Force fields adding to gravity where they reach
+    /// * `dt` - Time delta in seconds
+    /// * `ccd` - Whether to sweep fast balls (continuous collision detection)
+    /// * `integrator` - Numerical method used to advance positions and velocities
+    pub fn integrate(&mut self, gravity: Vec2, fields: &[ForceField], dt: f32, ccd: bool, integrator: Integrator) {
+        self.move_walls(dt);
+
+        let acceleration = |position: Vec2, _: Vec2| {
+            fields.iter().fold(gravity, |total, field| total + field.acceleration(position))
+        };
+        // Moving a body updates the grid sections, so collect the handles first
+        let balls: Vec<BallHandle> = self.balls.handles().collect();
+        for handle in balls {
+            let ball = self.balls.get_mut(handle).unwrap();
+            let start = ball.position;
+            // Remember where the step started for render interpolation
+            ball.prev_position = start;
+            ball.integrate(integrator, acceleration, dt);
+            let motion = ball.position - start;
+            // Only balls that could skip past something this step need a sweep
+            if ccd && motion.length() > ball.radius as f32 {
+                let fraction = self.sweep_ball(handle, start, motion);
+                // Stop the ball at the impact but keep the velocity of the full step,
+                // so the collision pass sees it arrive at full speed
+                self.balls.get_mut(handle).unwrap().position = start + motion * fraction;
+            }
+            // Update which grid section the ball is in
+            self.move_ball(handle);
+        }
+
+        let bodies: Vec<PhysItem> = self.polygons.handles().map(PhysItem::Polygon)
+            .chain(self.capsules.handles().map(PhysItem::Capsule))
+            .collect();
+        for item in bodies {
+            match item {
+                PhysItem::Polygon(handle) => self.polygons.get_mut(handle).unwrap().integrate(integrator, acceleration, dt),
+                PhysItem::Capsule(handle) => self.capsules.get_mut(handle).unwrap().integrate(integrator, acceleration, dt),
+                _ => unreachable!(),
+            }
+            // Update which grid sections the body overlaps
+            self.move_body(item);
+        }
+    }
+
+    /// Renders all physics objects.
+    ///
+    /// # Arguments
+    ///
+    /// * `canvas` - The SDL2 canvas to draw on
+    /// * `alpha` - How far rendering is between the previous and current step, in
+    ///   [0, 1] (see [`FixedTimestep::alpha`](crate::FixedTimestep::alpha)); balls are
+    ///   drawn at their interpolated positions
+    #[cfg(feature = "sdl")]
+    pub fn draw_frame<T: RenderTarget>(&self, canvas:&mut Canvas<T>, alpha: f32) {
+        // Draw all walls
+        for wall in self.walls.values() {
+            wall.draw(canvas);
+        }
+
+        // Draw all polygons
+        for polygon in self.polygons.values() {
+            polygon.draw(canvas);
+        }
+
+        // Draw all capsules
+        for capsule in self.capsules.values() {
+            capsule.draw(canvas);
+        }
+
+        // Draw all joints, underneath the balls they connect
+        for joint in self.joints.values() {
+            let Some(a) = self.balls.get(joint.a) else { continue };
+            let b = match joint.b {
+                JointTarget::Ball(b) => match self.balls.get(b) {
+                    Some(b) => b.interpolated_position(alpha),
+                    None => continue,
+                },
+                JointTarget::Anchor(point) => point,
+            };
+            joint.draw(canvas, a.interpolated_position(alpha), b);
+        }
+
+        // Draw all hinges and sliders on top of the polygons and capsules they connect
+        for joint in self.revolute_joints.values() {
+            if let Some((anchor, _)) = joint.anchors(self) {
+                joint.draw(canvas, anchor);
+            }
+        }
+        for joint in self.prismatic_joints.values() {
+            if let Some((a, b)) = joint.anchors(self) {
+                joint.draw(canvas, a, b);
+            }
+        }
+
+        // Draw all balls
+        for ball in self.balls.values() {
+            ball.draw(canvas, alpha);
+        }
+    }
+
+    /// Updates the Plinko collection box counts.
+    ///
+    /// Counts the balls inside the rectangular sensors at the bottom of the window, each
+    /// sensor adding to the box it starts in. Call this after every step; a ball is
+    /// counted again for every step it stays inside a sensor.
+    ///
+    /// # Arguments
+    ///
+    /// * `boxes` - Vector tracking ball count for each collection box
+    /// * `box_size` - Width of each collection box in pixels
+    /// * `window_height` - Height of the window in pixels
+    pub fn count_boxes(&self, boxes: &mut [i32], box_size: u32, window_height: u32) {
+        for sensor in self.sensors.values() {
+            // Only the collection boxes below the window are counted
+            let SensorShape::Rectangle { min, .. } = sensor.shape else {
+                continue;
+            };
+            if min.y < window_height as f32 {
+                continue;
+            }
+            let box_pos = min.x as i32 / box_size as i32;
+            if box_pos >= 0 && box_pos < boxes.len() as i32 {
+                boxes[box_pos as usize] += sensor.balls.len() as i32;
+            }
+        }
+    }
+
+    /// Renders the Plinko collection box counts.
+    ///
+    /// # Arguments
+    ///
+    /// * `canvas` - The SDL2 canvas to draw on
+    /// * `boxes` - Vector tracking ball count for each collection box
+    /// * `font` - Font to use for rendering numbers
+    /// * `box_size` - Width of each collection box in pixels
+    /// * `window_height` - Height of the window in pixels
+    #[cfg(feature = "sdl")]
+    pub fn draw_boxes(&self, canvas: &mut Canvas<Window>, boxes: &[i32], font: &Font, box_size: u32, window_height: u32) {
+        // Render the count for each box
+        for (i, count) in boxes.iter().enumerate() {
+            let num_str = count.to_string();
+            let text_surface = font.render(&num_str).blended(Color::RGB(255, 255, 255)).unwrap();
+            let texture_creator = canvas.texture_creator();
+            let text_texture = texture_creator.create_texture_from_surface(&text_surface).unwrap();
+            let texture_query = text_texture.query();
+            let x = box_size as i32 * i as i32 + box_size as i32 / 3;
+            let y = window_height as i32 - 60;
+            let target_rect = Rect::new(x, y, texture_query.width, texture_query.height);
+            // Rotate text 90 degrees for vertical display
+            canvas.copy_ex(&text_texture, None, Some(target_rect), -90.0, None, false, false).unwrap();
+        }
+    }
+}
+
+/// Decides whether a time of impact found by a sweep should stop the ball.
+///
+/// # Arguments
+///
+/// * `motion` - Displacement of the ball over the step [dx, dy]
+/// * `at_start` - Contact with the ball inflated by the slop where the sweep starts
+/// * `at_impact` - The same contact where the sweep hits
+///
+/// # Returns
+///
+/// False if the ball already touches within the slop at the start or moves no more
+/// than the slop towards what it hits, as those contacts are left to the collision pass
+fn is_sweep_hit(motion: Vec2, at_start: Option<Manifold>, at_impact: Option<Manifold>) -> bool {
+    at_start.is_none() && at_impact.is_some_and(|manifold| motion.dot(manifold.normal) > CCD_SLOP)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stale_handles_stop_resolving() {
+        let mut grid = Grid::new(50, 50, 800, 800);
+        let old = grid.add_ball(Ball::new(Vec2::new(100.0, 100.0), None, None, None, None, None, None));
+        let floor = grid.add_wall(Wall::new(Vec2::new(0.0, 700.0), Vec2::new(800.0, 700.0), None, None, None, None));
+        assert!(grid.remove_ball(old).is_some());
+        assert!(grid.remove_wall(floor).is_some());
+
+        // The new ball reuses the removed ball's slot, but the old handle does not see it
+        let new = grid.add_ball(Ball::new(Vec2::new(400.0, 400.0), None, None, None, None, None, None));
+        assert_eq!(old.index(), new.index());
+        assert_ne!(old, new);
+        assert!(grid.ball(old).is_none());
+        assert!(grid.ball_mut(old).is_none());
+        assert!(grid.remove_ball(old).is_none());
+        grid.move_ball(old);
+        assert_eq!(grid.ball(new).unwrap().position, Vec2::new(400.0, 400.0));
+        assert!(grid.wall(floor).is_none());
+        assert_eq!(grid.ball_count(), 1);
+        // Nothing is left behind in the sections of the removed bodies
+        assert!(grid.find_contacts().is_empty());
+        assert!(grid.grid.iter().flatten().all(|section| !section.items.contains(&PhysItem::Ball(old))));
+    }
+
+    /// Returns true if a segment passes through the section with the given id.
+    fn segment_touches_section(grid: &Grid, s: Vec2, e: Vec2, [x, y]: [usize; 2]) -> bool {
+        // Section `x` covers [(x - 1) * width, x * width), so clip the segment to it
+        let min = Vec2::new(((x as i32 - 1) * grid.unit_width) as f32, ((y as i32 - 1) * grid.unit_height) as f32);
+        let max = min + Vec2::new(grid.unit_width as f32, grid.unit_height as f32);
+        let (mut t0, mut t1) = (0.0_f32, 1.0_f32);
+        let d = e - s;
+        for (start, delta, lo, hi) in [(s.x, d.x, min.x, max.x), (s.y, d.y, min.y, max.y)] {
+            if delta == 0.0 {
+                if start < lo - 1e-3 || start > hi + 1e-3 {
+                    return false;
+                }
+                continue;
+            }
+            let (a, b) = ((lo - start) / delta, (hi - start) / delta);
+            t0 = t0.max(a.min(b));
+            t1 = t1.min(a.max(b));
+        }
+        t0 <= t1 + 1e-3
+    }
+
+    #[test]
+    fn boxes_are_clamped_to_the_grid() {
+        let mut grid = Grid::new(50, 50, 800, 800);
+        let (x_units, y_units) = (grid.x_units, grid.y_units);
+
+        // A box inside the window only touches its own sections
+        let ids = grid.get_sections_in_box(Vec2::new(60.0, 60.0), Vec2::new(140.0, 90.0));
+        assert_eq!(ids, vec![[2, 2], [3, 2]]);
+
+        // A box covering everything lists every section once, then out_of_bounds once
+        let ids = grid.get_sections_in_box(Vec2::new(-f32::MAX, -f32::MAX), Vec2::new(f32::MAX, f32::MAX));
+        assert_eq!(ids.len(), (x_units * y_units) as usize + 1);
+        assert_eq!(ids.iter().filter(|id| **id == [usize::MAX, usize::MAX]).count(), 1);
+
+        // A box entirely outside the grid is only out_of_bounds
+        let ids = grid.get_sections_in_box(Vec2::new(1.0e30, 1.0e30), Vec2::new(f32::MAX, f32::MAX));
+        assert_eq!(ids, vec![[usize::MAX, usize::MAX]]);
+    }
+
+    #[test]
+    fn walls_register_in_the_sections_they_cross() {
+        let mut grid = Grid::new(50, 50, 800, 800);
+        let lines = [
+            // Long shallow diagonals cross many sections in both directions
+            (Vec2::new(10.0, 10.0), Vec2::new(790.0, 390.0)),
+            (Vec2::new(790.0, 700.0), Vec2::new(20.0, 460.0)),
+            (Vec2::new(130.0, 780.0), Vec2::new(370.0, 20.0)),
+            // Starting in the border sections left of and above the window
+            (Vec2::new(-30.0, 140.0), Vec2::new(420.0, 60.0)),
+            (Vec2::new(240.0, -20.0), Vec2::new(90.0, 610.0)),
+        ];
+        for (s, e) in lines {
+            let sections = grid.get_sections_between_points(s, e);
+            assert_eq!(sections.first(), Some(&grid.get_section_at_position(s.x, s.y).id), "{s:?} -> {e:?}");
+            assert_eq!(sections.last(), Some(&grid.get_section_at_position(e.x, e.y).id), "{s:?} -> {e:?}");
+            for pair in sections.windows(2) {
+                let (dx, dy) = (pair[0][0].abs_diff(pair[1][0]), pair[0][1].abs_diff(pair[1][1]));
+                assert_eq!(dx + dy, 1, "{s:?} -> {e:?} jumps from {:?} to {:?}", pair[0], pair[1]);
+            }
+            for id in &sections {
+                assert!(segment_touches_section(&grid, s, e, *id), "{s:?} -> {e:?} does not cross {id:?}");
+            }
         }
     }
 }
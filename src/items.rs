@@ -1,60 +1,112 @@
-use rphys::library::*;
-use sdl2::pixels::Color;
+use crate::arena::{BallHandle, CapsuleHandle, Handle, PolygonHandle, SensorHandle, WallHandle};
+use crate::body::RigidBody;
+use crate::integrator::Integrator;
+use crate::library::Vec2;
+use crate::color::Color;
+use crate::collision::Shape;
+use crate::filter::CollisionFilter;
+use crate::material::{CombineRule, Material};
+use crate::motion::WallMotion;
+#[cfg(feature = "sdl")]
 use sdl2::render::{Canvas, RenderTarget};
+#[cfg(feature = "sdl")]
 use sdl2::gfx::primitives::DrawRenderer;
 
-/// Maximum allowed velocity for balls (prevents extreme speeds)
-const MAX_VELOCITY: f32 = 2000.0;
-/// Minimum allowed velocity for balls (prevents extreme speeds)
-const MIN_VELOCITY: f32 = -2000.0;
+/// Default ball density in mass units per square pixel, used to derive mass from radius
+pub const DEFAULT_DENSITY: f32 = 0.01;
 /// Global gravity force vector applied to all balls [x, y]
-pub const GRAVITY: [f32; 2] = [0.0, 400.0];
+pub const GRAVITY: Vec2 = Vec2::new(0.0, 400.0);
+/// How far backwards (as the cosine of the angle to `nvec`) a one-way wall must push a
+/// body before it lets the body through, so bodies pushed along the wall still collide
+const ONE_WAY_TOLERANCE: f32 = 1e-3;
 
 /// Converts SDL2 Color from RGBA to ABGR format for rendering.
 ///
 /// SDL2's gfx primitives expect colors in ABGR format rather than RGBA.
-fn to_abgr(color: Color) -> Color {
-    Color::RGBA(color.a, color.b, color.g, color.r)
+#[cfg(feature = "sdl")]
+pub(crate) fn to_abgr(color: Color) -> sdl2::pixels::Color {
+    sdl2::pixels::Color::RGBA(color.a, color.b, color.g, color.r)
 }
 
 /// Represents a physics item in the simulation.
 ///
 /// This enum is used to identify and differentiate between different types of
-/// physics objects stored in grid sections. The usize value is the unique ID
-/// of the item in the Grid's HashMap.
-#[derive(Clone)]
+/// physics objects stored in grid sections. Each variant holds the handle of
+/// the item in the Grid's arena for its kind.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum PhysItem {
-    /// A wall object identified by its unique ID
-    Wall(usize),
-    /// A ball object identified by its unique ID
-    Ball(usize),
+    /// A wall object identified by its handle
+    Wall(WallHandle),
+    /// A ball object identified by its handle
+    Ball(BallHandle),
+    /// A polygon object identified by its handle
+    Polygon(PolygonHandle),
+    /// A capsule object identified by its handle
+    Capsule(CapsuleHandle),
+    /// A sensor region identified by its handle
+    Sensor(SensorHandle),
 }
 
 /// Represents a wall (line segment) in the physics simulation.
 ///
-/// Walls are static line segments that balls can collide with. They have
-/// physical properties like friction and restitution that affect collision behavior.
+/// Walls are line segments that balls can collide with. They have physical
+/// properties like friction and restitution that affect collision behavior.
+/// Walls are static unless given a [`WallMotion`], which makes them kinematic: they
+/// follow the scripted path, are never pushed back, and pass their surface velocity
+/// on to whatever they touch. A wall's surface can also act as a conveyor belt, kick
+/// what hits it away like a pinball bumper, or only block bodies from one side.
 pub struct Wall {
-    /// Unique identifier for this wall
-    pub id: usize,
+    /// Handle of this wall in its grid (dangling until the wall is added)
+    pub id: WallHandle,
     /// Starting point of the wall segment [x, y]
-    pub a: [f32; 2],
+    pub a: Vec2,
     /// Ending point of the wall segment [x, y]
-    pub b: [f32; 2],
+    pub b: Vec2,
     /// Visual width of the wall in pixels
     pub width: i32,
     /// Color used to render the wall
     pub color: Color,
     /// Normalized direction vector along the wall
-    pub vec: [f32; 2],
+    pub vec: Vec2,
     /// Length of the wall segment
     pub length: f32,
     /// Normalized normal vector (perpendicular to the wall)
-    pub nvec: [f32; 2],
+    pub nvec: Vec2,
     /// Friction coefficient (affects tangential velocity loss in collisions)
     pub friction: f32,
     /// Restitution coefficient (affects normal velocity bounce in collisions)
     pub restitution: f32,
+    /// Rule for combining this wall's friction with a ball's
+    pub friction_combine: CombineRule,
+    /// Rule for combining this wall's restitution with a ball's
+    pub restitution_combine: CombineRule,
+    /// Material identifier used to look up pair overrides in a [`MaterialTable`](crate::MaterialTable) (0 = none)
+    pub material_id: u32,
+    /// Collision layers deciding which bodies the wall collides with
+    pub filter: CollisionFilter,
+    /// Speed in pixels per second at which the surface slides along `vec`, carrying
+    /// touching bodies like a conveyor belt (0.0 for a plain surface)
+    pub surface_speed: f32,
+    /// Impulse along the normal added when a body hits the wall, kicking it away like a
+    /// pinball bumper or slingshot; bodies resting against the wall are not kicked again
+    /// (0.0 for no kick)
+    pub kick: f32,
+    /// Whether bodies only collide from the side `nvec` points to, passing through the
+    /// wall from the other side
+    pub one_way: bool,
+    /// Scripted path the wall follows, or `None` for a static wall
+    pub motion: Option<WallMotion>,
+    /// Endpoints the wall's path starts from: where the wall was when its motion was
+    /// set, shifted whenever [`Grid::set_wall_endpoints`](crate::Grid::set_wall_endpoints) moves it
+    pub origin: [Vec2; 2],
+    /// Seconds the wall has followed its path
+    pub time: f32,
+    /// Velocity of the wall's center [vx, vy]
+    pub velocity: Vec2,
+    /// Angular velocity of the wall in radians per second (positive = clockwise on screen)
+    pub angular_velocity: f32,
+    /// IDs of the grid sections this wall passes through [x_unit, y_unit]
+    pub unit_ids: Vec<[usize; 2]>,
 }
 
 impl Wall {
@@ -73,27 +125,138 @@ impl Wall {
     ///
     /// A new Wall instance with computed direction and normal vectors
     pub fn new(
-        a: [f32; 2],
-        b: [f32; 2],
+        a: Vec2,
+        b: Vec2,
         width: Option<i32>,
         color: Option<Color>,
         friction: Option<f32>,
         restitution: Option<f32>,
     ) -> Wall {
-        let vector = find_vector(a, b);
-        let wall = Wall {
-            id: 0,
-            a: a,
-            b: b,
+        let vector = b - a;
+        Wall {
+            id: Handle::DANGLING,
+            a,
+            b,
             width: width.unwrap_or(10),
             color: color.unwrap_or(Color::GREEN),
-            vec: normalize(vector),
-            length: get_magnitude(vector),
-            nvec: find_normal(a, b),
+            vec: vector.normalize(),
+            length: vector.length(),
+            nvec: vector.normalize().perp(),
             friction: friction.unwrap_or(0.1),
             restitution: restitution.unwrap_or(0.1),
+            friction_combine: CombineRule::default(),
+            restitution_combine: CombineRule::default(),
+            material_id: 0,
+            filter: CollisionFilter::default(),
+            surface_speed: 0.0,
+            kick: 0.0,
+            one_way: false,
+            motion: None,
+            origin: [a, b],
+            time: 0.0,
+            velocity: Vec2::ZERO,
+            angular_velocity: 0.0,
+            unit_ids: Vec::new(),
+        }
+    }
+
+    /// Makes the wall follow a scripted path from where it is now, or stops it.
+    ///
+    /// Use [`Grid::set_wall_motion`](crate::Grid::set_wall_motion) for a wall that is
+    /// already in a grid.
+    ///
+    /// # Arguments
+    ///
+    /// * `motion` - Path to follow, or `None` to leave the wall static where it is
+    pub fn set_motion(&mut self, motion: Option<WallMotion>) {
+        self.motion = motion;
+        self.origin = [self.a, self.b];
+        self.time = 0.0;
+        (self.velocity, self.angular_velocity) = match motion {
+            Some(motion) => motion.velocity(self.origin, 0.0),
+            None => (Vec2::ZERO, 0.0),
+        };
+    }
+
+    /// Advances the wall along its path, updating its endpoints and velocity.
+    ///
+    /// Use [`Grid::move_walls`](crate::Grid::move_walls) for walls that are in a grid,
+    /// so their sections are updated as well.
+    ///
+    /// # Arguments
+    ///
+    /// * `dt` - Time delta in seconds
+    ///
+    /// # Returns
+    ///
+    /// Whether the wall has a path and moved along it
+    pub fn advance(&mut self, dt: f32) -> bool {
+        let Some(motion) = self.motion else {
+            return false;
         };
-        wall
+        self.time += dt;
+        let [a, b] = motion.endpoints(self.origin, self.time);
+        self.set_endpoints(a, b);
+        (self.velocity, self.angular_velocity) = motion.velocity(self.origin, self.time);
+        true
+    }
+
+    /// Returns the velocity of a point on the wall, including the effect of rotation.
+    ///
+    /// # Arguments
+    ///
+    /// * `offset` - Point relative to the wall's center [x, y]
+    pub fn velocity_at(&self, offset: Vec2) -> Vec2 {
+        self.velocity + offset.perp() * self.angular_velocity
+    }
+
+    /// Returns the velocity a conveyor surface adds along the wall [vx, vy].
+    pub fn surface_velocity(&self) -> Vec2 {
+        self.vec * self.surface_speed
+    }
+
+    /// Returns whether a one-way wall lets a touching body through.
+    ///
+    /// A body is let through when its center is behind the wall (opposite `nvec`) or
+    /// when it would be pushed out of the wall backwards, so bodies coming from behind
+    /// pass all the way through instead of being caught half way. Bodies pushed out
+    /// sideways, such as at the wall's ends, still collide.
+    ///
+    /// # Arguments
+    ///
+    /// * `center` - Center of the body [x, y]
+    /// * `direction` - Direction the body would be pushed out of the wall [x, y]
+    pub fn lets_through(&self, center: Vec2, direction: Vec2) -> bool {
+        self.one_way && ((center - self.a).dot(self.nvec) < 0.0
+            || direction.dot(self.nvec) < -ONE_WAY_TOLERANCE * direction.length())
+    }
+
+    /// Moves the wall's endpoints, recomputing its direction, length and normal.
+    ///
+    /// Use [`Grid::set_wall_endpoints`](crate::Grid::set_wall_endpoints) for a wall
+    /// that is already in a grid, so its sections are updated as well.
+    ///
+    /// # Arguments
+    ///
+    /// * `a` - New starting point of the wall [x, y]
+    /// * `b` - New ending point of the wall [x, y]
+    pub fn set_endpoints(&mut self, a: impl Into<Vec2>, b: impl Into<Vec2>) {
+        self.a = a.into();
+        self.b = b.into();
+        let vector = self.b - self.a;
+        self.vec = vector.normalize();
+        self.length = vector.length();
+        self.nvec = self.vec.perp();
+    }
+
+    /// Returns the wall's endpoints as a two-vertex shape for the SAT narrowphase.
+    pub fn vertices(&self) -> [Vec2; 2] {
+        [self.a, self.b]
+    }
+
+    /// Returns half the wall's width, the radius its segment is inflated by.
+    pub fn radius(&self) -> f32 {
+        self.width as f32 / 2.0
     }
 
     /// Draws the wall on the canvas as a thick line.
@@ -101,28 +264,91 @@ impl Wall {
     /// # Arguments
     ///
     /// * `canvas` - The SDL2 canvas to draw on
+    #[cfg(feature = "sdl")]
     pub fn draw<T: RenderTarget>(&self, canvas:&mut Canvas<T>) {
-        let x1 = self.a[0] as i16;
-        let y1 = self.a[1] as i16;
-        let x2 = self.b[0] as i16;
-        let y2 = self.b[1] as i16;
+        let x1 = self.a.x as i16;
+        let y1 = self.a.y as i16;
+        let x2 = self.b.x as i16;
+        let y2 = self.b.y as i16;
         let width = self.width as u8;
         let color = to_abgr(self.color);
         let _ = canvas.thick_line(x1, y1, x2, y2, width, color);
     }
 }
 
+impl RigidBody for Wall {
+    fn position(&self) -> Vec2 {
+        (self.a + self.b) / 2.0
+    }
+
+    fn velocity_at(&self, offset: Vec2) -> Vec2 {
+        Wall::velocity_at(self, offset)
+    }
+
+    fn angle(&self) -> f32 {
+        // Measured from the start of the path, so static walls stay at 0
+        let start = self.origin[1] - self.origin[0];
+        start.cross(self.vec).atan2(start.dot(self.vec))
+    }
+
+    fn angular_velocity(&self) -> f32 {
+        self.angular_velocity
+    }
+
+    fn inv_mass(&self) -> f32 {
+        0.0
+    }
+
+    fn inv_inertia(&self) -> f32 {
+        0.0
+    }
+
+    fn apply_impulse_at(&mut self, _impulse: Vec2, _offset: Vec2) {}
+
+    fn apply_angular_impulse(&mut self, _impulse: f32) {}
+
+    fn translate(&mut self, _delta: Vec2) {}
+
+    fn rotate(&mut self, _delta: f32) {}
+
+    fn material(&self) -> Material {
+        Material {
+            friction: self.friction,
+            restitution: self.restitution,
+            friction_combine: self.friction_combine,
+            restitution_combine: self.restitution_combine,
+            id: self.material_id,
+        }
+    }
+
+    fn filter(&self) -> CollisionFilter {
+        self.filter
+    }
+
+    fn shape(&self) -> Shape {
+        Shape::Segment { a: self.a, b: self.b, radius: self.radius() }
+    }
+
+    fn aabb(&self) -> (Vec2, Vec2) {
+        let (a, b) = (self.a, self.b);
+        let r = Vec2::new(self.radius(), self.radius());
+        (Vec2::new(a.x.min(b.x), a.y.min(b.y)) - r, Vec2::new(a.x.max(b.x), a.y.max(b.y)) + r)
+    }
+}
+
 /// Represents a ball (circle) in the physics simulation.
 ///
 /// Balls are dynamic physics objects that move, collide with walls and other balls,
 /// and respond to forces like gravity.
 pub struct Ball {
-    /// Unique identifier for this ball
-    pub id: usize,
+    /// Handle of this ball in its grid (dangling until the ball is added)
+    pub id: BallHandle,
     /// Current position in world space [x, y]
-    pub position: [f32; 2],
+    pub position: Vec2,
+    /// Position at the start of the last simulation step, used for render interpolation
+    pub prev_position: Vec2,
     /// Current velocity vector [vx, vy]
-    pub velocity: [f32; 2],
+    pub velocity: Vec2,
     /// Radius of the ball in pixels
     pub radius: i32,
     /// Color used to render the ball
@@ -131,6 +357,22 @@ pub struct Ball {
     pub friction: f32,
     /// Restitution coefficient (affects normal velocity bounce in collisions)
     pub restitution: f32,
+    /// Rule for combining this ball's friction with another object's
+    pub friction_combine: CombineRule,
+    /// Rule for combining this ball's restitution with another object's
+    pub restitution_combine: CombineRule,
+    /// Material identifier used to look up pair overrides in a [`MaterialTable`](crate::MaterialTable) (0 = none)
+    pub material_id: u32,
+    /// Collision layers deciding which bodies the ball collides with
+    pub filter: CollisionFilter,
+    /// Mass of the ball (a non-positive mass makes the ball immovable)
+    pub mass: f32,
+    /// Moment of inertia about the center (a non-positive inertia prevents spinning)
+    pub inertia: f32,
+    /// Current orientation in radians
+    pub angle: f32,
+    /// Current angular velocity in radians per second (positive = clockwise on screen)
+    pub angular_velocity: f32,
     /// ID of the grid section this ball currently occupies [x_unit, y_unit]
     pub unit_id: [usize; 2],
 }
@@ -146,215 +388,231 @@ impl Ball {
     /// * `color` - Optional color (default: RED)
     /// * `friction` - Optional friction coefficient (default: 0.1)
     /// * `restitution` - Optional restitution coefficient (default: 0.1)
+    /// * `mass` - Optional mass (default: area of the ball times [`DEFAULT_DENSITY`])
+    ///
+    /// The moment of inertia is that of a solid disc: `mass * radius² / 2`
     ///
     /// # Returns
     ///
     /// A new Ball instance
     pub fn new(
-        position: [f32; 2],
-        velocity: Option<[f32; 2]>,
+        position: Vec2,
+        velocity: Option<Vec2>,
         radius: Option<i32>,
         color: Option<Color>,
         friction: Option<f32>,
         restitution: Option<f32>,
+        mass: Option<f32>,
     ) -> Ball {
-        let ball = Ball {
-            id: 0,
-            position: position,
-            velocity: velocity.unwrap_or([0.0, 0.0]),
-            radius: radius.unwrap_or(10),
+        let radius = radius.unwrap_or(10);
+        let area = std::f32::consts::PI * (radius * radius) as f32;
+        let mass = mass.unwrap_or(area * DEFAULT_DENSITY);
+        Ball {
+            id: Handle::DANGLING,
+            position,
+            prev_position: position,
+            velocity: velocity.unwrap_or(Vec2::ZERO),
+            radius,
             color: color.unwrap_or(Color::RED),
             friction: friction.unwrap_or(0.1),
             restitution: restitution.unwrap_or(0.1),
+            friction_combine: CombineRule::default(),
+            restitution_combine: CombineRule::default(),
+            material_id: 0,
+            filter: CollisionFilter::default(),
+            mass,
+            inertia: 0.5 * mass * (radius * radius) as f32,
+            angle: 0.0,
+            angular_velocity: 0.0,
             unit_id: [0, 0]
-        };
-        ball
+        }
     }
 
-    /// Draws the ball on the canvas as a filled circle.
+    /// Returns the inverse of the ball's mass, or 0.0 for an immovable ball.
+    pub fn inv_mass(&self) -> f32 {
+        if self.mass > 0.0 {
+            1.0 / self.mass
+        } else {
+            0.0
+        }
+    }
+
+    /// Returns the inverse of the ball's moment of inertia, or 0.0 if it cannot spin.
+    pub fn inv_inertia(&self) -> f32 {
+        if self.inertia > 0.0 {
+            1.0 / self.inertia
+        } else {
+            0.0
+        }
+    }
+
+    /// Applies an instantaneous impulse to the ball: `velocity += impulse / mass`
+    ///
+    /// # Arguments
+    ///
+    /// * `impulse` - The impulse vector to apply [jx, jy]
+    pub fn apply_impulse(&mut self, impulse: Vec2) {
+        self.velocity += impulse * self.inv_mass();
+    }
+
+    /// Applies an impulse at a point on the ball, changing both its velocity and spin.
+    ///
+    /// # Arguments
+    ///
+    /// * `impulse` - The impulse vector to apply [jx, jy]
+    /// * `offset` - Point of application relative to the ball's center [x, y]
+    pub fn apply_impulse_at(&mut self, impulse: Vec2, offset: Vec2) {
+        self.velocity += impulse * self.inv_mass();
+        self.angular_velocity += offset.cross(impulse) * self.inv_inertia();
+    }
+
+    /// Returns the velocity of a point on the ball, including the effect of spin.
+    ///
+    /// # Arguments
+    ///
+    /// * `offset` - Point relative to the ball's center [x, y]
+    pub fn velocity_at(&self, offset: Vec2) -> Vec2 {
+        self.velocity + offset.perp() * self.angular_velocity
+    }
+
+    /// Returns the ball's position blended between the previous and current step.
+    ///
+    /// # Arguments
+    ///
+    /// * `alpha` - Blend factor, 0.0 for the previous position and 1.0 for the current one
+    pub fn interpolated_position(&self, alpha: f32) -> Vec2 {
+        self.prev_position.lerp(self.position, alpha)
+    }
+
+    /// Draws the ball on the canvas as a filled circle with a line marking its orientation.
     ///
     /// # Arguments
     ///
     /// * `canvas` - The SDL2 canvas to draw on
-    pub fn draw<T: RenderTarget>(&self, canvas:&mut Canvas<T>) {
-        let x = self.position[0] as i16;
-        let y = self.position[1] as i16;
+    /// * `alpha` - Blend factor between the previous and current position (see
+    ///   [`interpolated_position`](Ball::interpolated_position))
+    #[cfg(feature = "sdl")]
+    pub fn draw<T: RenderTarget>(&self, canvas:&mut Canvas<T>, alpha: f32) {
+        let position = self.interpolated_position(alpha);
+        let x = position.x as i16;
+        let y = position.y as i16;
         let rad = self.radius as i16;
         let color = to_abgr(self.color);
         let _ = canvas.filled_circle(x, y, rad, color);
+        // Mark the orientation so spin is visible
+        let edge = position + Vec2::new(self.radius as f32, 0.0).rotate(self.angle);
+        let _ = canvas.line(x, y, edge.x as i16, edge.y as i16, to_abgr(Color::BLACK));
     }
 
-    /// Updates the ball's position based on its velocity.
+    /// Updates the ball's position and orientation based on its velocities.
     ///
     /// Applies velocity to position using: `position += velocity * dt`
-    /// Also clamps velocity to prevent extreme speeds.
+    /// and angular velocity to orientation using: `angle += angular_velocity * dt`
+    /// Also clamps velocity to prevent extreme speeds. This is an
+    /// [`integrate`](Self::integrate) step without any acceleration.
     ///
     /// # Arguments
     ///
     /// * `delta` - Optional time delta in seconds (default: 1.0)
-    pub fn move_ball(&mut self, delta: Option<f32>) {
-        let dt = delta.unwrap_or(1.0);
-        let new_x = self.position[0] + self.velocity[0] * dt;
-        let new_y = self.position[1] + self.velocity[1] * dt;
-        // Clamp velocities to prevent unrealistic speeds
-        let clamped_vx = self.velocity[0].clamp(MIN_VELOCITY, MAX_VELOCITY);
-        let clamped_vy = self.velocity[1].clamp(MIN_VELOCITY, MAX_VELOCITY);
-        self.position = [new_x, new_y];
-        self.velocity = [clamped_vx, clamped_vy];
+    /// * `integrator` - Optional numerical method (default: [`Integrator::ExplicitEuler`])
+    pub fn move_ball(&mut self, delta: Option<f32>, integrator: Option<Integrator>) {
+        self.integrate(integrator.unwrap_or_default(), |_, _| Vec2::ZERO, delta.unwrap_or(1.0));
     }
 
     /// Applies a force to the ball, modifying its velocity.
     ///
-    /// Uses simple Euler integration: `velocity += force * dt`
+    /// With a constant force every integrator gives `velocity += force * dt`;
+    /// the position is left for [`move_ball`](Self::move_ball) to update.
     ///
     /// # Arguments
     ///
     /// * `force` - The force vector to apply [fx, fy]
     /// * `delta` - Optional time delta in seconds (default: 1.0)
-    pub fn apply_force(&mut self, force: [f32; 2], delta: Option<f32>) {
+    /// * `integrator` - Optional numerical method (default: [`Integrator::ExplicitEuler`])
+    pub fn apply_force(&mut self, force: Vec2, delta: Option<f32>, integrator: Option<Integrator>) {
         let dt = delta.unwrap_or(1.0);
-        let new_x = self.velocity[0] + force[0] * dt;
-        let new_y = self.velocity[1] + force[1] * dt;
-        self.velocity = [new_x, new_y];
+        // Only the velocity half of the integrator's step
+        (_, self.velocity) = integrator.unwrap_or_default().step(self.position, self.velocity, dt, |_, _| force);
     }
 
-    /// Handles collision between this ball and a wall.
-    ///
-    /// Uses impulse-based collision resolution with friction and restitution.
-    /// Handles both line segment collisions and endpoint (corner) collisions.
+    /// Advances the ball by one time step with the given integrator (see
+    /// [`Integrator::advance`]).
     ///
     /// # Arguments
     ///
-    /// * `wall` - The wall to check collision with
-    pub fn wall_collision(&mut self, wall:&Wall) {
-        // Find vector from wall start to ball
-        let mut vec = find_vector(wall.a, self.position);
-        // Project ball position onto wall direction to find closest point
-        let position = dot(vec, wall.vec);
-        let nv: [f32; 2];  // Normal vector (perpendicular to collision surface)
-        let tv: [f32; 2];  // Tangent vector (along collision surface)
-        let dist: f32;     // Distance from ball to wall/endpoint
-        let min_dist: f32; // Minimum distance before collision
-
-        // If ball is past the end of the wall, check collision with endpoint
-        if position > wall.length {
-            vec = find_vector(wall.b, self.position)
-        }
+    /// * `integrator` - Numerical method to use
+    /// * `acceleration` - Acceleration as a function of position and velocity
+    /// * `dt` - Time delta in seconds
+    pub fn integrate(&mut self, integrator: Integrator, acceleration: impl Fn(Vec2, Vec2) -> Vec2, dt: f32) {
+        integrator.advance(&mut self.position, &mut self.velocity, &mut self.angle, self.angular_velocity, dt, acceleration);
+    }
+}
 
-        // Check if ball is colliding with wall endpoints (corners)
-        if position < 0.0 || position > wall.length {
-            // Endpoint collision: use radial normal from endpoint to ball center
-            nv = normalize(vec);
-            tv = [-nv[1], nv[0]];
-            dist = get_magnitude(vec);
-            min_dist = self.radius as f32;
-        } else {
-            // Line segment collision: use wall's normal vector
-            nv = wall.nvec;
-            tv = wall.vec;
-            dist = dot(vec, wall.nvec);
-            min_dist = (self.radius + wall.width / 2) as f32;
-        }
+impl RigidBody for Ball {
+    fn position(&self) -> Vec2 {
+        self.position
+    }
 
-        // Early exit if ball is too far from wall
-        if dist.abs() > min_dist {
-            return;
-        }
+    fn velocity_at(&self, offset: Vec2) -> Vec2 {
+        Ball::velocity_at(self, offset)
+    }
 
-        // Calculate velocity components along normal and tangent
-        let n_vel = dot(self.velocity, nv);
-        // Early exit if ball is moving away from wall
-        if (n_vel < 0.0 && dist < 0.0) || (n_vel > 0.0 && dist > 0.0) {
-            return;
-        }
-        let t_vel = dot(self.velocity, tv);
-
-        // Apply physics: bounce (restitution) and friction
-        let total_restitution = wall.restitution + self.restitution;
-        let total_friction = wall.friction + self.friction;
-
-        // Calculate new velocity components
-        // Normal component: reversed and scaled by restitution (bounce)
-        let x_n = -n_vel * wall.nvec[0] * total_restitution;
-        // Tangent component: preserved but reduced by friction
-        let x_t = t_vel * wall.vec[0] * (1.0 - total_friction);
-        let y_n = -n_vel * wall.nvec[1] * total_restitution;
-        let y_t = t_vel * wall.vec[1] * (1.0 - total_friction);
-        self.velocity = [x_n + x_t, y_n + y_t];
-
-        // Resolve penetration by pushing ball out of wall
-        let penetration = min_dist - dist.abs();
-        if penetration > 0.0 {
-            let sign = if dist >= 0.0 {1.0} else {-1.0};
-            let new_x = self.position[0] + nv[0] * penetration * sign;
-            let new_y = self.position[1] + nv[1] * penetration * sign;
-            self.position = [new_x, new_y];
-        }
+    fn angle(&self) -> f32 {
+        self.angle
     }
 
-    /// Handles collision between this ball and another ball.
-    ///
-    /// Uses impulse-based collision resolution with friction and restitution.
-    /// Updates velocities and positions of both balls.
-    ///
-    /// # Arguments
-    ///
-    /// * `other` - The other ball to collide with (mutable reference)
-    pub fn ball_collision(&mut self, other:&mut Ball) {
-        // Find vector from other ball to this ball
-        let vec = find_vector(other.position, self.position);
-        let dist = get_magnitude(vec);
-        let min_dist = (self.radius + other.radius) as f32;
+    fn angular_velocity(&self) -> f32 {
+        self.angular_velocity
+    }
 
-        // Early exit if balls aren't touching
-        if dist > min_dist {
-            return;
-        }
+    fn inv_mass(&self) -> f32 {
+        Ball::inv_mass(self)
+    }
+
+    fn inv_inertia(&self) -> f32 {
+        Ball::inv_inertia(self)
+    }
 
-        // Calculate collision normal and tangent vectors
-        let nv = normalize(vec);  // Normal: from other to self
-        let tv = [-nv[1], nv[0]]; // Tangent: perpendicular to normal
+    fn apply_impulse_at(&mut self, impulse: Vec2, offset: Vec2) {
+        Ball::apply_impulse_at(self, impulse, offset)
+    }
+
+    fn apply_angular_impulse(&mut self, impulse: f32) {
+        self.angular_velocity += impulse * Ball::inv_inertia(self);
+    }
 
-        // Decompose velocities into normal and tangential components
-        let n_vel_self = dot(self.velocity, nv);
-        let t_vel_self = dot(self.velocity, tv);
-        let n_vel_other = dot(other.velocity, nv);
-        let t_vel_other = dot(other.velocity, tv);
+    fn translate(&mut self, delta: Vec2) {
+        if Ball::inv_mass(self) > 0.0 {
+            self.position += delta;
+        }
+    }
 
-        // Early exit if balls are moving apart (not approaching each other)
-        if n_vel_self - n_vel_other > 0.0 {
-            return;
+    fn rotate(&mut self, delta: f32) {
+        if Ball::inv_inertia(self) > 0.0 {
+            self.angle += delta;
         }
+    }
 
-        // Calculate average normal velocity for equal mass collision
-        let avg_n_vel = (n_vel_self.abs() + n_vel_other.abs()) / 2.0;
-        let total_restitution = self.restitution + other.restitution;
-        let total_friction = self.friction + other.friction;
-
-        // Calculate new velocity components
-        // Normal component: reversed for both balls (equal and opposite)
-        let x_n = avg_n_vel * nv[0] * total_restitution;
-        // Tangent component: preserved but reduced by friction
-        let x_t_self = t_vel_self * tv[0] * (1.0 - total_friction);
-        let x_t_other = t_vel_other * tv[0] * (1.0 - total_friction);
-        let y_n = avg_n_vel * nv[1] * total_restitution;
-        let y_t_self = t_vel_self * tv[1] * (1.0 - total_friction);
-        let y_t_other = t_vel_other * tv[1] * (1.0 - total_friction);
-
-        // Apply new velocities (normal components are opposite for each ball)
-        self.velocity = [x_n + x_t_self, y_n + y_t_self];
-        other.velocity = [-x_n + x_t_other, -y_n + y_t_other];
-
-        // Resolve penetration by pushing balls apart equally
-        let penetration = min_dist - dist.abs();
-        if penetration > 0.0 {
-            // Each ball moves half the penetration distance
-            let new_x_self = self.position[0] + nv[0] * penetration / 2.0;
-            let new_y_self = self.position[1] + nv[1] * penetration / 2.0;
-            let new_x_other = other.position[0] - nv[0] * penetration / 2.0;
-            let new_y_other = other.position[1] - nv[1] * penetration / 2.0;
-            self.position = [new_x_self, new_y_self];
-            other.position = [new_x_other, new_y_other];
+    fn material(&self) -> Material {
+        Material {
+            friction: self.friction,
+            restitution: self.restitution,
+            friction_combine: self.friction_combine,
+            restitution_combine: self.restitution_combine,
+            id: self.material_id,
         }
     }
+
+    fn filter(&self) -> CollisionFilter {
+        self.filter
+    }
+
+    fn shape(&self) -> Shape {
+        Shape::Circle { center: self.position, radius: self.radius as f32 }
+    }
+
+    fn aabb(&self) -> (Vec2, Vec2) {
+        let r = Vec2::new(self.radius as f32, self.radius as f32);
+        (self.position - r, self.position + r)
+    }
 }
@@ -0,0 +1,735 @@
+use crate::arena::{BallHandle, Handle, JointHandle, PrismaticHandle, RevoluteHandle};
+use crate::body::RigidBody;
+use crate::color::Color;
+use crate::grid::Grid;
+use crate::items::PhysItem;
+use crate::library::Vec2;
+#[cfg(feature = "sdl")]
+use crate::items::to_abgr;
+#[cfg(feature = "sdl")]
+use sdl2::render::{Canvas, RenderTarget};
+#[cfg(feature = "sdl")]
+use sdl2::gfx::primitives::DrawRenderer;
+
+/// What the second end of a [`Joint`] is attached to.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum JointTarget {
+    /// The center of another ball
+    Ball(BallHandle),
+    /// A fixed point in the world [x, y]
+    Anchor(Vec2),
+}
+
+impl From<BallHandle> for JointTarget {
+    fn from(ball: BallHandle) -> JointTarget {
+        JointTarget::Ball(ball)
+    }
+}
+
+impl From<Vec2> for JointTarget {
+    fn from(point: Vec2) -> JointTarget {
+        JointTarget::Anchor(point)
+    }
+}
+
+impl From<[f32; 2]> for JointTarget {
+    fn from(point: [f32; 2]) -> JointTarget {
+        JointTarget::Anchor(point.into())
+    }
+}
+
+/// How a [`Joint`] constrains the distance between its two ends.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum JointKind {
+    /// A rigid rod keeping the ends exactly `length` apart
+    Distance,
+    /// A damped spring pulling the ends towards `length` apart
+    Spring {
+        /// Natural frequency of the spring in Hz (higher = stiffer); the stiffness is
+        /// derived from the masses, so the same frequency behaves alike for any ball
+        frequency: f32,
+        /// Damping ratio (0 = no damping, 1 = critically damped)
+        damping_ratio: f32,
+    },
+    /// A rope keeping the ends at most `length` apart, but letting them come closer
+    Rope,
+}
+
+/// A constraint between a ball and another ball or a fixed point in the world.
+///
+/// Joints connect the centers of the balls, so they never make a ball spin. They are
+/// solved by the [`ContactSolver`](crate::ContactSolver) in the same iterations as
+/// the contacts, which lets pendulums, chains and Newton's cradles settle together
+/// with the collisions between their balls. Two balls joined to each other do not
+/// collide unless [`collide_connected`](Joint::collide_connected) is set.
+pub struct Joint {
+    /// Handle of this joint in its grid (dangling until the joint is added)
+    pub id: JointHandle,
+    /// Ball at the first end of the joint
+    pub a: BallHandle,
+    /// Ball or point at the second end of the joint
+    pub b: JointTarget,
+    /// How the joint constrains the distance between its ends
+    pub kind: JointKind,
+    /// Rod length, spring rest length or maximum rope length in pixels
+    pub length: f32,
+    /// Whether two joined balls still collide with each other (default `false`),
+    /// read when the joint is added
+    pub collide_connected: bool,
+    /// Color used to render the joint
+    pub color: Color,
+    /// Impulse applied along the joint in the last step, positive when pushing the
+    /// ends apart and negative when pulling them together (reused to warm start)
+    pub impulse: f32,
+}
+
+impl Joint {
+    /// Creates a new joint.
+    ///
+    /// # Arguments
+    ///
+    /// * `a` - Ball at the first end
+    /// * `b` - Ball or world point at the second end
+    /// * `kind` - How the joint constrains the distance between its ends
+    /// * `length` - Rod length, spring rest length or maximum rope length in pixels
+    /// * `color` - Optional color (default: GRAY)
+    ///
+    /// # Returns
+    ///
+    /// A new Joint instance
+    pub fn new(a: BallHandle, b: impl Into<JointTarget>, kind: JointKind, length: f32, color: Option<Color>) -> Joint {
+        Joint {
+            id: Handle::DANGLING,
+            a,
+            b: b.into(),
+            kind,
+            length,
+            collide_connected: false,
+            color: color.unwrap_or(Color::GRAY),
+            impulse: 0.0,
+        }
+    }
+
+    /// Creates a rigid rod keeping a ball at a fixed distance from a ball or point.
+    ///
+    /// # Arguments
+    ///
+    /// * `a` - Ball at the first end
+    /// * `b` - Ball or world point at the second end
+    /// * `length` - Length of the rod in pixels
+    pub fn distance(a: BallHandle, b: impl Into<JointTarget>, length: f32) -> Joint {
+        Joint::new(a, b, JointKind::Distance, length, None)
+    }
+
+    /// Creates a damped spring between a ball and a ball or point.
+    ///
+    /// # Arguments
+    ///
+    /// * `a` - Ball at the first end
+    /// * `b` - Ball or world point at the second end
+    /// * `rest_length` - Length at which the spring exerts no force, in pixels
+    /// * `frequency` - Natural frequency of the spring in Hz
+    /// * `damping_ratio` - Damping ratio (0 = no damping, 1 = critically damped)
+    pub fn spring(a: BallHandle, b: impl Into<JointTarget>, rest_length: f32, frequency: f32, damping_ratio: f32) -> Joint {
+        Joint::new(a, b, JointKind::Spring { frequency, damping_ratio }, rest_length, None)
+    }
+
+    /// Creates a rope keeping a ball within a maximum distance of a ball or point.
+    ///
+    /// # Arguments
+    ///
+    /// * `a` - Ball at the first end
+    /// * `b` - Ball or world point at the second end
+    /// * `max_length` - Length of the rope in pixels
+    pub fn rope(a: BallHandle, b: impl Into<JointTarget>, max_length: f32) -> Joint {
+        Joint::new(a, b, JointKind::Rope, max_length, None)
+    }
+
+    /// Returns whether a ball is at either end of the joint.
+    ///
+    /// # Arguments
+    ///
+    /// * `ball` - The ball to look for
+    pub fn involves(&self, ball: BallHandle) -> bool {
+        self.a == ball || self.b == JointTarget::Ball(ball)
+    }
+
+    /// Draws the joint as a line between its ends.
+    ///
+    /// # Arguments
+    ///
+    /// * `canvas` - The SDL2 canvas to draw on
+    /// * `a` - Position of the first end [x, y]
+    /// * `b` - Position of the second end [x, y]
+    #[cfg(feature = "sdl")]
+    pub fn draw<T: RenderTarget>(&self, canvas: &mut Canvas<T>, a: Vec2, b: Vec2) {
+        let _ = canvas.line(a.x as i16, a.y as i16, b.x as i16, b.y as i16, to_abgr(self.color));
+    }
+}
+
+/// A motor driving a [`RevoluteJoint`] or [`PrismaticJoint`] at a target speed.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct JointMotor {
+    /// Target speed, in radians per second for hinges (positive = clockwise on
+    /// screen) or pixels per second along the axis for sliders
+    pub speed: f32,
+    /// Maximum torque (hinges) or force (sliders) the motor can apply to reach the speed
+    pub max_force: f32,
+}
+
+impl JointMotor {
+    /// Creates a new joint motor.
+    ///
+    /// # Arguments
+    ///
+    /// * `speed` - Target speed in radians or pixels per second
+    /// * `max_force` - Maximum torque or force the motor can apply
+    ///
+    /// # Returns
+    ///
+    /// A new JointMotor instance
+    pub fn new(speed: f32, max_force: f32) -> JointMotor {
+        JointMotor { speed, max_force }
+    }
+}
+
+/// A hinge pinning a point of body `a` to body `b` or to a fixed point in the world.
+///
+/// The bodies can rotate freely about the shared point, unless the angle of `a`
+/// relative to `b` is limited or driven by a motor. A flipper is a capsule hinged to
+/// the world with a motor and limits; a seesaw is a polygon hinged to the world.
+pub struct RevoluteJoint {
+    /// Handle of this joint in its grid (dangling until the joint is added)
+    pub id: RevoluteHandle,
+    /// Body hinged at the anchor
+    pub a: PhysItem,
+    /// Body `a` is hinged to, or `None` for the world
+    pub b: Option<PhysItem>,
+    /// World point the joint was created at [x, y]
+    pub anchor: Vec2,
+    /// Anchor relative to the center and orientation of `a` (set when the joint is added)
+    pub local_anchor_a: Vec2,
+    /// Anchor relative to the center and orientation of `b`, or in the world for no
+    /// `b` (set when the joint is added)
+    pub local_anchor_b: Vec2,
+    /// Angle of `a` relative to `b` when the joint was added
+    pub reference_angle: f32,
+    /// Allowed range (lower, upper) of the joint angle in radians, or `None` for no limits
+    pub limits: Option<(f32, f32)>,
+    /// Motor driving the joint angle, or `None` to let it swing freely
+    pub motor: Option<JointMotor>,
+    /// Whether `a` and `b` still collide with each other (default `false`), read
+    /// when the joint is added
+    pub collide_connected: bool,
+    /// Color used to render the joint
+    pub color: Color,
+    /// Impulse that held the anchors together in the last step, applied to `b`
+    pub impulse: Vec2,
+    /// Angular impulse the motor applied in the last step
+    pub motor_impulse: f32,
+    /// Angular impulses the lower and upper limits applied in the last step
+    pub limit_impulses: [f32; 2],
+}
+
+impl RevoluteJoint {
+    /// Creates a new hinge.
+    ///
+    /// # Arguments
+    ///
+    /// * `a` - Body hinged at the anchor
+    /// * `b` - Optional body `a` is hinged to (default: the world)
+    /// * `anchor` - World point the bodies rotate about [x, y]
+    /// * `limits` - Optional range (lower, upper) of the joint angle in radians,
+    ///   measured from the angle when the joint is added (default: no limits)
+    /// * `motor` - Optional motor driving the joint angle (default: none)
+    ///
+    /// # Returns
+    ///
+    /// A new RevoluteJoint instance
+    pub fn new(a: PhysItem, b: Option<PhysItem>, anchor: Vec2, limits: Option<(f32, f32)>, motor: Option<JointMotor>) -> RevoluteJoint {
+        RevoluteJoint {
+            id: Handle::DANGLING,
+            a,
+            b,
+            anchor,
+            local_anchor_a: anchor,
+            local_anchor_b: anchor,
+            reference_angle: 0.0,
+            limits,
+            motor,
+            collide_connected: false,
+            color: Color::WHITE,
+            impulse: Vec2::ZERO,
+            motor_impulse: 0.0,
+            limit_impulses: [0.0, 0.0],
+        }
+    }
+
+    /// Returns whether a body is at either end of the joint.
+    ///
+    /// # Arguments
+    ///
+    /// * `item` - The body to look for
+    pub fn involves(&self, item: PhysItem) -> bool {
+        self.a == item || self.b == Some(item)
+    }
+
+    /// Returns the joint angle: the rotation of `a` relative to `b` since the joint was
+    /// added, in radians.
+    ///
+    /// # Arguments
+    ///
+    /// * `grid` - The grid holding the bodies
+    ///
+    /// # Returns
+    ///
+    /// The angle, or `None` if one of the bodies was removed
+    pub fn angle(&self, grid: &Grid) -> Option<f32> {
+        let a = grid.body(self.a)?;
+        let b = end_body(grid, self.b)?;
+        Some(a.angle() - body_angle(b) - self.reference_angle)
+    }
+
+    /// Returns the current world positions of the anchor on `a` and on `b`.
+    ///
+    /// The two only drift apart while the joint is being pulled apart.
+    ///
+    /// # Arguments
+    ///
+    /// * `grid` - The grid holding the bodies
+    pub fn anchors(&self, grid: &Grid) -> Option<(Vec2, Vec2)> {
+        let a = grid.body(self.a)?;
+        let b = end_body(grid, self.b)?;
+        Some((world_point(Some(a), self.local_anchor_a), world_point(b, self.local_anchor_b)))
+    }
+
+    /// Draws the joint as a small circle around the anchor.
+    ///
+    /// # Arguments
+    ///
+    /// * `canvas` - The SDL2 canvas to draw on
+    /// * `anchor` - Current position of the anchor [x, y]
+    #[cfg(feature = "sdl")]
+    pub fn draw<T: RenderTarget>(&self, canvas: &mut Canvas<T>, anchor: Vec2) {
+        let _ = canvas.circle(anchor.x as i16, anchor.y as i16, 3, to_abgr(self.color));
+    }
+}
+
+/// A slider letting body `a` move along an axis fixed to body `b` or to the world.
+///
+/// The bodies cannot rotate relative to each other, and `a` cannot leave the line
+/// through the anchor along the axis. How far it moved along the axis can be limited
+/// and driven by a motor, which makes a piston.
+pub struct PrismaticJoint {
+    /// Handle of this joint in its grid (dangling until the joint is added)
+    pub id: PrismaticHandle,
+    /// Body sliding along the axis
+    pub a: PhysItem,
+    /// Body the axis is fixed to, or `None` for the world
+    pub b: Option<PhysItem>,
+    /// World point the joint was created at [x, y]
+    pub anchor: Vec2,
+    /// Unit direction `a` slides along, in the world when the joint was created
+    pub axis: Vec2,
+    /// Anchor relative to the center and orientation of `a` (set when the joint is added)
+    pub local_anchor_a: Vec2,
+    /// Anchor relative to the center and orientation of `b`, or in the world for no
+    /// `b` (set when the joint is added)
+    pub local_anchor_b: Vec2,
+    /// Axis relative to the orientation of `b`, or in the world for no `b` (set when
+    /// the joint is added)
+    pub local_axis: Vec2,
+    /// Angle of `a` relative to `b` when the joint was added
+    pub reference_angle: f32,
+    /// Allowed range (lower, upper) of the translation along the axis in pixels, or
+    /// `None` for no limits
+    pub limits: Option<(f32, f32)>,
+    /// Motor driving the translation, or `None` to let `a` slide freely
+    pub motor: Option<JointMotor>,
+    /// Whether `a` and `b` still collide with each other (default `false`), read
+    /// when the joint is added
+    pub collide_connected: bool,
+    /// Color used to render the joint
+    pub color: Color,
+    /// Impulse that kept `a` on the axis in the last step, applied to `a` across the axis
+    pub impulse: f32,
+    /// Angular impulse that kept the bodies from rotating in the last step, applied to `a`
+    pub angular_impulse: f32,
+    /// Impulse the motor applied along the axis in the last step
+    pub motor_impulse: f32,
+    /// Impulses the lower and upper limits applied in the last step
+    pub limit_impulses: [f32; 2],
+}
+
+impl PrismaticJoint {
+    /// Creates a new slider.
+    ///
+    /// # Arguments
+    ///
+    /// * `a` - Body sliding along the axis
+    /// * `b` - Optional body the axis is fixed to (default: the world)
+    /// * `anchor` - World point on the axis [x, y]
+    /// * `axis` - Direction `a` slides along [x, y] (normalized)
+    /// * `limits` - Optional range (lower, upper) of the translation along the axis
+    ///   in pixels, measured from where `a` is when the joint is added (default: no limits)
+    /// * `motor` - Optional motor driving the translation (default: none)
+    ///
+    /// # Returns
+    ///
+    /// A new PrismaticJoint instance
+    pub fn new(
+        a: PhysItem,
+        b: Option<PhysItem>,
+        anchor: Vec2,
+        axis: Vec2,
+        limits: Option<(f32, f32)>,
+        motor: Option<JointMotor>,
+    ) -> PrismaticJoint {
+        let axis = axis.normalize();
+        PrismaticJoint {
+            id: Handle::DANGLING,
+            a,
+            b,
+            anchor,
+            axis,
+            local_anchor_a: anchor,
+            local_anchor_b: anchor,
+            local_axis: axis,
+            reference_angle: 0.0,
+            limits,
+            motor,
+            collide_connected: false,
+            color: Color::WHITE,
+            impulse: 0.0,
+            angular_impulse: 0.0,
+            motor_impulse: 0.0,
+            limit_impulses: [0.0, 0.0],
+        }
+    }
+
+    /// Returns whether a body is at either end of the joint.
+    ///
+    /// # Arguments
+    ///
+    /// * `item` - The body to look for
+    pub fn involves(&self, item: PhysItem) -> bool {
+        self.a == item || self.b == Some(item)
+    }
+
+    /// Returns how far `a` has moved along the axis since the joint was added, in pixels.
+    ///
+    /// # Arguments
+    ///
+    /// * `grid` - The grid holding the bodies
+    ///
+    /// # Returns
+    ///
+    /// The translation, or `None` if one of the bodies was removed
+    pub fn translation(&self, grid: &Grid) -> Option<f32> {
+        let (a, b) = self.anchors(grid)?;
+        let axis = self.local_axis.rotate(body_angle(end_body(grid, self.b)?));
+        Some((a - b).dot(axis))
+    }
+
+    /// Returns the current world positions of the anchor on `a` and on `b`.
+    ///
+    /// # Arguments
+    ///
+    /// * `grid` - The grid holding the bodies
+    pub fn anchors(&self, grid: &Grid) -> Option<(Vec2, Vec2)> {
+        let a = grid.body(self.a)?;
+        let b = end_body(grid, self.b)?;
+        Some((world_point(Some(a), self.local_anchor_a), world_point(b, self.local_anchor_b)))
+    }
+
+    /// Draws the joint as a line from the anchor on `b` to the anchor on `a`.
+    ///
+    /// # Arguments
+    ///
+    /// * `canvas` - The SDL2 canvas to draw on
+    /// * `a` - Current position of the anchor on `a` [x, y]
+    /// * `b` - Current position of the anchor on `b` [x, y]
+    #[cfg(feature = "sdl")]
+    pub fn draw<T: RenderTarget>(&self, canvas: &mut Canvas<T>, a: Vec2, b: Vec2) {
+        let color = to_abgr(self.color);
+        let _ = canvas.line(b.x as i16, b.y as i16, a.x as i16, a.y as i16, color);
+        let _ = canvas.circle(a.x as i16, a.y as i16, 3, color);
+    }
+}
+
+/// Returns the body at one end of a hinge or slider: `Some(None)` for the world, or
+/// `None` if the body was removed.
+pub(crate) fn end_body(grid: &Grid, item: Option<PhysItem>) -> Option<Option<&dyn RigidBody>> {
+    match item {
+        Some(item) => grid.body(item).map(Some),
+        None => Some(None),
+    }
+}
+
+/// Returns the orientation of a joint's body, or 0.0 for the world.
+pub(crate) fn body_angle(body: Option<&dyn RigidBody>) -> f32 {
+    body.map_or(0.0, |body| body.angle())
+}
+
+/// Converts a point relative to a body's center and orientation into the world.
+pub(crate) fn world_point(body: Option<&dyn RigidBody>, local: Vec2) -> Vec2 {
+    match body {
+        Some(body) => body.position() + local.rotate(body.angle()),
+        None => local,
+    }
+}
+
+/// Converts a world point into one relative to a body's center and orientation.
+pub(crate) fn local_point(body: Option<&dyn RigidBody>, point: Vec2) -> Vec2 {
+    match body {
+        Some(body) => (point - body.position()).rotate(-body.angle()),
+        None => point,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::Grid;
+    use crate::items::{Ball, GRAVITY};
+    use crate::polygon::Polygon;
+    use crate::world::World;
+
+    const DT: f32 = 1.0 / 60.0;
+
+    #[test]
+    fn pendulum_keeps_its_length() {
+        let mut world = World::new(Grid::new(50, 50, 800, 800));
+        let pivot = Vec2::new(400.0, 100.0);
+        // Released level with the pivot, so it swings through the bottom at full speed
+        let ball = world.add_ball(Ball::new(Vec2::new(600.0, 100.0), None, None, None, None, None, None));
+        world.add_joint(Joint::distance(ball, pivot, 200.0));
+        let mut lowest = pivot.y;
+        for _ in 0..180 {
+            world.step(DT);
+            let position = world.grid().ball(ball).unwrap().position;
+            let length = (position - pivot).length();
+            assert!((length - 200.0).abs() < 2.0, "length = {length}");
+            lowest = lowest.max(position.y);
+        }
+        assert!(lowest > 295.0, "lowest y = {lowest}");
+    }
+
+    #[test]
+    fn slack_rope_lets_the_ball_fall_until_taut() {
+        let mut world = World::new(Grid::new(50, 50, 800, 800));
+        let pivot = Vec2::new(400.0, 100.0);
+        let ball = world.add_ball(Ball::new(Vec2::new(400.0, 150.0), None, None, None, None, None, None));
+        world.add_joint(Joint::rope(ball, pivot, 200.0));
+        for _ in 0..30 {
+            world.step(DT);
+        }
+        // Half a second of free fall, the rope still slack
+        let y = world.grid().ball(ball).unwrap().position.y;
+        assert!((y - (150.0 + GRAVITY.y / 8.0)).abs() < 3.0, "y = {y}");
+        for _ in 0..180 {
+            world.step(DT);
+            let length = (world.grid().ball(ball).unwrap().position - pivot).length();
+            assert!(length < 202.0, "length = {length}");
+        }
+        let y = world.grid().ball(ball).unwrap().position.y;
+        assert!((y - 300.0).abs() < 2.0, "y = {y}");
+    }
+
+    #[test]
+    fn damped_spring_settles_at_its_rest_length() {
+        let pivot = Vec2::new(400.0, 400.0);
+        // Stretched 100 px beyond the rest length, once without damping and once critically damped
+        let stretch_after = |damping_ratio: f32| {
+            let mut world = World::new(Grid::new(50, 50, 800, 800));
+            world.gravity = Vec2::ZERO;
+            let ball = world.add_ball(Ball::new(Vec2::new(600.0, 400.0), None, None, None, None, None, None));
+            world.add_joint(Joint::spring(ball, pivot, 100.0, 1.0, damping_ratio));
+            for _ in 0..120 {
+                world.step(DT);
+            }
+            let mut largest: f32 = 0.0;
+            for _ in 0..60 {
+                world.step(DT);
+                let length = (world.grid().ball(ball).unwrap().position - pivot).length();
+                largest = largest.max((length - 100.0).abs());
+            }
+            largest
+        };
+        let undamped = stretch_after(0.0);
+        let damped = stretch_after(1.0);
+        assert!(undamped > 50.0, "undamped stretch = {undamped}");
+        assert!(damped < 2.0, "damped stretch = {damped}");
+    }
+
+    #[test]
+    fn joints_need_live_balls() {
+        let mut world = World::new(Grid::new(50, 50, 800, 800));
+        let ball = || Ball::new(Vec2::new(400.0, 400.0), None, None, None, None, None, None);
+        let live = world.add_ball(ball());
+        let removed = world.add_ball(ball());
+        world.remove_ball(removed);
+        assert!(world.add_joint(Joint::distance(removed, Vec2::new(400.0, 300.0), 100.0)).is_none());
+        assert!(world.add_joint(Joint::rope(live, removed, 100.0)).is_none());
+        assert_eq!(world.grid().joints().count(), 0);
+        assert!(world.add_joint(Joint::rope(live, Vec2::new(400.0, 300.0), 100.0)).is_some());
+    }
+
+    #[test]
+    fn revolute_limit_holds() {
+        let mut world = World::new(Grid::new(50, 50, 800, 800));
+        // A bar hinged to the world at its left end, free to droop by at most 0.5 rad
+        let bar = Polygon::new(Vec2::new(450.0, 400.0), Polygon::rectangle(100.0, 10.0), None, None, None, None, None);
+        let bar = PhysItem::Polygon(world.add_polygon(bar));
+        let hinge = world.add_revolute_joint(RevoluteJoint::new(bar, None, Vec2::new(400.0, 400.0), Some((-0.5, 0.5)), None)).unwrap();
+        for _ in 0..180 {
+            world.step(DT);
+            let angle = world.grid().revolute_joint(hinge).unwrap().angle(world.grid()).unwrap();
+            assert!(angle < 0.52, "angle = {angle}");
+        }
+        let joint = world.grid().revolute_joint(hinge).unwrap();
+        let angle = joint.angle(world.grid()).unwrap();
+        assert!(angle > 0.48, "angle = {angle}");
+        let (on_bar, on_world) = joint.anchors(world.grid()).unwrap();
+        assert!((on_bar - on_world).length() < 1.0, "anchors {on_bar:?} and {on_world:?}");
+    }
+
+    #[test]
+    fn revolute_motor_turns_at_its_speed() {
+        let mut world = World::new(Grid::new(50, 50, 800, 800));
+        world.gravity = Vec2::ZERO;
+        let bar = Polygon::new(Vec2::new(400.0, 400.0), Polygon::rectangle(100.0, 10.0), None, None, None, None, None);
+        let bar = world.add_polygon(bar);
+        let motor = JointMotor::new(2.0, 1e9);
+        world.add_revolute_joint(RevoluteJoint::new(PhysItem::Polygon(bar), None, Vec2::new(400.0, 400.0), None, Some(motor)));
+        for _ in 0..30 {
+            world.step(DT);
+        }
+        let bar = world.grid().polygon(bar).unwrap();
+        assert!((bar.angular_velocity - 2.0).abs() < 0.01, "angular velocity = {}", bar.angular_velocity);
+        assert!((bar.position - Vec2::new(400.0, 400.0)).length() < 0.5, "center = {:?}", bar.position);
+    }
+
+    #[test]
+    fn hinge_between_two_bodies_keeps_them_together() {
+        let mut world = World::new(Grid::new(50, 50, 800, 800));
+        world.gravity = Vec2::ZERO;
+        // Two bars end to end, hinged where they meet; only the left one is moving
+        let left = Polygon::new(Vec2::new(350.0, 400.0), Polygon::rectangle(100.0, 10.0), Some(Vec2::new(0.0, 100.0)), None, None, None, None);
+        let left = world.add_polygon(left);
+        let right = Polygon::new(Vec2::new(450.0, 400.0), Polygon::rectangle(100.0, 10.0), None, None, None, None, None);
+        let right = world.add_polygon(right);
+        let (a, b) = (PhysItem::Polygon(left), PhysItem::Polygon(right));
+        let hinge = world.add_revolute_joint(RevoluteJoint::new(a, Some(b), Vec2::new(400.0, 400.0), None, None)).unwrap();
+        let momentum = |world: &World| {
+            world.grid().polygons().fold(Vec2::ZERO, |total, bar| total + bar.velocity * bar.mass)
+        };
+        let start = momentum(&world);
+        for _ in 0..60 {
+            world.step(DT);
+            let (on_left, on_right) = world.grid().revolute_joint(hinge).unwrap().anchors(world.grid()).unwrap();
+            assert!((on_left - on_right).length() < 2.0, "anchors {on_left:?} and {on_right:?}");
+        }
+        // The gap opened by the left bar's initial velocity has been closed again
+        let (on_left, on_right) = world.grid().revolute_joint(hinge).unwrap().anchors(world.grid()).unwrap();
+        assert!((on_left - on_right).length() < 0.1, "anchors {on_left:?} and {on_right:?}");
+        // The left bar dragged the right one along, and the pair kept its momentum
+        let (left, right) = (world.grid().polygon(left).unwrap(), world.grid().polygon(right).unwrap());
+        assert!(right.velocity.y > 10.0, "right v = {:?}", right.velocity);
+        assert!(left.angular_velocity.abs() > 0.1 && right.angular_velocity.abs() > 0.1);
+        assert!((momentum(&world) - start).length() < 0.01 * start.length(), "momentum = {:?}", momentum(&world));
+    }
+
+    #[test]
+    fn hinged_bodies_only_collide_when_asked_to() {
+        // Two boxes overlapping around the hinge between them
+        let contacts_with = |collide_connected: bool| {
+            let mut grid = Grid::new(50, 50, 800, 800);
+            let a = grid.add_polygon(Polygon::new(Vec2::new(390.0, 400.0), Polygon::rectangle(40.0, 40.0), None, None, None, None, None));
+            let b = grid.add_polygon(Polygon::new(Vec2::new(410.0, 400.0), Polygon::rectangle(40.0, 40.0), None, None, None, None, None));
+            let mut hinge = RevoluteJoint::new(PhysItem::Polygon(a), Some(PhysItem::Polygon(b)), Vec2::new(400.0, 400.0), None, None);
+            hinge.collide_connected = collide_connected;
+            grid.add_revolute_joint(hinge);
+            grid.find_contacts().len()
+        };
+        assert_eq!(contacts_with(false), 0);
+        assert_eq!(contacts_with(true), 1);
+    }
+
+    #[test]
+    fn joined_balls_only_collide_when_asked_to() {
+        // Two overlapping balls, once joined to each other and once to the same point
+        let contacts_with = |joint: fn(BallHandle, BallHandle) -> Joint| {
+            let mut grid = Grid::new(50, 50, 800, 800);
+            let a = grid.add_ball(Ball::new(Vec2::new(395.0, 400.0), None, None, None, None, None, None));
+            let b = grid.add_ball(Ball::new(Vec2::new(405.0, 400.0), None, None, None, None, None, None));
+            grid.add_joint(joint(a, b));
+            grid.find_contacts().len()
+        };
+        assert_eq!(contacts_with(|a, b| Joint::distance(a, b, 10.0)), 0);
+        assert_eq!(contacts_with(|a, b| Joint { collide_connected: true, ..Joint::spring(a, b, 10.0, 1.0, 0.5) }), 1);
+        assert_eq!(contacts_with(|a, _| Joint::rope(a, Vec2::new(400.0, 300.0), 100.0)), 1);
+        // Removing the joint lets them collide again
+        let mut grid = Grid::new(50, 50, 800, 800);
+        let a = grid.add_ball(Ball::new(Vec2::new(395.0, 400.0), None, None, None, None, None, None));
+        let b = grid.add_ball(Ball::new(Vec2::new(405.0, 400.0), None, None, None, None, None, None));
+        let rope = grid.add_joint(Joint::rope(a, b, 20.0)).unwrap();
+        assert_eq!(grid.find_contacts().len(), 0);
+        grid.remove_joint(rope);
+        assert_eq!(grid.find_contacts().len(), 1);
+    }
+
+    #[test]
+    fn bodies_collide_again_once_their_last_hinge_or_slider_is_gone() {
+        let mut grid = Grid::new(50, 50, 800, 800);
+        let a = grid.add_polygon(Polygon::new(Vec2::new(390.0, 400.0), Polygon::rectangle(40.0, 40.0), None, None, None, None, None));
+        let b = grid.add_polygon(Polygon::new(Vec2::new(410.0, 400.0), Polygon::rectangle(40.0, 40.0), None, None, None, None, None));
+        let (a, b) = (PhysItem::Polygon(a), PhysItem::Polygon(b));
+        let anchor = Vec2::new(400.0, 400.0);
+        let hinge = grid.add_revolute_joint(RevoluteJoint::new(a, Some(b), anchor, None, None)).unwrap();
+        let slider = grid.add_prismatic_joint(PrismaticJoint::new(b, Some(a), anchor, Vec2::new(1.0, 0.0), None, None)).unwrap();
+        assert_eq!(grid.find_contacts().len(), 0);
+        // The slider still keeps them apart
+        grid.remove_revolute_joint(hinge);
+        assert_eq!(grid.find_contacts().len(), 0);
+        grid.remove_prismatic_joint(slider);
+        assert_eq!(grid.find_contacts().len(), 1);
+    }
+
+    #[test]
+    fn hinges_and_sliders_need_live_bodies() {
+        let mut world = World::new(Grid::new(50, 50, 800, 800));
+        let square = || Polygon::new(Vec2::new(400.0, 400.0), Polygon::rectangle(20.0, 20.0), None, None, None, None, None);
+        let live = PhysItem::Polygon(world.add_polygon(square()));
+        let removed = world.add_polygon(square());
+        world.remove_polygon(removed);
+        let removed = PhysItem::Polygon(removed);
+        let anchor = Vec2::new(400.0, 400.0);
+        assert!(world.add_revolute_joint(RevoluteJoint::new(removed, None, anchor, None, None)).is_none());
+        assert!(world.add_revolute_joint(RevoluteJoint::new(live, Some(removed), anchor, None, None)).is_none());
+        let axis = Vec2::new(1.0, 0.0);
+        assert!(world.add_prismatic_joint(PrismaticJoint::new(removed, None, anchor, axis, None, None)).is_none());
+        assert!(world.add_prismatic_joint(PrismaticJoint::new(live, Some(removed), anchor, axis, None, None)).is_none());
+        assert_eq!(world.grid().revolute_joints().count() + world.grid().prismatic_joints().count(), 0);
+        assert!(world.add_revolute_joint(RevoluteJoint::new(live, None, anchor, None, None)).is_some());
+    }
+
+    #[test]
+    fn prismatic_limit_stops_a_falling_box() {
+        let mut world = World::new(Grid::new(50, 50, 800, 800));
+        // A box free to drop 100 px straight down from where it starts
+        let block = Polygon::new(Vec2::new(400.0, 200.0), Polygon::rectangle(40.0, 40.0), None, None, None, None, None);
+        let block = world.add_polygon(block);
+        let slider = PrismaticJoint::new(PhysItem::Polygon(block), None, Vec2::new(400.0, 200.0), Vec2::new(0.0, 1.0), Some((0.0, 100.0)), None);
+        let slider = world.add_prismatic_joint(slider).unwrap();
+        for _ in 0..120 {
+            world.step(DT);
+            let translation = world.grid().prismatic_joint(slider).unwrap().translation(world.grid()).unwrap();
+            assert!(translation < 102.0, "translation = {translation}");
+        }
+        let translation = world.grid().prismatic_joint(slider).unwrap().translation(world.grid()).unwrap();
+        assert!((translation - 100.0).abs() < 2.0, "translation = {translation}");
+        let block = world.grid().polygon(block).unwrap();
+        assert!((block.position.x - 400.0).abs() < 0.5 && block.angle.abs() < 0.01, "{:?} at {}", block.position, block.angle);
+    }
+}
@@ -1,8 +1,218 @@
+//! rphys (Rust Physics) - a lightweight 2D physics library.
+//!
+//! The crate exposes the physics objects ([`Ball`], [`Wall`], [`Polygon`], [`Capsule`]),
+//! the spatial partitioning [`Grid`] that owns them (addressed by generational
+//! [`Handle`]s), the headless [`World`] that steps the simulation (at a
+//! [`FixedTimestep`] if desired), and the [`library`] module of vector mathematics
+//! they are built on. The Plinko demo in `main.rs` is one consumer of this API.
+
+pub mod arena;
+pub mod body;
+pub mod capsule;
+pub mod cleanup;
+pub mod collision;
+pub mod color;
+pub mod contact;
+pub mod field;
+pub mod filter;
+pub mod integrator;
+pub mod items;
+pub mod joint;
+pub mod material;
+pub mod motion;
+pub mod polygon;
+pub mod sensor;
+pub mod solver;
+pub mod timestep;
+pub mod grid;
+pub mod world;
+
+pub use arena::{
+    Arena, BallHandle, CapsuleHandle, Handle, JointHandle, PolygonHandle, PrismaticHandle, RevoluteHandle, SensorHandle,
+    WallHandle,
+};
+pub use body::RigidBody;
+pub use capsule::Capsule;
+pub use cleanup::{CleanupPolicy, CleanupRule};
+pub use color::Color;
+pub use contact::{CachedPoint, Contact, ContactCache, ContactEvent, ContactEventKind};
+pub use field::{ForceField, WindRegion};
+pub use filter::CollisionFilter;
+pub use integrator::Integrator;
+pub use joint::{Joint, JointKind, JointMotor, JointTarget, PrismaticJoint, RevoluteJoint};
+pub use library::Vec2;
+pub use material::{CombineRule, Material, MaterialTable};
+pub use motion::WallMotion;
+pub use polygon::Polygon;
+pub use sensor::{Sensor, SensorEvent, SensorEventKind, SensorShape};
+pub use solver::ContactSolver;
+pub use timestep::FixedTimestep;
+pub use items::{Ball, Wall, PhysItem, DEFAULT_DENSITY, GRAVITY};
+pub use grid::{Grid, Section};
+pub use world::World;
+
 /// A library module containing 2D vector mathematics utilities.
 ///
-/// This module provides fundamental vector operations used throughout the physics engine,
-/// including dot products, magnitude calculations, normalization, and normal vector computation.
+/// This module provides the [`Vec2`] type used throughout the physics engine,
+/// along with array-based helpers for dot products, magnitude calculations, normalization,
+/// and normal vector computation.
 pub mod library {
+    use std::ops::{Add, AddAssign, Div, Mul, MulAssign, Neg, Sub, SubAssign};
+
+    /// A 2D vector with `f32` components.
+    ///
+    /// Used for positions, velocities and directions throughout the physics engine.
+    /// Converts to and from `[f32; 2]` so it can be mixed with the array-based
+    /// helper functions below.
+    #[derive(Clone, Copy, Debug, Default, PartialEq)]
+    pub struct Vec2 {
+        /// Horizontal component
+        pub x: f32,
+        /// Vertical component
+        pub y: f32,
+    }
+
+    impl Vec2 {
+        /// The zero vector [0.0, 0.0]
+        pub const ZERO: Vec2 = Vec2 { x: 0.0, y: 0.0 };
+
+        /// Creates a new vector from its components.
+        pub const fn new(x: f32, y: f32) -> Vec2 {
+            Vec2 { x, y }
+        }
+
+        /// Computes the dot product: `self.x * other.x + self.y * other.y`
+        pub fn dot(self, other: Vec2) -> f32 {
+            self.x * other.x + self.y * other.y
+        }
+
+        /// Computes the 2D cross product (the z component of the 3D cross product):
+        /// `self.x * other.y - self.y * other.x`
+        pub fn cross(self, other: Vec2) -> f32 {
+            self.x * other.y - self.y * other.x
+        }
+
+        /// Returns the squared length of the vector.
+        pub fn length_squared(self) -> f32 {
+            self.dot(self)
+        }
+
+        /// Returns the length (magnitude) of the vector.
+        pub fn length(self) -> f32 {
+            self.length_squared().sqrt()
+        }
+
+        /// Returns a unit vector in the same direction.
+        ///
+        /// Returns [`Vec2::ZERO`] if the vector has zero length to avoid division by zero.
+        pub fn normalize(self) -> Vec2 {
+            let len = self.length();
+            if len == 0.0 {
+                return Vec2::ZERO;
+            }
+            self / len
+        }
+
+        /// Returns the vector rotated 90 degrees counter-clockwise: (x, y) -> (-y, x)
+        pub fn perp(self) -> Vec2 {
+            Vec2::new(-self.y, self.x)
+        }
+
+        /// Returns the vector rotated by `angle` radians.
+        pub fn rotate(self, angle: f32) -> Vec2 {
+            let (sin, cos) = angle.sin_cos();
+            Vec2::new(self.x * cos - self.y * sin, self.x * sin + self.y * cos)
+        }
+
+        /// Linearly interpolates between `self` (t = 0) and `other` (t = 1).
+        pub fn lerp(self, other: Vec2, t: f32) -> Vec2 {
+            self + (other - self) * t
+        }
+
+        /// Projects the vector onto `other`.
+        ///
+        /// Returns [`Vec2::ZERO`] if `other` has zero length.
+        pub fn project_onto(self, other: Vec2) -> Vec2 {
+            let len_sq = other.length_squared();
+            if len_sq == 0.0 {
+                return Vec2::ZERO;
+            }
+            other * (self.dot(other) / len_sq)
+        }
+    }
+
+    impl From<[f32; 2]> for Vec2 {
+        fn from(vec: [f32; 2]) -> Vec2 {
+            Vec2::new(vec[0], vec[1])
+        }
+    }
+
+    impl From<Vec2> for [f32; 2] {
+        fn from(vec: Vec2) -> [f32; 2] {
+            [vec.x, vec.y]
+        }
+    }
+
+    impl Add for Vec2 {
+        type Output = Vec2;
+        fn add(self, other: Vec2) -> Vec2 {
+            Vec2::new(self.x + other.x, self.y + other.y)
+        }
+    }
+
+    impl AddAssign for Vec2 {
+        fn add_assign(&mut self, other: Vec2) {
+            *self = *self + other;
+        }
+    }
+
+    impl Sub for Vec2 {
+        type Output = Vec2;
+        fn sub(self, other: Vec2) -> Vec2 {
+            Vec2::new(self.x - other.x, self.y - other.y)
+        }
+    }
+
+    impl SubAssign for Vec2 {
+        fn sub_assign(&mut self, other: Vec2) {
+            *self = *self - other;
+        }
+    }
+
+    impl Mul<f32> for Vec2 {
+        type Output = Vec2;
+        fn mul(self, scalar: f32) -> Vec2 {
+            Vec2::new(self.x * scalar, self.y * scalar)
+        }
+    }
+
+    impl Mul<Vec2> for f32 {
+        type Output = Vec2;
+        fn mul(self, vec: Vec2) -> Vec2 {
+            vec * self
+        }
+    }
+
+    impl MulAssign<f32> for Vec2 {
+        fn mul_assign(&mut self, scalar: f32) {
+            *self = *self * scalar;
+        }
+    }
+
+    impl Div<f32> for Vec2 {
+        type Output = Vec2;
+        fn div(self, scalar: f32) -> Vec2 {
+            Vec2::new(self.x / scalar, self.y / scalar)
+        }
+    }
+
+    impl Neg for Vec2 {
+        type Output = Vec2;
+        fn neg(self) -> Vec2 {
+            Vec2::new(-self.x, -self.y)
+        }
+    }
+
     /// Computes the dot product of two 2D vectors.
     ///
     /// The dot product is calculated as: `vec1.x * vec2.x + vec1.y * vec2.y`
@@ -89,3 +299,51 @@ pub mod library {
         [-vec[1], vec[0]]
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::library::*;
+    use super::{Ball, Grid, Vec2, Wall};
+
+    #[test]
+    fn vector_helpers() {
+        assert_eq!(dot([1.0, 2.0], [3.0, 4.0]), 11.0);
+        assert_eq!(get_magnitude([3.0, 4.0]), 5.0);
+        assert_eq!(normalize([0.0, 5.0]), [0.0, 1.0]);
+        assert_eq!(normalize([0.0, 0.0]), [0.0, 0.0]);
+        assert_eq!(find_vector([1.0, 1.0], [4.0, 5.0]), [3.0, 4.0]);
+        assert_eq!(find_normal([0.0, 0.0], [2.0, 0.0]), [-0.0, 1.0]);
+    }
+
+    #[test]
+    fn vec2_operators() {
+        let a = Vec2::new(3.0, 4.0);
+        let b = Vec2::from([1.0, -2.0]);
+        assert_eq!(a + b, Vec2::new(4.0, 2.0));
+        assert_eq!(a - b, Vec2::new(2.0, 6.0));
+        assert_eq!(a * 2.0, Vec2::new(6.0, 8.0));
+        assert_eq!(a / 2.0, Vec2::new(1.5, 2.0));
+        assert_eq!(-a, Vec2::new(-3.0, -4.0));
+        assert_eq!(a.dot(b), -5.0);
+        assert_eq!(a.cross(b), -10.0);
+        assert_eq!(a.length(), 5.0);
+        assert_eq!(a.normalize(), Vec2::new(0.6, 0.8));
+        assert_eq!(Vec2::ZERO.normalize(), Vec2::ZERO);
+        assert_eq!(a.perp(), Vec2::new(-4.0, 3.0));
+        assert_eq!(a.lerp(b, 0.5), Vec2::new(2.0, 1.0));
+        assert_eq!(a.project_onto(Vec2::new(0.0, 2.0)), Vec2::new(0.0, 4.0));
+        assert_eq!(<[f32; 2]>::from(a), [3.0, 4.0]);
+        let turned = Vec2::new(1.0, 0.0).rotate(std::f32::consts::FRAC_PI_2);
+        assert!((turned - Vec2::new(0.0, 1.0)).length() < 1e-6);
+    }
+
+    #[test]
+    fn grid_counts_added_bodies() {
+        let mut grid = Grid::new(50, 50, 800, 800);
+        grid.add_ball(Ball::new(Vec2::new(100.0, 100.0), None, None, None, None, None, None));
+        grid.add_ball(Ball::new(Vec2::new(200.0, 100.0), None, None, None, None, None, None));
+        grid.add_wall(Wall::new(Vec2::new(0.0, 700.0), Vec2::new(800.0, 700.0), None, None, None, None));
+        assert_eq!(grid.ball_count(), 2);
+        assert_eq!(grid.wall_count(), 1);
+    }
+}
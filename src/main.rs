@@ -4,18 +4,12 @@
 //! and collect in boxes at the bottom. It uses a custom 2D physics engine with
 //! spatial partitioning for efficient collision detection.
 
-mod items;
-mod grid;
-
-use crate::items::{Ball, Wall};
-use crate::grid::Grid;
-use sdl2::pixels::Color;
+use rphys::{Ball, CleanupRule, Color, Grid, Sensor, SensorEventKind, SensorHandle, SensorShape, Vec2, Wall, World};
 use sdl2::event::Event;
 use sdl2::video::Window;
 use sdl2::keyboard::Keycode;
 use sdl2::render::{Canvas};
 use sdl2::ttf::Font;
-use rand;
 use std::time::{Duration, Instant};
 
 /// Window title displayed in the title bar
@@ -26,48 +20,81 @@ const WINDOW_WIDTH: u32 = 520;
 const WINDOW_HEIGHT: u32 = 800;
 /// Background color for the canvas
 const BACKGROUND: Color = Color::BLACK;
-/// Number of collision resolution iterations per frame
-const COLLISION_LOOPS: u32 = 20;
 /// Path to the font file used for rendering text
 const FONT_PATH: &str = "/usr/share/fonts/truetype/futuristic-font/Futuristic-MRer.ttf";
 /// Width of each collection box at the bottom in pixels
 const BOXSIZE: u32 = 40;
+/// Simulated time between ball spawns in seconds
+const SPAWN_INTERVAL: f32 = 1.2;
 
 /// Main game loop that updates and renders the simulation for one frame.
 ///
+/// The simulation advances in fixed ticks, so the number of ticks run depends on
+/// how much wall-clock time has passed. Balls are spawned per tick and counted when
+/// they enter a box's sensor, then the frame is drawn interpolated between the last
+/// two ticks.
+///
 /// # Arguments
 ///
-/// * `grid` - The spatial grid containing all physics objects
+/// * `world` - The physics world containing all physics objects
 /// * `boxes` - Vector tracking ball counts for each collection box
+/// * `box_sensors` - Handle of each collection box's sensor, in box order
+/// * `spawn_time` - Simulated time since the last ball was spawned
 /// * `canvas` - SDL2 canvas for rendering
 /// * `font` - Font for rendering text
 /// * `dt` - Time delta in seconds since last frame
-fn main_loop(grid: &mut Grid, boxes: &mut Vec<i32>, canvas:&mut Canvas<Window>, font: &Font, dt: f32) {
+fn main_loop(world: &mut World, boxes: &mut [i32], box_sensors: &[SensorHandle], spawn_time: &mut f32, canvas:&mut Canvas<Window>, font: &Font, dt: f32) {
     canvas.set_draw_color(BACKGROUND);
     canvas.clear();
 
-    grid.draw_frame(canvas, dt);
-    grid.update_boxes(canvas, boxes, font, BOXSIZE, WINDOW_HEIGHT);
-    grid.cleanup();
-    // Run multiple collision passes per frame for stability
-    for _ in 0..COLLISION_LOOPS {
-        grid.handle_collisions()
+    let steps = world.timestep.accumulate(dt);
+    for _ in 0..steps {
+        let tick = world.timestep.dt();
+        // Spawn a new ball every SPAWN_INTERVAL seconds of simulated time
+        *spawn_time += tick;
+        if *spawn_time > SPAWN_INTERVAL {
+            *spawn_time = 0.0;
+            spawn_balls(world);
+        }
+        world.step(tick);
+        for event in world.drain_sensor_events() {
+            if event.kind != SensorEventKind::Enter {
+                continue;
+            }
+            if let Some(i) = box_sensors.iter().position(|&sensor| sensor == event.sensor) {
+                boxes[i] += 1;
+            }
+        }
+        // The demo does not react to contacts or despawns, so keep the queues from growing
+        world.drain_events();
+        world.drain_removed();
     }
+    world.grid().draw_frame(canvas, world.alpha());
+    world.grid().draw_boxes(canvas, boxes, font, BOXSIZE, WINDOW_HEIGHT);
 }
 
 /// Sets up the Plinko board with walls, pegs, and collection boxes.
 ///
 /// Creates the border walls, collection box dividers, and arranges the pegs
-/// in a staggered pattern across the board.
+/// in a staggered pattern across the board. Each box gets a sensor just below the
+/// bottom of the window that counts the balls falling into it, and a kill zone
+/// underneath removes them once counted.
 ///
 /// # Arguments
 ///
-/// * `grid` - The spatial grid to add objects to
+/// * `world` - The physics world to add objects to
 /// * `boxes` - Vector to initialize for tracking ball counts
-fn set_up(grid: &mut Grid, boxes: &mut Vec<i32>) {
+/// * `box_sensors` - Vector to fill with the handle of each box's sensor
+fn set_up(world: &mut World, boxes: &mut Vec<i32>, box_sensors: &mut Vec<SensorHandle>) {
     // Add left and right border walls
-    grid.add_wall(Wall::new([0.0, 0.0], [0.0, WINDOW_HEIGHT as f32], Some(20), None, None, None));
-    grid.add_wall(Wall::new([WINDOW_WIDTH as f32, 0.0], [WINDOW_WIDTH as f32, WINDOW_HEIGHT as f32], Some(20), None, None, None));
+    world.add_wall(Wall::new(Vec2::new(0.0, 0.0), Vec2::new(0.0, WINDOW_HEIGHT as f32), Some(20), None, None, None));
+    world.add_wall(Wall::new(Vec2::new(WINDOW_WIDTH as f32, 0.0), Vec2::new(WINDOW_WIDTH as f32, WINDOW_HEIGHT as f32), Some(20), None, None, None));
+
+    // Despawn balls that fall out of the bottom, as well as those leaving the grid
+    world.cleanup.rules.push(CleanupRule::KillZone(SensorShape::Rectangle {
+        min: Vec2::new(0.0, WINDOW_HEIGHT as f32),
+        max: Vec2::new(WINDOW_WIDTH as f32, WINDOW_HEIGHT as f32 + 100.0),
+    }));
 
     // Calculate Plinko board dimensions
     let num_areas = WINDOW_WIDTH / BOXSIZE;
@@ -77,10 +104,10 @@ fn set_up(grid: &mut Grid, boxes: &mut Vec<i32>) {
 
     // Create collection boxes and pegs
     for i in 1..num_areas {
-        boxes.push(0);
+        add_box(world, boxes, box_sensors, i - 1);
         let x = i * BOXSIZE;
         // Add divider wall for collection box
-        grid.add_wall(Wall::new([x as f32, WINDOW_HEIGHT as f32 - 60.0], [x as f32, WINDOW_HEIGHT as f32 + 40.0], None, None, None, None));
+        world.add_wall(Wall::new(Vec2::new(x as f32, WINDOW_HEIGHT as f32 - 60.0), Vec2::new(x as f32, WINDOW_HEIGHT as f32 + 40.0), None, None, None, None));
 
         // Add pegs in staggered rows (alternating pattern for Plinko effect)
         if i % 2 == 0 && i != num_areas - 1 {
@@ -88,38 +115,53 @@ fn set_up(grid: &mut Grid, boxes: &mut Vec<i32>) {
             for j in (0..num_plinkies).step_by(2) {
                 let y = j * 100;
                 let color = Color::BLUE;
-                add_plinky(grid, x, y, plinkies_offset, plinkies_length, color);
+                add_plinky(world, x, y, plinkies_offset, plinkies_length, color);
             }
         } else if i != 1 && i != num_areas - 1 {
             // Odd columns: pegs on odd rows
             for j in (1..num_plinkies).step_by(2) {
                 let y = j * 100;
                 let color = Color::CYAN;
-                add_plinky(grid, x, y, plinkies_offset, plinkies_length, color);
+                add_plinky(world, x, y, plinkies_offset, plinkies_length, color);
             }
         }
     }
-    boxes.push(0)
+    add_box(world, boxes, box_sensors, num_areas - 1);
+}
+
+/// Adds a collection box counter and the sensor that fills it.
+///
+/// # Arguments
+///
+/// * `world` - The physics world to add the sensor to
+/// * `boxes` - Vector tracking ball counts for each collection box
+/// * `box_sensors` - Vector tracking the sensor of each collection box
+/// * `i` - Index of the box from the left
+fn add_box(world: &mut World, boxes: &mut Vec<i32>, box_sensors: &mut Vec<SensorHandle>, i: u32) {
+    boxes.push(0);
+    let x = (i * BOXSIZE) as f32;
+    box_sensors.push(world.add_sensor(Sensor::rectangle(
+        Vec2::new(x, WINDOW_HEIGHT as f32), Vec2::new(x + BOXSIZE as f32, WINDOW_HEIGHT as f32 + 40.0))));
 }
 
 /// Adds a plinky (peg) to the grid as two diagonal walls forming a V shape.
 ///
 /// # Arguments
 ///
-/// * `grid` - The spatial grid to add the plinky to
+/// * `world` - The physics world to add the plinky to
 /// * `x` - Horizontal position of the peg center
 /// * `y` - Vertical position of the peg base
 /// * `offset` - Vertical offset from y position
 /// * `length` - Length of each diagonal line
 /// * `color` - Color to render the peg
-fn add_plinky(grid: &mut Grid, x: u32, y: u32, offset: u32, length: u32, color: Color) {
+fn add_plinky(world: &mut World, x: u32, y: u32, offset: u32, length: u32, color: Color) {
     // Right diagonal line (going down and right)
-    grid.add_wall(Wall::new(
-        [x as f32, (y + offset) as f32], [(x + length) as f32, (y + length + offset) as f32],
+    world.add_wall(Wall::new(
+        Vec2::new(x as f32, (y + offset) as f32), Vec2::new((x + length) as f32, (y + length + offset) as f32),
         None, Some(color), None, None));
     // Left diagonal line (going down and left)
-    grid.add_wall(Wall::new(
-        [x as f32, (y + offset) as f32], [(x - length) as f32, (y + length + offset) as f32],
+    world.add_wall(Wall::new(
+        Vec2::new(x as f32, (y + offset) as f32), Vec2::new((x - length) as f32, (y + length + offset) as f32),
         None, Some(color), None, None));
 }
 
@@ -127,13 +169,13 @@ fn add_plinky(grid: &mut Grid, x: u32, y: u32, offset: u32, length: u32, color:
 ///
 /// # Arguments
 ///
-/// * `grid` - The spatial grid to add the ball to
-fn spawn_balls(grid: &mut Grid) {
+/// * `world` - The physics world to add the ball to
+fn spawn_balls(world: &mut World) {
     // Random horizontal position (avoiding edges)
     let x: f32 = rand::random_range(20.0..(WINDOW_WIDTH as f32 - 20.0));
     // Random initial horizontal velocity
     let v: f32 = rand::random_range(-200.0..200.0);
-    grid.add_ball(Ball::new([x, 60.0], Some([v, 0.0]), None, Some(Color::RED), None, None));
+    world.add_ball(Ball::new(Vec2::new(x, 60.0), Some(Vec2::new(v, 0.0)), None, Some(Color::RED), None, None, None));
 }
 
 /// Main entry point for the Plinko simulation.
@@ -160,13 +202,14 @@ fn main() {
     canvas.present();
 
     // Initialize physics grid with 50x50 pixel cells
-    let mut grid: Grid = Grid::new(50, 50, WINDOW_WIDTH as i32, WINDOW_HEIGHT as i32);
+    let mut world = World::new(Grid::new(50, 50, WINDOW_WIDTH as i32, WINDOW_HEIGHT as i32));
     let mut boxes: Vec<i32> = Vec::new();
-    set_up(&mut grid, &mut boxes);
+    let mut box_sensors: Vec<SensorHandle> = Vec::new();
+    set_up(&mut world, &mut boxes, &mut box_sensors);
 
     // Timing variables
     let mut last_frame_time = Instant::now();
-    let mut time: f32 = 0.0;
+    let mut spawn_time: f32 = 0.0;
     let mut event_pump = sdl_context.event_pump().unwrap();
 
     // Main game loop
@@ -186,16 +229,9 @@ fn main() {
         let now = Instant::now();
         let dt = now.duration_since(last_frame_time).as_secs_f32();
         last_frame_time = now;
-        time += dt;
-
-        // Spawn a new ball every 1.2 seconds
-        if time > 1.2 {
-            time = 0.0;
-            spawn_balls(&mut grid);
-        }
 
         // Update and render the simulation
-        main_loop(&mut grid, &mut boxes, &mut canvas, &font, dt);
+        main_loop(&mut world, &mut boxes, &box_sensors, &mut spawn_time, &mut canvas, &font, dt);
 
         canvas.present();
         // Target 60 FPS
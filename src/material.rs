@@ -0,0 +1,204 @@
+use std::collections::HashMap;
+
+/// Rule for combining the friction or restitution coefficients of two colliding objects.
+///
+/// Each object carries its own rule for friction and for restitution. When two objects
+/// collide, the rule with the higher priority wins, in the order the variants are
+/// declared: `Average < Min < Multiply < GeometricMean < Max`. All rules keep the
+/// combined coefficient within [0, 1] as long as both inputs are within [0, 1].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum CombineRule {
+    /// Arithmetic mean: `(a + b) / 2`
+    #[default]
+    Average,
+    /// Smaller of the two coefficients
+    Min,
+    /// Product of the two coefficients: `a * b`
+    Multiply,
+    /// Geometric mean: `sqrt(a * b)`
+    GeometricMean,
+    /// Larger of the two coefficients
+    Max,
+}
+
+impl CombineRule {
+    /// Combines two coefficients using this rule.
+    ///
+    /// # Arguments
+    ///
+    /// * `a` - Coefficient of the first object
+    /// * `b` - Coefficient of the second object
+    ///
+    /// # Returns
+    ///
+    /// The combined coefficient
+    pub fn apply(self, a: f32, b: f32) -> f32 {
+        match self {
+            CombineRule::Average => (a + b) / 2.0,
+            CombineRule::Min => a.min(b),
+            CombineRule::Multiply => a * b,
+            CombineRule::GeometricMean => (a * b).max(0.0).sqrt(),
+            CombineRule::Max => a.max(b),
+        }
+    }
+
+    /// Combines the coefficients of a material pair.
+    ///
+    /// Picks the higher priority of the two objects' rules and applies it.
+    ///
+    /// # Arguments
+    ///
+    /// * `a` - Coefficient of the first object
+    /// * `a_rule` - Combine rule of the first object
+    /// * `b` - Coefficient of the second object
+    /// * `b_rule` - Combine rule of the second object
+    ///
+    /// # Returns
+    ///
+    /// The combined coefficient for the pair
+    pub fn combine(a: f32, a_rule: CombineRule, b: f32, b_rule: CombineRule) -> f32 {
+        a_rule.max(b_rule).apply(a, b)
+    }
+}
+
+/// The surface properties of a physics object that take part in collision response.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Material {
+    /// Friction coefficient (limits the tangential impulse in collisions)
+    pub friction: f32,
+    /// Restitution coefficient (affects normal velocity bounce in collisions)
+    pub restitution: f32,
+    /// Rule for combining this friction with another object's
+    pub friction_combine: CombineRule,
+    /// Rule for combining this restitution with another object's
+    pub restitution_combine: CombineRule,
+    /// Identifier used to look up pair overrides in a [`MaterialTable`] (0 = none)
+    pub id: u32,
+}
+
+impl Material {
+    /// Combines the materials of two colliding objects.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - The material of the other object
+    ///
+    /// # Returns
+    ///
+    /// The combined `(friction, restitution)` coefficients for the pair
+    pub fn combine(&self, other: &Material) -> (f32, f32) {
+        let friction = CombineRule::combine(
+            self.friction, self.friction_combine, other.friction, other.friction_combine);
+        let restitution = CombineRule::combine(
+            self.restitution, self.restitution_combine, other.restitution, other.restitution_combine);
+        (friction, restitution)
+    }
+}
+
+/// Friction and restitution overrides for specific pairs of materials.
+///
+/// Combine rules are chosen per object, so a pair such as rubber on ice can only get
+/// the coefficients of whichever rule wins. The table stores exact coefficients for a
+/// pair of material ids instead; pairs without an entry fall back to
+/// [`Material::combine`].
+#[derive(Clone, Debug, Default)]
+pub struct MaterialTable {
+    /// Combined `(friction, restitution)` keyed by the sorted pair of material ids
+    pairs: HashMap<(u32, u32), (f32, f32)>,
+}
+
+impl MaterialTable {
+    /// Creates an empty table.
+    pub fn new() -> MaterialTable {
+        MaterialTable::default()
+    }
+
+    /// Sorts a pair of ids so the table is symmetric.
+    fn key(a: u32, b: u32) -> (u32, u32) {
+        (a.min(b), a.max(b))
+    }
+
+    /// Sets the coefficients used when the two materials touch.
+    ///
+    /// # Arguments
+    ///
+    /// * `a` - Id of the first material
+    /// * `b` - Id of the second material (may equal `a`)
+    /// * `friction` - Friction coefficient for the pair
+    /// * `restitution` - Restitution coefficient for the pair
+    pub fn set(&mut self, a: u32, b: u32, friction: f32, restitution: f32) {
+        self.pairs.insert(MaterialTable::key(a, b), (friction, restitution));
+    }
+
+    /// Returns the `(friction, restitution)` override for a pair, if there is one.
+    pub fn get(&self, a: u32, b: u32) -> Option<(f32, f32)> {
+        self.pairs.get(&MaterialTable::key(a, b)).copied()
+    }
+
+    /// Removes the override for a pair, returning it if there was one.
+    pub fn remove(&mut self, a: u32, b: u32) -> Option<(f32, f32)> {
+        self.pairs.remove(&MaterialTable::key(a, b))
+    }
+
+    /// Combines the materials of two colliding objects.
+    ///
+    /// # Arguments
+    ///
+    /// * `a` - The material of the first object
+    /// * `b` - The material of the second object
+    ///
+    /// # Returns
+    ///
+    /// The pair's override if the table has one, otherwise the result of the
+    /// objects' combine rules
+    pub fn combine(&self, a: &Material, b: &Material) -> (f32, f32) {
+        self.get(a.id, b.id).unwrap_or_else(|| a.combine(b))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rules_combine_coefficients() {
+        assert_eq!(CombineRule::Average.apply(0.2, 0.6), 0.4);
+        assert_eq!(CombineRule::Min.apply(0.2, 0.6), 0.2);
+        assert!((CombineRule::Multiply.apply(0.2, 0.6) - 0.12).abs() < 1e-6);
+        assert_eq!(CombineRule::GeometricMean.apply(0.25, 1.0), 0.5);
+        assert_eq!(CombineRule::Max.apply(0.2, 0.6), 0.6);
+    }
+
+    #[test]
+    fn higher_priority_rule_wins() {
+        // Max outranks Average whichever object carries it
+        assert_eq!(CombineRule::combine(0.2, CombineRule::Average, 0.6, CombineRule::Max), 0.6);
+        assert_eq!(CombineRule::combine(0.2, CombineRule::Max, 0.6, CombineRule::Average), 0.6);
+        assert_eq!(CombineRule::combine(0.2, CombineRule::Min, 0.6, CombineRule::Multiply), 0.2 * 0.6);
+    }
+
+    #[test]
+    fn pair_override_replaces_the_combine_rules() {
+        let material = |id, friction, restitution| Material {
+            friction,
+            restitution,
+            friction_combine: CombineRule::Max,
+            restitution_combine: CombineRule::Max,
+            id,
+        };
+        let rubber = material(1, 0.9, 0.8);
+        let ice = material(2, 0.05, 0.1);
+        let mut table = MaterialTable::new();
+        assert_eq!(table.combine(&rubber, &ice), (0.9, 0.8));
+
+        // The override applies whichever way round the pair is given
+        table.set(2, 1, 0.2, 0.3);
+        assert_eq!(table.combine(&rubber, &ice), (0.2, 0.3));
+        assert_eq!(table.combine(&ice, &rubber), (0.2, 0.3));
+        // Other pairs still use the rules
+        assert_eq!(table.combine(&rubber, &rubber), (0.9, 0.8));
+
+        assert_eq!(table.remove(1, 2), Some((0.2, 0.3)));
+        assert_eq!(table.combine(&rubber, &ice), (0.9, 0.8));
+    }
+}
@@ -0,0 +1,232 @@
+use crate::library::Vec2;
+use std::f32::consts::TAU;
+
+/// A scripted path a kinematic [`Wall`](crate::Wall) follows.
+///
+/// The path is measured from where the wall's endpoints were when the motion was set,
+/// and is followed regardless of what the wall touches: a kinematic wall pushes balls
+/// and bodies out of its way but is never pushed back. Its surface velocity is fed
+/// into the collision response, so a ball resting on a rising elevator is lifted and
+/// a ball hit by a paddle leaves faster than it arrived.
+///
+/// Continuous collision detection sweeps balls against a kinematic wall where it is at
+/// the end of the step, as if it had been there all along, and ignores the wall's own
+/// motion. A wall moving faster than about a ball's radius per step can therefore
+/// pass through a ball.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum WallMotion {
+    /// Moves in a straight line at a constant velocity
+    Linear {
+        /// Velocity of the wall [vx, vy]
+        velocity: Vec2,
+    },
+    /// Moves back and forth around its starting place:
+    /// `offset = amplitude * sin(2π * frequency * t + phase)`
+    Oscillate {
+        /// Largest offset from the starting place [x, y]
+        amplitude: Vec2,
+        /// Number of full swings per second
+        frequency: f32,
+        /// Phase at the start of the motion in radians
+        phase: f32,
+    },
+    /// Rotates about a fixed point at a constant speed
+    Rotate {
+        /// Point the wall rotates about [x, y]
+        pivot: Vec2,
+        /// Angular velocity in radians per second (positive = clockwise on screen)
+        angular_velocity: f32,
+    },
+}
+
+impl WallMotion {
+    /// Returns where a wall following the path is after `time` seconds.
+    ///
+    /// # Arguments
+    ///
+    /// * `origin` - Endpoints of the wall when the motion started
+    /// * `time` - Seconds since the motion started
+    ///
+    /// # Returns
+    ///
+    /// The wall's endpoints at that time
+    pub fn endpoints(&self, origin: [Vec2; 2], time: f32) -> [Vec2; 2] {
+        match *self {
+            WallMotion::Linear { velocity } => origin.map(|point| point + velocity * time),
+            WallMotion::Oscillate { amplitude, frequency, phase } => {
+                let offset = amplitude * (TAU * frequency * time + phase).sin();
+                origin.map(|point| point + offset)
+            },
+            WallMotion::Rotate { pivot, angular_velocity } => {
+                origin.map(|point| pivot + (point - pivot).rotate(angular_velocity * time))
+            },
+        }
+    }
+
+    /// Returns where a wall's path must have started for it to be at `endpoints` after
+    /// `time` seconds, the inverse of [`endpoints`](WallMotion::endpoints).
+    ///
+    /// # Arguments
+    ///
+    /// * `endpoints` - Endpoints of the wall after `time` seconds
+    /// * `time` - Seconds since the motion started
+    ///
+    /// # Returns
+    ///
+    /// The wall's endpoints when the motion started
+    pub fn origin(&self, endpoints: [Vec2; 2], time: f32) -> [Vec2; 2] {
+        match *self {
+            WallMotion::Linear { velocity } => endpoints.map(|point| point - velocity * time),
+            WallMotion::Oscillate { amplitude, frequency, phase } => {
+                let offset = amplitude * (TAU * frequency * time + phase).sin();
+                endpoints.map(|point| point - offset)
+            },
+            WallMotion::Rotate { pivot, angular_velocity } => {
+                endpoints.map(|point| pivot + (point - pivot).rotate(-angular_velocity * time))
+            },
+        }
+    }
+
+    /// Returns how fast a wall following the path moves after `time` seconds.
+    ///
+    /// # Arguments
+    ///
+    /// * `origin` - Endpoints of the wall when the motion started
+    /// * `time` - Seconds since the motion started
+    ///
+    /// # Returns
+    ///
+    /// The velocity of the wall's center [vx, vy] and its angular velocity
+    pub fn velocity(&self, origin: [Vec2; 2], time: f32) -> (Vec2, f32) {
+        match *self {
+            WallMotion::Linear { velocity } => (velocity, 0.0),
+            WallMotion::Oscillate { amplitude, frequency, phase } => {
+                let speed = TAU * frequency;
+                (amplitude * (speed * (speed * time + phase).cos()), 0.0)
+            },
+            WallMotion::Rotate { pivot, angular_velocity } => {
+                let [a, b] = self.endpoints(origin, time);
+                let center = (a + b) / 2.0;
+                ((center - pivot).perp() * angular_velocity, angular_velocity)
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::body::RigidBody;
+    use crate::grid::Grid;
+    use crate::items::{Ball, Wall};
+    use std::f32::consts::PI;
+    use crate::world::World;
+
+    const DT: f32 = 1.0 / 60.0;
+
+    #[test]
+    fn sliding_platform_carries_a_ball() {
+        let mut world = World::new(Grid::new(50, 50, 800, 800));
+        let platform = world.add_wall(Wall::new(Vec2::new(100.0, 700.0), Vec2::new(500.0, 700.0), None, None, Some(1.0), Some(0.0)));
+        let ball = world.add_ball(Ball::new(Vec2::new(300.0, 685.0), None, None, None, Some(1.0), Some(0.0), None));
+        assert!(world.set_wall_motion(platform, Some(WallMotion::Linear { velocity: Vec2::new(100.0, 0.0) })));
+        for _ in 0..120 {
+            world.step(DT);
+        }
+        // Two seconds later the platform is 200 px on, and the ball rides along on top
+        let wall = world.wall(platform).unwrap();
+        assert!((wall.a.x - 300.0).abs() < 0.5, "platform at {:?}", wall.a);
+        let ball = world.ball(ball).unwrap();
+        assert!((ball.position.y - 685.0).abs() < 1.0, "y = {}", ball.position.y);
+        // Its bottom moves with the platform, and as it rolls its center keeps a third
+        // of the platform's speed (a disk's inertia takes the rest)
+        let bottom = ball.velocity_at(Vec2::new(0.0, 10.0));
+        assert!((bottom - Vec2::new(100.0, 0.0)).length() < 1.0, "bottom moves at {bottom:?}");
+        assert!((ball.velocity.x - 100.0 / 3.0).abs() < 1.0, "vx = {}", ball.velocity.x);
+        assert!((ball.position.x - (300.0 + 200.0 / 3.0)).abs() < 10.0, "x = {}", ball.position.x);
+    }
+
+    #[test]
+    fn oscillating_wall_follows_its_sine() {
+        let mut world = World::new(Grid::new(50, 50, 800, 800));
+        let wall = world.add_wall(Wall::new(Vec2::new(100.0, 400.0), Vec2::new(300.0, 400.0), None, None, None, None));
+        let motion = WallMotion::Oscillate { amplitude: Vec2::new(0.0, 50.0), frequency: 0.5, phase: 0.0 };
+        world.set_wall_motion(wall, Some(motion));
+        // A quarter of a swing later the wall is at its lowest, and stopped
+        for _ in 0..30 {
+            world.step(DT);
+        }
+        let w = world.wall(wall).unwrap();
+        assert!((w.a - Vec2::new(100.0, 450.0)).length() < 0.1, "a = {:?}", w.a);
+        assert!((w.b - Vec2::new(300.0, 450.0)).length() < 0.1, "b = {:?}", w.b);
+        assert!(w.velocity.length() < 1.0, "v = {:?}", w.velocity);
+        // Half a swing later it passes its starting place going up at full speed
+        for _ in 0..30 {
+            world.step(DT);
+        }
+        let w = world.wall(wall).unwrap();
+        assert!((w.a - Vec2::new(100.0, 400.0)).length() < 0.1, "a = {:?}", w.a);
+        assert!((w.velocity - Vec2::new(0.0, -50.0 * PI)).length() < 0.5, "v = {:?}", w.velocity);
+        assert_eq!(RigidBody::angle(w), 0.0);
+    }
+
+    #[test]
+    fn rotating_wall_turns_about_its_pivot() {
+        let mut world = World::new(Grid::new(50, 50, 800, 800));
+        let pivot = Vec2::new(400.0, 400.0);
+        let wall = world.add_wall(Wall::new(Vec2::new(400.0, 400.0), Vec2::new(500.0, 400.0), None, None, None, None));
+        world.set_wall_motion(wall, Some(WallMotion::Rotate { pivot, angular_velocity: PI / 2.0 }));
+        // A quarter turn clockwise on screen in one second
+        for _ in 0..60 {
+            world.step(DT);
+        }
+        let w = world.wall(wall).unwrap();
+        assert!((w.a - pivot).length() < 0.1, "a = {:?}", w.a);
+        assert!((w.b - Vec2::new(400.0, 500.0)).length() < 0.1, "b = {:?}", w.b);
+        assert!((RigidBody::angle(w) - PI / 2.0).abs() < 1e-3, "angle = {}", RigidBody::angle(w));
+        // Each point moves across the arm at its distance from the pivot times the turn rate
+        let center = (w.a + w.b) / 2.0;
+        let tip = w.velocity_at(w.b - center);
+        assert!((tip - (w.b - pivot).perp() * (PI / 2.0)).length() < 0.1, "tip v = {tip:?}");
+        assert!((tip.length() - 50.0 * PI).abs() < 0.1, "tip speed = {}", tip.length());
+        assert!(w.velocity_at(w.a - center).length() < 0.1);
+        // Static walls keep angle 0, whichever way they point
+        let slope = world.add_wall(Wall::new(Vec2::new(100.0, 100.0), Vec2::new(200.0, 200.0), None, None, None, None));
+        assert_eq!(RigidBody::angle(world.wall(slope).unwrap()), 0.0);
+    }
+
+    #[test]
+    fn elevator_lifts_a_resting_ball() {
+        let mut world = World::new(Grid::new(50, 50, 800, 800));
+        let platform = world.add_wall(Wall::new(Vec2::new(300.0, 700.0), Vec2::new(500.0, 700.0), None, None, None, Some(0.0)));
+        let ball = world.add_ball(Ball::new(Vec2::new(400.0, 685.0), None, None, None, None, Some(0.0), None));
+        world.set_wall_motion(platform, Some(WallMotion::Linear { velocity: Vec2::new(0.0, -60.0) }));
+        for _ in 0..60 {
+            world.step(DT);
+        }
+        // The platform rose 60 px, pushing the ball up at its own speed
+        let ball = world.ball(ball).unwrap();
+        assert!((ball.position.y - 625.0).abs() < 1.0, "y = {}", ball.position.y);
+        assert!((ball.velocity.y + 60.0).abs() < 1.0, "vy = {}", ball.velocity.y);
+        assert!((ball.position.x - 400.0).abs() < 0.1, "x = {}", ball.position.x);
+    }
+
+    #[test]
+    fn moved_wall_carries_on_from_its_new_place() {
+        let mut world = World::new(Grid::new(50, 50, 800, 800));
+        let wall = world.add_wall(Wall::new(Vec2::new(100.0, 400.0), Vec2::new(300.0, 400.0), None, None, None, None));
+        world.set_wall_motion(wall, Some(WallMotion::Linear { velocity: Vec2::new(100.0, 0.0) }));
+        for _ in 0..30 {
+            world.step(DT);
+        }
+        assert!(world.set_wall_endpoints(wall, [100.0, 500.0], [300.0, 500.0]));
+        for _ in 0..30 {
+            world.step(DT);
+        }
+        // Half a second on from where it was put, not back on its old line
+        let w = world.wall(wall).unwrap();
+        assert!((w.a - Vec2::new(150.0, 500.0)).length() < 0.1, "a = {:?}", w.a);
+        assert!((w.b - Vec2::new(350.0, 500.0)).length() < 0.1, "b = {:?}", w.b);
+        assert_eq!(w.velocity, Vec2::new(100.0, 0.0));
+    }
+}
@@ -0,0 +1,384 @@
+use crate::arena::{Handle, PolygonHandle};
+use crate::body::RigidBody;
+use crate::color::Color;
+use crate::collision::Shape;
+use crate::filter::CollisionFilter;
+use crate::items::DEFAULT_DENSITY;
+#[cfg(feature = "sdl")]
+use crate::items::to_abgr;
+use crate::integrator::Integrator;
+use crate::library::Vec2;
+use crate::material::{CombineRule, Material};
+#[cfg(feature = "sdl")]
+use sdl2::render::{Canvas, RenderTarget};
+#[cfg(feature = "sdl")]
+use sdl2::gfx::primitives::DrawRenderer;
+
+/// Represents a convex polygon rigid body in the physics simulation.
+///
+/// Polygons are dynamic physics objects that move, rotate, collide with balls, walls and
+/// other polygons, and respond to forces like gravity. Vertices are stored relative to
+/// the center of mass and rotated by `angle` to get their world positions.
+pub struct Polygon {
+    /// Handle of this polygon in its grid (dangling until the polygon is added)
+    pub id: PolygonHandle,
+    /// Current position of the center of mass in world space [x, y]
+    pub position: Vec2,
+    /// Current velocity vector [vx, vy]
+    pub velocity: Vec2,
+    /// Current orientation in radians
+    pub angle: f32,
+    /// Current angular velocity in radians per second (positive = clockwise on screen)
+    pub angular_velocity: f32,
+    /// Vertices relative to the center of mass, forming a convex hull with positive winding
+    pub vertices: Vec<Vec2>,
+    /// Color used to render the polygon
+    pub color: Color,
+    /// Friction coefficient (limits the tangential impulse in collisions)
+    pub friction: f32,
+    /// Restitution coefficient (affects normal velocity bounce in collisions)
+    pub restitution: f32,
+    /// Rule for combining this polygon's friction with another object's
+    pub friction_combine: CombineRule,
+    /// Rule for combining this polygon's restitution with another object's
+    pub restitution_combine: CombineRule,
+    /// Material identifier used to look up pair overrides in a [`MaterialTable`](crate::MaterialTable) (0 = none)
+    pub material_id: u32,
+    /// Collision layers deciding which bodies the polygon collides with
+    pub filter: CollisionFilter,
+    /// Mass of the polygon (a non-positive mass makes the polygon immovable)
+    pub mass: f32,
+    /// Moment of inertia about the center of mass (a non-positive inertia prevents spinning)
+    pub inertia: f32,
+    /// IDs of the grid sections this polygon's bounding box overlaps [x_unit, y_unit]
+    pub unit_ids: Vec<[usize; 2]>,
+}
+
+impl Polygon {
+    /// Creates a new convex polygon.
+    ///
+    /// The convex hull of the given points is used, so the order of the points does
+    /// not matter and concave input is wrapped. The vertices are re-centered on the
+    /// polygon's center of mass, which becomes its position.
+    ///
+    /// # Arguments
+    ///
+    /// * `position` - Point the vertices are relative to [x, y]
+    /// * `vertices` - At least three points relative to `position`
+    /// * `velocity` - Optional initial velocity [vx, vy] (default: [0.0, 0.0])
+    /// * `color` - Optional color (default: YELLOW)
+    /// * `friction` - Optional friction coefficient (default: 0.1)
+    /// * `restitution` - Optional restitution coefficient (default: 0.1)
+    /// * `density` - Optional mass per square pixel (default: [`DEFAULT_DENSITY`])
+    ///
+    /// # Returns
+    ///
+    /// A new Polygon instance with mass and moment of inertia computed from its shape
+    ///
+    /// # Panics
+    ///
+    /// Panics if fewer than three of the points are distinct and non-collinear
+    pub fn new(
+        position: Vec2,
+        vertices: Vec<Vec2>,
+        velocity: Option<Vec2>,
+        color: Option<Color>,
+        friction: Option<f32>,
+        restitution: Option<f32>,
+        density: Option<f32>,
+    ) -> Polygon {
+        let hull = convex_hull(vertices);
+        assert!(hull.len() >= 3, "a polygon needs at least three non-collinear points");
+        let density = density.unwrap_or(DEFAULT_DENSITY);
+
+        // Area, centroid and second moment of area by splitting into triangles
+        let mut area = 0.0;
+        let mut centroid = Vec2::ZERO;
+        for i in 0..hull.len() {
+            let (p1, p2) = (hull[i], hull[(i + 1) % hull.len()]);
+            let tri_area = p1.cross(p2) / 2.0;
+            area += tri_area;
+            centroid += (p1 + p2) * (tri_area / 3.0);
+        }
+        if area > 0.0 {
+            centroid = centroid / area;
+        }
+        let vertices: Vec<Vec2> = hull.iter().map(|v| *v - centroid).collect();
+        let mut inertia = 0.0;
+        for i in 0..vertices.len() {
+            let (p1, p2) = (vertices[i], vertices[(i + 1) % vertices.len()]);
+            inertia += p1.cross(p2) * (p1.dot(p1) + p1.dot(p2) + p2.dot(p2)) / 12.0;
+        }
+
+        Polygon {
+            id: Handle::DANGLING,
+            position: position + centroid,
+            velocity: velocity.unwrap_or(Vec2::ZERO),
+            angle: 0.0,
+            angular_velocity: 0.0,
+            vertices,
+            color: color.unwrap_or(Color::YELLOW),
+            friction: friction.unwrap_or(0.1),
+            restitution: restitution.unwrap_or(0.1),
+            friction_combine: CombineRule::default(),
+            restitution_combine: CombineRule::default(),
+            material_id: 0,
+            filter: CollisionFilter::default(),
+            mass: area * density,
+            inertia: inertia * density,
+            unit_ids: Vec::new(),
+        }
+    }
+
+    /// Returns the vertices of a `width` by `height` rectangle centered on the origin.
+    pub fn rectangle(width: f32, height: f32) -> Vec<Vec2> {
+        let (hw, hh) = (width / 2.0, height / 2.0);
+        vec![Vec2::new(-hw, -hh), Vec2::new(hw, -hh), Vec2::new(hw, hh), Vec2::new(-hw, hh)]
+    }
+
+    /// Returns the vertices of a regular polygon centered on the origin.
+    ///
+    /// # Arguments
+    ///
+    /// * `sides` - Number of sides (at least 3)
+    /// * `radius` - Distance from the center to each vertex
+    pub fn regular(sides: usize, radius: f32) -> Vec<Vec2> {
+        (0..sides)
+            .map(|i| {
+                let angle = i as f32 * std::f32::consts::TAU / sides as f32;
+                Vec2::new(radius, 0.0).rotate(angle)
+            })
+            .collect()
+    }
+
+    /// Returns the polygon's vertices in world space.
+    pub fn world_vertices(&self) -> Vec<Vec2> {
+        self.vertices.iter().map(|v| self.position + v.rotate(self.angle)).collect()
+    }
+
+    /// Returns the axis-aligned bounding box of the polygon as (min, max) corners.
+    pub fn aabb(&self) -> (Vec2, Vec2) {
+        let vertices = self.world_vertices();
+        let mut min = vertices[0];
+        let mut max = vertices[0];
+        for v in &vertices[1..] {
+            min = Vec2::new(min.x.min(v.x), min.y.min(v.y));
+            max = Vec2::new(max.x.max(v.x), max.y.max(v.y));
+        }
+        (min, max)
+    }
+
+    /// Draws the polygon on the canvas as a filled shape.
+    ///
+    /// # Arguments
+    ///
+    /// * `canvas` - The SDL2 canvas to draw on
+    #[cfg(feature = "sdl")]
+    pub fn draw<T: RenderTarget>(&self, canvas:&mut Canvas<T>) {
+        let vertices = self.world_vertices();
+        let vx: Vec<i16> = vertices.iter().map(|v| v.x as i16).collect();
+        let vy: Vec<i16> = vertices.iter().map(|v| v.y as i16).collect();
+        let _ = canvas.filled_polygon(&vx, &vy, to_abgr(self.color));
+    }
+
+    /// Updates the polygon's position and orientation based on its velocities.
+    ///
+    /// Applies velocity to position using: `position += velocity * dt`
+    /// and angular velocity to orientation using: `angle += angular_velocity * dt`
+    /// Also clamps velocity to prevent extreme speeds. This is an
+    /// [`integrate`](Self::integrate) step without any acceleration.
+    ///
+    /// # Arguments
+    ///
+    /// * `delta` - Optional time delta in seconds (default: 1.0)
+    /// * `integrator` - Optional numerical method (default: [`Integrator::ExplicitEuler`])
+    pub fn move_polygon(&mut self, delta: Option<f32>, integrator: Option<Integrator>) {
+        self.integrate(integrator.unwrap_or_default(), |_, _| Vec2::ZERO, delta.unwrap_or(1.0));
+    }
+
+    /// Applies a force to the polygon, modifying its velocity.
+    ///
+    /// With a constant force every integrator gives `velocity += force * dt`;
+    /// the position is left for [`move_polygon`](Self::move_polygon) to update.
+    ///
+    /// # Arguments
+    ///
+    /// * `force` - The force vector to apply [fx, fy]
+    /// * `delta` - Optional time delta in seconds (default: 1.0)
+    /// * `integrator` - Optional numerical method (default: [`Integrator::ExplicitEuler`])
+    pub fn apply_force(&mut self, force: Vec2, delta: Option<f32>, integrator: Option<Integrator>) {
+        let dt = delta.unwrap_or(1.0);
+        // Only the velocity half of the integrator's step
+        (_, self.velocity) = integrator.unwrap_or_default().step(self.position, self.velocity, dt, |_, _| force);
+    }
+
+    /// Advances the polygon by one time step with the given integrator (see
+    /// [`Integrator::advance`]).
+    ///
+    /// # Arguments
+    ///
+    /// * `integrator` - Numerical method to use
+    /// * `acceleration` - Acceleration as a function of position and velocity
+    /// * `dt` - Time delta in seconds
+    pub fn integrate(&mut self, integrator: Integrator, acceleration: impl Fn(Vec2, Vec2) -> Vec2, dt: f32) {
+        integrator.advance(&mut self.position, &mut self.velocity, &mut self.angle, self.angular_velocity, dt, acceleration);
+    }
+}
+
+impl RigidBody for Polygon {
+    fn position(&self) -> Vec2 {
+        self.position
+    }
+
+    fn velocity_at(&self, offset: Vec2) -> Vec2 {
+        self.velocity + offset.perp() * self.angular_velocity
+    }
+
+    fn angle(&self) -> f32 {
+        self.angle
+    }
+
+    fn angular_velocity(&self) -> f32 {
+        self.angular_velocity
+    }
+
+    fn inv_mass(&self) -> f32 {
+        if self.mass > 0.0 { 1.0 / self.mass } else { 0.0 }
+    }
+
+    fn inv_inertia(&self) -> f32 {
+        if self.inertia > 0.0 { 1.0 / self.inertia } else { 0.0 }
+    }
+
+    fn apply_impulse_at(&mut self, impulse: Vec2, offset: Vec2) {
+        self.velocity += impulse * self.inv_mass();
+        self.angular_velocity += offset.cross(impulse) * self.inv_inertia();
+    }
+
+    fn apply_angular_impulse(&mut self, impulse: f32) {
+        self.angular_velocity += impulse * self.inv_inertia();
+    }
+
+    fn translate(&mut self, delta: Vec2) {
+        if self.inv_mass() > 0.0 {
+            self.position += delta;
+        }
+    }
+
+    fn rotate(&mut self, delta: f32) {
+        if self.inv_inertia() > 0.0 {
+            self.angle += delta;
+        }
+    }
+
+    fn material(&self) -> Material {
+        Material {
+            friction: self.friction,
+            restitution: self.restitution,
+            friction_combine: self.friction_combine,
+            restitution_combine: self.restitution_combine,
+            id: self.material_id,
+        }
+    }
+
+    fn filter(&self) -> CollisionFilter {
+        self.filter
+    }
+
+    fn shape(&self) -> Shape {
+        Shape::Polygon { vertices: self.world_vertices() }
+    }
+
+    fn aabb(&self) -> (Vec2, Vec2) {
+        Polygon::aabb(self)
+    }
+}
+
+/// Computes the convex hull of a set of points with positive winding.
+///
+/// Uses Andrew's monotone chain algorithm. Collinear points are dropped.
+fn convex_hull(mut points: Vec<Vec2>) -> Vec<Vec2> {
+    points.sort_by(|p, q| p.x.total_cmp(&q.x).then(p.y.total_cmp(&q.y)));
+    points.dedup();
+    if points.len() < 3 {
+        return points;
+    }
+    let mut hull: Vec<Vec2> = Vec::new();
+    // Lower hull, then upper hull
+    for pass in 0..2 {
+        let start = hull.len();
+        let iter: Box<dyn Iterator<Item = &Vec2>> = if pass == 0 {
+            Box::new(points.iter())
+        } else {
+            Box::new(points.iter().rev())
+        };
+        for p in iter {
+            while hull.len() >= start + 2
+                && (hull[hull.len() - 1] - hull[hull.len() - 2]).cross(*p - hull[hull.len() - 2]) <= 0.0
+            {
+                hull.pop();
+            }
+            hull.push(*p);
+        }
+        // The last point of each chain is the first of the next
+        hull.pop();
+    }
+    hull
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn box_mass_and_inertia() {
+        let polygon = Polygon::new(Vec2::new(100.0, 100.0), Polygon::rectangle(40.0, 20.0), None, None, None, None, Some(2.0));
+        let mass = 40.0 * 20.0 * 2.0;
+        assert!((polygon.mass - mass).abs() < 1e-3);
+        assert!((polygon.inertia - mass * (40.0 * 40.0 + 20.0 * 20.0) / 12.0).abs() < 1.0);
+        assert!((polygon.position - Vec2::new(100.0, 100.0)).length() < 1e-4);
+    }
+
+    #[test]
+    fn hull_recenters_on_the_centroid() {
+        // An offset square given out of order with an interior point
+        let points = vec![
+            Vec2::new(10.0, 10.0), Vec2::new(0.0, 0.0), Vec2::new(5.0, 5.0),
+            Vec2::new(10.0, 0.0), Vec2::new(0.0, 10.0),
+        ];
+        let polygon = Polygon::new(Vec2::new(0.0, 0.0), points, None, None, None, None, None);
+        assert_eq!(polygon.vertices.len(), 4);
+        assert!((polygon.position - Vec2::new(5.0, 5.0)).length() < 1e-4);
+    }
+
+    #[test]
+    #[should_panic]
+    fn collinear_points_are_rejected() {
+        let points = vec![Vec2::new(0.0, 0.0), Vec2::new(5.0, 0.0), Vec2::new(10.0, 0.0)];
+        Polygon::new(Vec2::new(0.0, 0.0), points, None, None, None, None, None);
+    }
+
+    #[test]
+    fn move_and_apply_force_take_an_euler_step() {
+        let mut polygon = Polygon::new(Vec2::ZERO, Polygon::rectangle(20.0, 20.0), Some(Vec2::new(10.0, 0.0)), None, None, None, None);
+        polygon.angular_velocity = 1.0;
+        polygon.apply_force(Vec2::new(0.0, 20.0), Some(0.5), None);
+        assert_eq!(polygon.velocity, Vec2::new(10.0, 10.0));
+        assert_eq!(polygon.position, Vec2::ZERO);
+        polygon.move_polygon(Some(0.5), None);
+        assert_eq!(polygon.position, Vec2::new(5.0, 5.0));
+        assert_eq!(polygon.angle, 0.5);
+    }
+
+    #[test]
+    fn move_and_apply_force_use_the_given_integrator() {
+        for integrator in [Integrator::SemiImplicitEuler, Integrator::VelocityVerlet, Integrator::RK4] {
+            let mut polygon = Polygon::new(Vec2::ZERO, Polygon::rectangle(20.0, 20.0), Some(Vec2::new(10.0, 0.0)), None, None, None, None);
+            polygon.apply_force(Vec2::new(0.0, 20.0), Some(0.5), Some(integrator));
+            // A constant force changes the velocity by the same amount in every method
+            assert!((polygon.velocity - Vec2::new(10.0, 10.0)).length() < 1e-5, "{integrator:?}");
+            polygon.move_polygon(Some(0.5), Some(integrator));
+            assert!((polygon.position - Vec2::new(5.0, 5.0)).length() < 1e-5, "{integrator:?}");
+        }
+    }
+}
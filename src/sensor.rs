@@ -0,0 +1,234 @@
+use crate::arena::{BallHandle, Handle, SensorHandle};
+use crate::collision::{collide_circle_segment, collide_circles};
+use crate::library::Vec2;
+
+/// The region covered by a [`Sensor`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SensorShape {
+    /// A line segment, such as a finish line or checkpoint
+    Segment {
+        /// Starting point of the segment [x, y]
+        a: Vec2,
+        /// Ending point of the segment [x, y]
+        b: Vec2,
+    },
+    /// An axis-aligned rectangle, such as a collection bin
+    Rectangle {
+        /// Top-left corner [x, y]
+        min: Vec2,
+        /// Bottom-right corner [x, y]
+        max: Vec2,
+    },
+    /// A circle
+    Circle {
+        /// Center of the circle [x, y]
+        center: Vec2,
+        /// Radius of the circle
+        radius: f32,
+    },
+}
+
+impl SensorShape {
+    /// Returns whether a point lies inside the shape (never for a segment).
+    ///
+    /// # Arguments
+    ///
+    /// * `point` - The point to test [x, y]
+    pub fn contains(&self, point: Vec2) -> bool {
+        match *self {
+            SensorShape::Segment { .. } => false,
+            SensorShape::Rectangle { min, max } => {
+                point.x >= min.x && point.x <= max.x && point.y >= min.y && point.y <= max.y
+            },
+            SensorShape::Circle { center, radius } => (point - center).length() <= radius,
+        }
+    }
+}
+
+/// A trigger region that detects balls without colliding with them.
+///
+/// Sensors are registered in the [`Grid`](crate::Grid) like walls, but take no part in
+/// collision response: balls pass straight through. After every step the grid
+/// compares the balls overlapping each sensor with those of the step before and
+/// reports the difference as [`SensorEvent`]s.
+pub struct Sensor {
+    /// Handle of this sensor in its grid (dangling until the sensor is added)
+    pub id: SensorHandle,
+    /// Region covered by the sensor
+    pub shape: SensorShape,
+    /// Handles of the balls overlapping the sensor after the last step, in ascending order
+    pub balls: Vec<BallHandle>,
+    /// IDs of the grid sections this sensor overlaps [x_unit, y_unit]
+    pub unit_ids: Vec<[usize; 2]>,
+}
+
+impl Sensor {
+    /// Creates a new sensor covering the given shape.
+    ///
+    /// # Arguments
+    ///
+    /// * `shape` - Region covered by the sensor
+    ///
+    /// # Returns
+    ///
+    /// A new Sensor instance with no balls inside
+    pub fn new(shape: SensorShape) -> Sensor {
+        Sensor {
+            id: Handle::DANGLING,
+            shape,
+            balls: Vec::new(),
+            unit_ids: Vec::new(),
+        }
+    }
+
+    /// Creates a new sensor along a line segment.
+    ///
+    /// # Arguments
+    ///
+    /// * `a` - Starting point of the segment [x, y]
+    /// * `b` - Ending point of the segment [x, y]
+    pub fn segment(a: Vec2, b: Vec2) -> Sensor {
+        Sensor::new(SensorShape::Segment { a, b })
+    }
+
+    /// Creates a new sensor covering an axis-aligned rectangle.
+    ///
+    /// # Arguments
+    ///
+    /// * `min` - Top-left corner [x, y]
+    /// * `max` - Bottom-right corner [x, y]
+    pub fn rectangle(min: Vec2, max: Vec2) -> Sensor {
+        Sensor::new(SensorShape::Rectangle { min, max })
+    }
+
+    /// Creates a new sensor covering a circle.
+    ///
+    /// # Arguments
+    ///
+    /// * `center` - Center of the circle [x, y]
+    /// * `radius` - Radius of the circle
+    pub fn circle(center: Vec2, radius: f32) -> Sensor {
+        Sensor::new(SensorShape::Circle { center, radius })
+    }
+
+    /// Returns the axis-aligned bounding box of the sensor as (min, max) corners.
+    pub fn aabb(&self) -> (Vec2, Vec2) {
+        match self.shape {
+            SensorShape::Segment { a, b } => (Vec2::new(a.x.min(b.x), a.y.min(b.y)), Vec2::new(a.x.max(b.x), a.y.max(b.y))),
+            SensorShape::Rectangle { min, max } => (min, max),
+            SensorShape::Circle { center, radius } => {
+                let r = Vec2::new(radius, radius);
+                (center - r, center + r)
+            },
+        }
+    }
+
+    /// Returns whether a point lies inside the sensor (never for a segment).
+    ///
+    /// # Arguments
+    ///
+    /// * `point` - The point to test [x, y]
+    pub fn contains(&self, point: Vec2) -> bool {
+        self.shape.contains(point)
+    }
+
+    /// Returns whether a circle (such as a ball) overlaps the sensor.
+    ///
+    /// # Arguments
+    ///
+    /// * `center` - Center of the circle [x, y]
+    /// * `radius` - Radius of the circle
+    pub fn overlaps_circle(&self, center: Vec2, radius: f32) -> bool {
+        match self.shape {
+            SensorShape::Segment { a, b } => collide_circle_segment(center, radius, a, b, 0.0).is_some(),
+            SensorShape::Rectangle { min, max } => {
+                let closest = Vec2::new(center.x.clamp(min.x, max.x), center.y.clamp(min.y, max.y));
+                (center - closest).length() < radius
+            },
+            SensorShape::Circle { center: c, radius: r } => collide_circles(center, radius, c, r).is_some(),
+        }
+    }
+}
+
+/// Whether a ball entered or left a sensor.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum SensorEventKind {
+    /// The ball started overlapping the sensor this step
+    Enter,
+    /// The ball stopped overlapping the sensor this step (or was removed)
+    Exit,
+}
+
+/// A ball entering or leaving a sensor.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct SensorEvent {
+    /// Whether the ball entered or left
+    pub kind: SensorEventKind,
+    /// Handle of the sensor
+    pub sensor: SensorHandle,
+    /// Handle of the ball
+    pub ball: BallHandle,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::Grid;
+    use crate::items::Ball;
+    use crate::world::World;
+
+    #[test]
+    fn ball_passing_through_enters_and_exits() {
+        let mut world = World::new(Grid::new(50, 50, 800, 800));
+        world.gravity = Vec2::ZERO;
+        let sensor = world.add_sensor(Sensor::rectangle(Vec2::new(200.0, 350.0), Vec2::new(300.0, 450.0)));
+        let ball = world.add_ball(Ball::new(Vec2::new(100.0, 400.0), Some(Vec2::new(300.0, 0.0)), None, None, None, None, None));
+        let mut events = Vec::new();
+        for _ in 0..60 {
+            world.step(1.0 / 60.0);
+            events.extend(world.drain_sensor_events());
+        }
+        assert_eq!(
+            events,
+            [
+                SensorEvent { kind: SensorEventKind::Enter, sensor, ball },
+                SensorEvent { kind: SensorEventKind::Exit, sensor, ball },
+            ]
+        );
+        // Sensors never push back
+        assert_eq!(world.grid().ball(ball).unwrap().velocity, Vec2::new(300.0, 0.0));
+        assert!(world.grid().sensor(sensor).unwrap().balls.is_empty());
+    }
+
+    #[test]
+    fn removed_sensor_stops_reporting() {
+        let mut world = World::new(Grid::new(50, 50, 800, 800));
+        world.gravity = Vec2::ZERO;
+        let sensor = world.add_sensor(Sensor::circle(Vec2::new(400.0, 400.0), 30.0));
+        world.add_ball(Ball::new(Vec2::new(400.0, 400.0), None, None, None, None, None, None));
+        world.step(1.0 / 60.0);
+        assert_eq!(world.drain_sensor_events().count(), 1);
+        assert!(world.remove_sensor(sensor).is_some());
+        assert!(world.remove_sensor(sensor).is_none());
+        assert_eq!(world.grid().sensor_count(), 0);
+        world.step(1.0 / 60.0);
+        assert_eq!(world.drain_sensor_events().count(), 0);
+    }
+
+    #[test]
+    fn count_boxes_adds_the_balls_in_each_box_sensor() {
+        let mut world = World::new(Grid::new(50, 50, 800, 800));
+        world.gravity = Vec2::ZERO;
+        // Boxes 100 px wide below a 600 px window, and a sensor higher up
+        world.add_sensor(Sensor::rectangle(Vec2::new(0.0, 600.0), Vec2::new(100.0, 640.0)));
+        world.add_sensor(Sensor::rectangle(Vec2::new(200.0, 600.0), Vec2::new(300.0, 640.0)));
+        world.add_sensor(Sensor::circle(Vec2::new(400.0, 300.0), 30.0));
+        for position in [[50.0, 620.0], [230.0, 620.0], [270.0, 620.0], [400.0, 300.0], [150.0, 620.0]] {
+            world.add_ball(Ball::new(Vec2::from(position), None, None, None, None, None, None));
+        }
+        world.step(1.0 / 60.0);
+        let mut boxes = [0; 4];
+        world.grid().count_boxes(&mut boxes, 100, 600);
+        assert_eq!(boxes, [1, 0, 2, 0]);
+    }
+}
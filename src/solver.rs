@@ -0,0 +1,1105 @@
+use crate::arena::{BallHandle, JointHandle, PrismaticHandle, RevoluteHandle};
+use crate::body::RigidBody;
+use crate::contact::{CachedPoint, Contact, ContactCache, ContactEvent};
+use crate::collision::Shape;
+use crate::filter::CollisionFilter;
+use crate::grid::Grid;
+use crate::items::PhysItem;
+use crate::joint::{JointKind, JointMotor, JointTarget};
+use crate::library::Vec2;
+use crate::material::{CombineRule, Material, MaterialTable};
+use std::collections::BTreeMap;
+
+/// Default number of velocity iterations per step
+pub const SOLVER_ITERATIONS: u32 = 10;
+/// Default fraction of the penetration removed per step by position correction
+pub const BAUMGARTE: f32 = 0.2;
+/// Default penetration in pixels that is tolerated without correction, so resting
+/// contacts stay touching instead of jittering in and out of contact
+pub const PENETRATION_SLOP: f32 = 0.5;
+/// Default approach speed in pixels per second below which contacts do not bounce
+pub const RESTITUTION_THRESHOLD: f32 = 20.0;
+/// Distance in pixels within which a contact point is matched to the previous step's
+const WARM_START_DISTANCE: f32 = 4.0;
+
+/// Solver state for one point of a contact manifold.
+struct SolverPoint {
+    /// Contact point in world space [x, y]
+    point: Vec2,
+    /// Penetration depth along the normal
+    depth: f32,
+    /// Contact point relative to the center of mass of `a`
+    offset_a: Vec2,
+    /// Contact point relative to the center of mass of `b`
+    offset_b: Vec2,
+    /// Effective mass along the normal
+    normal_mass: f32,
+    /// Effective mass along the tangent
+    tangent_mass: f32,
+    /// Target separating speed from restitution (and Baumgarte position correction)
+    bias: f32,
+    /// Impulse along the normal a bumper adds to this point once the velocities are solved
+    kick: f32,
+    /// Target separating speed of the split-impulse position correction
+    correction: f32,
+    /// Accumulated normal impulse (never negative, contacts only push)
+    normal_impulse: f32,
+    /// Accumulated friction impulse (within the Coulomb friction cone)
+    tangent_impulse: f32,
+    /// Accumulated split-impulse position correction (never negative)
+    pseudo_impulse: f32,
+}
+
+/// A non-penetration and friction constraint between two touching bodies.
+struct Constraint {
+    /// First body of the pair
+    a: PhysItem,
+    /// Second body of the pair
+    b: PhysItem,
+    /// Unit normal pointing from `a` to `b`
+    normal: Vec2,
+    /// Combined friction coefficient of the pair
+    friction: f32,
+    /// Target sliding speed of `b` over `a` along the tangent, from conveyor surfaces
+    tangent_speed: f32,
+    /// Inverse mass of `a`
+    inv_mass_a: f32,
+    /// Inverse mass of `b`
+    inv_mass_b: f32,
+    /// Inverse moment of inertia of `a`
+    inv_inertia_a: f32,
+    /// Inverse moment of inertia of `b`
+    inv_inertia_b: f32,
+    /// One or two contact points
+    points: Vec<SolverPoint>,
+}
+
+/// A distance constraint along the axis between the two ends of a joint.
+struct JointConstraint {
+    /// The joint the constraint was built from
+    joint: JointHandle,
+    /// Ball at the first end
+    a: BallHandle,
+    /// Ball at the second end, or `None` for a world anchor
+    b: Option<BallHandle>,
+    /// Unit vector pointing from `a` to `b`
+    axis: Vec2,
+    /// Effective mass along the axis (softened for springs)
+    mass: f32,
+    /// Target separating speed correcting the length error
+    bias: f32,
+    /// Softness of a spring (0.0 for rigid joints)
+    gamma: f32,
+    /// Whether the joint can only pull (a rope)
+    pull_only: bool,
+    /// Accumulated impulse along the axis
+    impulse: f32,
+}
+
+/// A hinge constraint pinning two anchors together, with its motor and angle limits.
+struct RevoluteConstraint {
+    /// The hinge the constraint was built from
+    joint: RevoluteHandle,
+    /// Body hinged at the anchor
+    a: PhysItem,
+    /// Body `a` is hinged to, or `None` for the world
+    b: Option<PhysItem>,
+    /// Anchor relative to the center of mass of `a`
+    offset_a: Vec2,
+    /// Anchor relative to the center of mass of `b` (or the world origin)
+    offset_b: Vec2,
+    /// Effective mass matrix of the anchors [k11, k12, k22]
+    mass: [f32; 3],
+    /// Target relative speed of the anchors correcting their separation
+    bias: Vec2,
+    /// Effective mass for rotation (0.0 if neither body can spin)
+    angular_mass: f32,
+    /// Target speeds keeping the joint angle within the lower and upper limits
+    limit_biases: [f32; 2],
+    /// Whether the angle is limited
+    limited: bool,
+    /// Motor driving the joint angle
+    motor: Option<JointMotor>,
+    /// Accumulated impulse holding the anchors together, applied to `b`
+    impulse: Vec2,
+    /// Accumulated motor impulse
+    motor_impulse: f32,
+    /// Accumulated lower and upper limit impulses (never negative)
+    limit_impulses: [f32; 2],
+}
+
+/// A slider constraint keeping a body on an axis without rotating, with its motor and
+/// translation limits.
+struct PrismaticConstraint {
+    /// The slider the constraint was built from
+    joint: PrismaticHandle,
+    /// Body sliding along the axis
+    a: PhysItem,
+    /// Body the axis is fixed to, or `None` for the world
+    b: Option<PhysItem>,
+    /// Anchor of `a` relative to the center of mass of `a`
+    offset_a: Vec2,
+    /// Anchor of `a` relative to the center of mass of `b` (or the world origin)
+    offset_b: Vec2,
+    /// Unit direction of the axis
+    axis: Vec2,
+    /// Unit direction across the axis
+    normal: Vec2,
+    /// Effective mass across the axis
+    normal_mass: f32,
+    /// Target speed across the axis correcting the distance from it
+    normal_bias: f32,
+    /// Effective mass for rotation (0.0 if neither body can spin)
+    angular_mass: f32,
+    /// Target relative spin correcting the relative angle
+    angular_bias: f32,
+    /// Effective mass along the axis
+    axial_mass: f32,
+    /// Target speeds keeping the translation within the lower and upper limits
+    limit_biases: [f32; 2],
+    /// Whether the translation is limited
+    limited: bool,
+    /// Motor driving the translation
+    motor: Option<JointMotor>,
+    /// Accumulated impulse across the axis, applied to `a`
+    impulse: f32,
+    /// Accumulated angular impulse, applied to `a`
+    angular_impulse: f32,
+    /// Accumulated motor impulse
+    motor_impulse: f32,
+    /// Accumulated lower and upper limit impulses (never negative)
+    limit_impulses: [f32; 2],
+}
+
+/// Returns the conveyor velocity and bumper kick of an object's surface (none for
+/// anything but walls).
+fn surface(grid: &Grid, item: PhysItem) -> (Vec2, f32) {
+    match item {
+        PhysItem::Wall(handle) => grid.wall(handle).map_or((Vec2::ZERO, 0.0), |wall| (wall.surface_velocity(), wall.kick)),
+        _ => (Vec2::ZERO, 0.0),
+    }
+}
+
+/// The immovable world, standing in for the missing second body of a hinge or slider.
+struct Ground;
+
+impl RigidBody for Ground {
+    fn position(&self) -> Vec2 {
+        Vec2::ZERO
+    }
+
+    fn velocity_at(&self, _offset: Vec2) -> Vec2 {
+        Vec2::ZERO
+    }
+
+    fn angle(&self) -> f32 {
+        0.0
+    }
+
+    fn angular_velocity(&self) -> f32 {
+        0.0
+    }
+
+    fn inv_mass(&self) -> f32 {
+        0.0
+    }
+
+    fn inv_inertia(&self) -> f32 {
+        0.0
+    }
+
+    fn apply_impulse_at(&mut self, _impulse: Vec2, _offset: Vec2) {}
+
+    fn apply_angular_impulse(&mut self, _impulse: f32) {}
+
+    fn translate(&mut self, _delta: Vec2) {}
+
+    fn rotate(&mut self, _delta: f32) {}
+
+    fn material(&self) -> Material {
+        Material {
+            friction: 0.0,
+            restitution: 0.0,
+            friction_combine: CombineRule::default(),
+            restitution_combine: CombineRule::default(),
+            id: 0,
+        }
+    }
+
+    fn filter(&self) -> CollisionFilter {
+        CollisionFilter::DEFAULT
+    }
+
+    fn shape(&self) -> Shape {
+        // The world has no extent of its own
+        Shape::Circle { center: Vec2::ZERO, radius: 0.0 }
+    }
+
+    fn aabb(&self) -> (Vec2, Vec2) {
+        (Vec2::ZERO, Vec2::ZERO)
+    }
+}
+
+/// Returns the two bodies of a hinge or slider mutably, with `ground` standing in for
+/// the world.
+fn joint_bodies<'a>(
+    grid: &'a mut Grid,
+    a: PhysItem,
+    b: Option<PhysItem>,
+    ground: &'a mut Ground,
+) -> Option<(&'a mut dyn RigidBody, &'a mut dyn RigidBody)> {
+    match b {
+        Some(b) => grid.body_pair_mut(a, b),
+        None => Some((grid.body_mut(a)?, ground)),
+    }
+}
+
+/// Returns the target speed keeping a limit's constraint value non-negative.
+///
+/// While the limit is not reached, the bodies may approach it as far as they would
+/// reach it within the step (a speculative limit), so they stop exactly at it.
+fn limit_bias(value: f32, baumgarte: f32, dt: f32) -> f32 {
+    if value > 0.0 { value / dt } else { baumgarte / dt * value }
+}
+
+/// A sequential-impulse contact solver.
+///
+/// [`Joint`](crate::Joint)s, [`RevoluteJoint`](crate::RevoluteJoint)s and
+/// [`PrismaticJoint`](crate::PrismaticJoint)s are solved in the same iterations as
+/// the contacts, so chains and mechanisms settle together with the collisions
+/// between them.
+///
+/// Each step, the contacts are gathered once and turned into constraints. The solver
+/// then sweeps over them `iterations` times, each time applying the change in impulse
+/// that makes the contact points stop approaching. Impulses are accumulated per point
+/// and clamped as a whole (the normal impulse can only push, friction stays within the
+/// Coulomb cone), which converges towards the exact solution for resting contacts.
+///
+/// The accumulated impulses are kept for the next step and applied up front (warm
+/// starting), so a resting stack starts each step close to its solution. Penetration
+/// beyond the allowed slop is removed by a separating speed proportional to its depth.
+/// With split impulses (the default), that speed is solved for separately and only
+/// moves the bodies, so it neither adds energy nor leaks into the warm start impulses.
+/// Otherwise it is added to the velocity constraint (Baumgarte stabilization).
+pub struct ContactSolver {
+    /// Number of velocity iterations per step (higher = stiffer stacks but slower)
+    pub iterations: u32,
+    /// Whether to start each step from the previous step's impulses
+    pub warm_starting: bool,
+    /// Whether position correction is solved separately from the velocities
+    /// (split impulse) instead of being added to them (Baumgarte stabilization)
+    pub split_impulse: bool,
+    /// Fraction of the penetration removed per step, in [0, 1]
+    pub baumgarte: f32,
+    /// Penetration in pixels tolerated without correction
+    pub slop: f32,
+    /// Approach speed in pixels per second below which contacts do not bounce
+    pub restitution_threshold: f32,
+    /// Friction and restitution overrides for specific material pairs
+    pub materials: MaterialTable,
+    /// Contacts and accumulated impulses from the last step, keyed by body pair
+    contacts: ContactCache,
+}
+
+impl ContactSolver {
+    /// Creates a new contact solver with the default settings.
+    ///
+    /// # Returns
+    ///
+    /// A new ContactSolver using [`SOLVER_ITERATIONS`], [`BAUMGARTE`],
+    /// [`PENETRATION_SLOP`] and [`RESTITUTION_THRESHOLD`], with warm starting and
+    /// split impulses enabled
+    pub fn new() -> ContactSolver {
+        ContactSolver {
+            iterations: SOLVER_ITERATIONS,
+            warm_starting: true,
+            split_impulse: true,
+            baumgarte: BAUMGARTE,
+            slop: PENETRATION_SLOP,
+            restitution_threshold: RESTITUTION_THRESHOLD,
+            materials: MaterialTable::new(),
+            contacts: ContactCache::new(),
+        }
+    }
+
+    /// Resolves every contact in the grid for one time step.
+    ///
+    /// Changes velocities, and with split impulses also moves penetrating bodies apart.
+    ///
+    /// # Arguments
+    ///
+    /// * `grid` - The grid holding the bodies
+    /// * `dt` - Time delta in seconds
+    ///
+    /// # Returns
+    ///
+    /// The begin, persist and end events of this step's contacts
+    pub fn solve(&mut self, grid: &mut Grid, dt: f32) -> Vec<ContactEvent> {
+        let mut joints = self.prepare_joints(grid, dt);
+        let mut hinges = self.prepare_revolute_joints(grid, dt);
+        let mut sliders = self.prepare_prismatic_joints(grid, dt);
+        let mut constraints = self.prepare(grid, dt);
+
+        for _ in 0..self.iterations {
+            for joint in &mut joints {
+                Self::solve_joint(grid, joint);
+            }
+            for hinge in &mut hinges {
+                Self::solve_revolute_joint(grid, hinge, dt);
+            }
+            for slider in &mut sliders {
+                Self::solve_prismatic_joint(grid, slider, dt);
+            }
+            for constraint in &mut constraints {
+                let Some((a, b)) = grid.body_pair_mut(constraint.a, constraint.b) else {
+                    continue;
+                };
+                let normal = constraint.normal;
+                let tangent = normal.perp();
+                for p in &mut constraint.points {
+                    // Normal impulse: stop the approach, plus the bias
+                    let rel_vel = b.velocity_at(p.offset_b) - a.velocity_at(p.offset_a);
+                    let lambda = p.normal_mass * (p.bias - rel_vel.dot(normal));
+                    // Clamp the accumulated impulse, not the change, so earlier
+                    // iterations can be partly undone
+                    let total = (p.normal_impulse + lambda).max(0.0);
+                    let lambda = total - p.normal_impulse;
+                    p.normal_impulse = total;
+                    a.apply_impulse_at(-normal * lambda, p.offset_a);
+                    b.apply_impulse_at(normal * lambda, p.offset_b);
+
+                    // Tangent impulse: stop sliding (or slide at the speed of a conveyor
+                    // surface), within the Coulomb friction cone
+                    let rel_vel = b.velocity_at(p.offset_b) - a.velocity_at(p.offset_a);
+                    let lambda = -p.tangent_mass * (rel_vel.dot(tangent) - constraint.tangent_speed);
+                    let max_friction = constraint.friction * p.normal_impulse;
+                    let total = (p.tangent_impulse + lambda).clamp(-max_friction, max_friction);
+                    let lambda = total - p.tangent_impulse;
+                    p.tangent_impulse = total;
+                    a.apply_impulse_at(-tangent * lambda, p.offset_a);
+                    b.apply_impulse_at(tangent * lambda, p.offset_b);
+                }
+            }
+        }
+
+        // Kick bodies away from bumpers on top of the solved bounce, so the kick is a
+        // fixed impulse and does not widen the friction cone
+        for constraint in &constraints {
+            let Some((a, b)) = grid.body_pair_mut(constraint.a, constraint.b) else {
+                continue;
+            };
+            for p in constraint.points.iter().filter(|p| p.kick != 0.0) {
+                a.apply_impulse_at(-constraint.normal * p.kick, p.offset_a);
+                b.apply_impulse_at(constraint.normal * p.kick, p.offset_b);
+            }
+        }
+
+        if self.split_impulse {
+            self.correct_positions(grid, &mut constraints, dt);
+        }
+
+        // Keep the joint impulses to warm start the next step
+        for constraint in joints {
+            if let Some(joint) = grid.joint_mut(constraint.joint) {
+                joint.impulse = constraint.impulse;
+            }
+        }
+        for constraint in hinges {
+            if let Some(joint) = grid.revolute_joint_mut(constraint.joint) {
+                joint.impulse = constraint.impulse;
+                joint.motor_impulse = constraint.motor_impulse;
+                joint.limit_impulses = constraint.limit_impulses;
+            }
+        }
+        for constraint in sliders {
+            if let Some(joint) = grid.prismatic_joint_mut(constraint.joint) {
+                joint.impulse = constraint.impulse;
+                joint.angular_impulse = constraint.angular_impulse;
+                joint.motor_impulse = constraint.motor_impulse;
+                joint.limit_impulses = constraint.limit_impulses;
+            }
+        }
+
+        // Keep the accumulated impulses to warm start the next step, and report
+        // which pairs started, kept or stopped touching
+        self.contacts.update(constraints.into_iter().map(|c| Contact {
+            a: c.a,
+            b: c.b,
+            normal: c.normal,
+            points: c.points
+                .iter()
+                .map(|p| CachedPoint {
+                    point: p.point,
+                    depth: p.depth,
+                    normal_impulse: p.normal_impulse,
+                    tangent_impulse: p.tangent_impulse,
+                })
+                .collect(),
+            age: 0,
+        }))
+    }
+
+    /// Returns the contacts resolved in the last step, with their accumulated impulses.
+    pub fn contacts(&self) -> &ContactCache {
+        &self.contacts
+    }
+
+    /// Forgets the contacts of the last step, so the next step is not warm started.
+    ///
+    /// Useful after teleporting bodies, when the old impulses no longer apply.
+    pub fn reset(&mut self) {
+        self.contacts.clear();
+    }
+
+    /// Moves penetrating bodies apart with split impulses.
+    ///
+    /// Solves the non-penetration constraints for pseudo-velocities, linear and
+    /// angular, that exist only for this correction. Then moves and turns every body by
+    /// its pseudo-velocities over `dt` and updates the grid sections it belongs to, in
+    /// handle order so identical worlds stay identical.
+    fn correct_positions(&self, grid: &mut Grid, constraints: &mut [Constraint], dt: f32) {
+        // Ordered by body, so bodies are always moved in the same order
+        let mut pseudo_velocities: BTreeMap<PhysItem, (Vec2, f32)> = BTreeMap::new();
+        for _ in 0..self.iterations {
+            for constraint in constraints.iter_mut() {
+                let normal = constraint.normal;
+                let (inv_mass_a, inv_mass_b) = (constraint.inv_mass_a, constraint.inv_mass_b);
+                let (inv_inertia_a, inv_inertia_b) = (constraint.inv_inertia_a, constraint.inv_inertia_b);
+                let (mut velocity_a, mut spin_a) = pseudo_velocities.get(&constraint.a).copied().unwrap_or_default();
+                let (mut velocity_b, mut spin_b) = pseudo_velocities.get(&constraint.b).copied().unwrap_or_default();
+                for p in &mut constraint.points {
+                    let rel_vel = (velocity_b + p.offset_b.perp() * spin_b) - (velocity_a + p.offset_a.perp() * spin_a);
+                    let lambda = p.normal_mass * (p.correction - rel_vel.dot(normal));
+                    let total = (p.pseudo_impulse + lambda).max(0.0);
+                    let impulse = normal * (total - p.pseudo_impulse);
+                    p.pseudo_impulse = total;
+                    velocity_a -= impulse * inv_mass_a;
+                    spin_a -= p.offset_a.cross(impulse) * inv_inertia_a;
+                    velocity_b += impulse * inv_mass_b;
+                    spin_b += p.offset_b.cross(impulse) * inv_inertia_b;
+                }
+                pseudo_velocities.insert(constraint.a, (velocity_a, spin_a));
+                pseudo_velocities.insert(constraint.b, (velocity_b, spin_b));
+            }
+        }
+        for (item, (velocity, spin)) in pseudo_velocities {
+            if let Some(body) = grid.body_mut(item) {
+                body.translate(velocity * dt);
+                body.rotate(spin * dt);
+            }
+            // Keep the broadphase in step with the corrected position
+            grid.move_body(item);
+        }
+    }
+
+    /// Builds the constraints for every joint whose balls exist and applies the warm
+    /// start impulses.
+    ///
+    /// Rigid joints correct their length error with a Baumgarte bias, whether or not
+    /// split impulses are enabled. Springs are soft constraints, so they stay stable
+    /// at any frequency. A slack rope only acts once its ball would pass the full
+    /// length within the step.
+    fn prepare_joints(&self, grid: &mut Grid, dt: f32) -> Vec<JointConstraint> {
+        let mut constraints = Vec::new();
+        for joint in grid.joints() {
+            let Some(a) = grid.ball(joint.a) else { continue };
+            let (b, position_b, inv_mass_b) = match joint.b {
+                JointTarget::Ball(handle) => match grid.ball(handle) {
+                    Some(b) => (Some(handle), b.position, b.inv_mass()),
+                    None => continue,
+                },
+                JointTarget::Anchor(point) => (None, point, 0.0),
+            };
+            let inv_mass_sum = a.inv_mass() + inv_mass_b;
+            let delta = position_b - a.position;
+            let distance = delta.length();
+            // A joint with no direction cannot push or pull
+            if inv_mass_sum == 0.0 || distance == 0.0 {
+                continue;
+            }
+            let error = distance - joint.length;
+
+            let (mass, bias, gamma) = match joint.kind {
+                JointKind::Distance => (1.0 / inv_mass_sum, self.baumgarte / dt * error, 0.0),
+                JointKind::Rope if error < 0.0 => (1.0 / inv_mass_sum, error / dt, 0.0),
+                JointKind::Rope => (1.0 / inv_mass_sum, self.baumgarte / dt * error, 0.0),
+                JointKind::Spring { frequency, damping_ratio } => {
+                    if frequency <= 0.0 {
+                        continue;
+                    }
+                    // Stiffness and damping from the frequency, as for a mass-spring system
+                    let mass = 1.0 / inv_mass_sum;
+                    let omega = 2.0 * std::f32::consts::PI * frequency;
+                    let stiffness = mass * omega * omega;
+                    let damping = 2.0 * mass * damping_ratio * omega;
+                    let gamma = 1.0 / (dt * (damping + dt * stiffness));
+                    (1.0 / (inv_mass_sum + gamma), error * dt * stiffness * gamma, gamma)
+                },
+            };
+            constraints.push(JointConstraint {
+                joint: joint.id,
+                a: joint.a,
+                b,
+                axis: delta / distance,
+                mass,
+                bias,
+                gamma,
+                pull_only: joint.kind == JointKind::Rope,
+                impulse: if self.warm_starting { joint.impulse } else { 0.0 },
+            });
+        }
+
+        for constraint in &constraints {
+            Self::apply_joint_impulse(grid, constraint, constraint.impulse);
+        }
+        constraints
+    }
+
+    /// Applies the change in impulse that makes a joint's length stop changing,
+    /// plus its bias.
+    fn solve_joint(grid: &mut Grid, constraint: &mut JointConstraint) {
+        let Some(velocity_a) = grid.ball(constraint.a).map(|a| a.velocity) else {
+            return;
+        };
+        let velocity_b = match constraint.b {
+            Some(b) => match grid.ball(b) {
+                Some(b) => b.velocity,
+                None => return,
+            },
+            None => Vec2::ZERO,
+        };
+        let rel_vel = (velocity_b - velocity_a).dot(constraint.axis);
+        let lambda = -constraint.mass * (rel_vel + constraint.bias + constraint.gamma * constraint.impulse);
+        // A rope can only pull, so its accumulated impulse is never positive
+        let total = if constraint.pull_only { (constraint.impulse + lambda).min(0.0) } else { constraint.impulse + lambda };
+        let lambda = total - constraint.impulse;
+        constraint.impulse = total;
+        Self::apply_joint_impulse(grid, constraint, lambda);
+    }
+
+    /// Applies an impulse along a joint's axis, pushing its ends apart when positive.
+    fn apply_joint_impulse(grid: &mut Grid, constraint: &JointConstraint, impulse: f32) {
+        if let Some(a) = grid.ball_mut(constraint.a) {
+            a.apply_impulse_at(-constraint.axis * impulse, Vec2::ZERO);
+        }
+        if let Some(b) = constraint.b.and_then(|b| grid.ball_mut(b)) {
+            b.apply_impulse_at(constraint.axis * impulse, Vec2::ZERO);
+        }
+    }
+
+    /// Builds the constraints for every hinge whose bodies exist and applies the warm
+    /// start impulses.
+    fn prepare_revolute_joints(&self, grid: &mut Grid, dt: f32) -> Vec<RevoluteConstraint> {
+        let mut constraints = Vec::new();
+        for joint in grid.revolute_joints() {
+            let Some(a) = grid.body(joint.a) else { continue };
+            let b: &dyn RigidBody = match joint.b {
+                Some(b) => match grid.body(b) {
+                    Some(b) => b,
+                    None => continue,
+                },
+                None => &Ground,
+            };
+            let offset_a = joint.local_anchor_a.rotate(a.angle());
+            let offset_b = joint.local_anchor_b.rotate(b.angle());
+            let (inv_mass_a, inv_mass_b) = (a.inv_mass(), b.inv_mass());
+            let (inv_inertia_a, inv_inertia_b) = (a.inv_inertia(), b.inv_inertia());
+            let k11 = inv_mass_a + inv_mass_b
+                + inv_inertia_a * offset_a.y * offset_a.y + inv_inertia_b * offset_b.y * offset_b.y;
+            let k12 = -inv_inertia_a * offset_a.x * offset_a.y - inv_inertia_b * offset_b.x * offset_b.y;
+            let k22 = inv_mass_a + inv_mass_b
+                + inv_inertia_a * offset_a.x * offset_a.x + inv_inertia_b * offset_b.x * offset_b.x;
+            if k11 * k22 - k12 * k12 == 0.0 {
+                continue;
+            }
+            let separation = (b.position() + offset_b) - (a.position() + offset_a);
+            let inv_inertia_sum = inv_inertia_a + inv_inertia_b;
+            let angle = a.angle() - b.angle() - joint.reference_angle;
+            let (lower, upper) = joint.limits.unwrap_or((f32::NEG_INFINITY, f32::INFINITY));
+
+            constraints.push(RevoluteConstraint {
+                joint: joint.id,
+                a: joint.a,
+                b: joint.b,
+                offset_a,
+                offset_b,
+                mass: [k11, k12, k22],
+                bias: separation * (self.baumgarte / dt),
+                angular_mass: if inv_inertia_sum > 0.0 { 1.0 / inv_inertia_sum } else { 0.0 },
+                limit_biases: [
+                    limit_bias(angle - lower, self.baumgarte, dt),
+                    limit_bias(upper - angle, self.baumgarte, dt),
+                ],
+                limited: joint.limits.is_some(),
+                motor: joint.motor,
+                impulse: if self.warm_starting { joint.impulse } else { Vec2::ZERO },
+                motor_impulse: if self.warm_starting && joint.motor.is_some() { joint.motor_impulse } else { 0.0 },
+                limit_impulses: if self.warm_starting && joint.limits.is_some() { joint.limit_impulses } else { [0.0, 0.0] },
+            });
+        }
+
+        let mut ground = Ground;
+        for constraint in &constraints {
+            let Some((a, b)) = joint_bodies(grid, constraint.a, constraint.b, &mut ground) else {
+                continue;
+            };
+            a.apply_impulse_at(-constraint.impulse, constraint.offset_a);
+            b.apply_impulse_at(constraint.impulse, constraint.offset_b);
+            let angular = constraint.motor_impulse + constraint.limit_impulses[0] - constraint.limit_impulses[1];
+            a.apply_angular_impulse(angular);
+            b.apply_angular_impulse(-angular);
+        }
+        constraints
+    }
+
+    /// Applies the changes in impulse that drive, limit and pin a hinge.
+    ///
+    /// The motor is solved first and the anchors last, so a motor that is too strong
+    /// can never pull the hinge apart.
+    fn solve_revolute_joint(grid: &mut Grid, constraint: &mut RevoluteConstraint, dt: f32) {
+        let mut ground = Ground;
+        let Some((a, b)) = joint_bodies(grid, constraint.a, constraint.b, &mut ground) else {
+            return;
+        };
+
+        if constraint.angular_mass > 0.0 {
+            if let Some(motor) = constraint.motor {
+                let spin = a.angular_velocity() - b.angular_velocity();
+                let lambda = -constraint.angular_mass * (spin - motor.speed);
+                let max_impulse = motor.max_force * dt;
+                let total = (constraint.motor_impulse + lambda).clamp(-max_impulse, max_impulse);
+                let lambda = total - constraint.motor_impulse;
+                constraint.motor_impulse = total;
+                a.apply_angular_impulse(lambda);
+                b.apply_angular_impulse(-lambda);
+            }
+
+            if constraint.limited {
+                // Lower limit: the angle may only grow
+                let spin = a.angular_velocity() - b.angular_velocity();
+                let lambda = -constraint.angular_mass * (spin + constraint.limit_biases[0]);
+                let total = (constraint.limit_impulses[0] + lambda).max(0.0);
+                let lambda = total - constraint.limit_impulses[0];
+                constraint.limit_impulses[0] = total;
+                a.apply_angular_impulse(lambda);
+                b.apply_angular_impulse(-lambda);
+
+                // Upper limit: the angle may only shrink
+                let spin = a.angular_velocity() - b.angular_velocity();
+                let lambda = -constraint.angular_mass * (-spin + constraint.limit_biases[1]);
+                let total = (constraint.limit_impulses[1] + lambda).max(0.0);
+                let lambda = total - constraint.limit_impulses[1];
+                constraint.limit_impulses[1] = total;
+                a.apply_angular_impulse(-lambda);
+                b.apply_angular_impulse(lambda);
+            }
+        }
+
+        // Stop the anchors moving apart, solving both axes at once
+        let rel_vel = b.velocity_at(constraint.offset_b) - a.velocity_at(constraint.offset_a);
+        let rhs = -(rel_vel + constraint.bias);
+        let [k11, k12, k22] = constraint.mass;
+        let det = k11 * k22 - k12 * k12;
+        let impulse = Vec2::new(k22 * rhs.x - k12 * rhs.y, k11 * rhs.y - k12 * rhs.x) / det;
+        constraint.impulse += impulse;
+        a.apply_impulse_at(-impulse, constraint.offset_a);
+        b.apply_impulse_at(impulse, constraint.offset_b);
+    }
+
+    /// Builds the constraints for every slider whose bodies exist and applies the warm
+    /// start impulses.
+    fn prepare_prismatic_joints(&self, grid: &mut Grid, dt: f32) -> Vec<PrismaticConstraint> {
+        let mut constraints = Vec::new();
+        for joint in grid.prismatic_joints() {
+            let Some(a) = grid.body(joint.a) else { continue };
+            let b: &dyn RigidBody = match joint.b {
+                Some(b) => match grid.body(b) {
+                    Some(b) => b,
+                    None => continue,
+                },
+                None => &Ground,
+            };
+            let offset_a = joint.local_anchor_a.rotate(a.angle());
+            let anchor_a = a.position() + offset_a;
+            let anchor_b = b.position() + joint.local_anchor_b.rotate(b.angle());
+            // The axis turns with `b`, so `b` is pushed at the anchor of `a`
+            let offset_b = anchor_a - b.position();
+            let axis = joint.local_axis.rotate(b.angle());
+            let normal = axis.perp();
+            let (inv_mass_sum, inv_inertia_a, inv_inertia_b) = (a.inv_mass() + b.inv_mass(), a.inv_inertia(), b.inv_inertia());
+            let effective_mass = |direction: Vec2| {
+                let k = inv_mass_sum
+                    + inv_inertia_a * offset_a.cross(direction).powi(2)
+                    + inv_inertia_b * offset_b.cross(direction).powi(2);
+                if k > 0.0 { 1.0 / k } else { 0.0 }
+            };
+            let (normal_mass, axial_mass) = (effective_mass(normal), effective_mass(axis));
+            if normal_mass == 0.0 {
+                continue;
+            }
+            let inv_inertia_sum = inv_inertia_a + inv_inertia_b;
+            let translation = (anchor_a - anchor_b).dot(axis);
+            let angle = a.angle() - b.angle() - joint.reference_angle;
+            let (lower, upper) = joint.limits.unwrap_or((f32::NEG_INFINITY, f32::INFINITY));
+
+            constraints.push(PrismaticConstraint {
+                joint: joint.id,
+                a: joint.a,
+                b: joint.b,
+                offset_a,
+                offset_b,
+                axis,
+                normal,
+                normal_mass,
+                normal_bias: self.baumgarte / dt * (anchor_a - anchor_b).dot(normal),
+                angular_mass: if inv_inertia_sum > 0.0 { 1.0 / inv_inertia_sum } else { 0.0 },
+                angular_bias: self.baumgarte / dt * angle,
+                axial_mass,
+                limit_biases: [
+                    limit_bias(translation - lower, self.baumgarte, dt),
+                    limit_bias(upper - translation, self.baumgarte, dt),
+                ],
+                limited: joint.limits.is_some(),
+                motor: joint.motor,
+                impulse: if self.warm_starting { joint.impulse } else { 0.0 },
+                angular_impulse: if self.warm_starting { joint.angular_impulse } else { 0.0 },
+                motor_impulse: if self.warm_starting && joint.motor.is_some() { joint.motor_impulse } else { 0.0 },
+                limit_impulses: if self.warm_starting && joint.limits.is_some() { joint.limit_impulses } else { [0.0, 0.0] },
+            });
+        }
+
+        let mut ground = Ground;
+        for constraint in &constraints {
+            let Some((a, b)) = joint_bodies(grid, constraint.a, constraint.b, &mut ground) else {
+                continue;
+            };
+            let axial = constraint.motor_impulse + constraint.limit_impulses[0] - constraint.limit_impulses[1];
+            let impulse = constraint.normal * constraint.impulse + constraint.axis * axial;
+            a.apply_impulse_at(impulse, constraint.offset_a);
+            b.apply_impulse_at(-impulse, constraint.offset_b);
+            a.apply_angular_impulse(constraint.angular_impulse);
+            b.apply_angular_impulse(-constraint.angular_impulse);
+        }
+        constraints
+    }
+
+    /// Applies the changes in impulse that drive, limit and guide a slider.
+    fn solve_prismatic_joint(grid: &mut Grid, constraint: &mut PrismaticConstraint, dt: f32) {
+        let mut ground = Ground;
+        let Some((a, b)) = joint_bodies(grid, constraint.a, constraint.b, &mut ground) else {
+            return;
+        };
+        let (axis, normal) = (constraint.axis, constraint.normal);
+
+        if constraint.axial_mass > 0.0 {
+            if let Some(motor) = constraint.motor {
+                let speed = (a.velocity_at(constraint.offset_a) - b.velocity_at(constraint.offset_b)).dot(axis);
+                let lambda = -constraint.axial_mass * (speed - motor.speed);
+                let max_impulse = motor.max_force * dt;
+                let total = (constraint.motor_impulse + lambda).clamp(-max_impulse, max_impulse);
+                let lambda = total - constraint.motor_impulse;
+                constraint.motor_impulse = total;
+                a.apply_impulse_at(axis * lambda, constraint.offset_a);
+                b.apply_impulse_at(-axis * lambda, constraint.offset_b);
+            }
+
+            if constraint.limited {
+                // Lower limit: `a` may only move forwards along the axis
+                let speed = (a.velocity_at(constraint.offset_a) - b.velocity_at(constraint.offset_b)).dot(axis);
+                let lambda = -constraint.axial_mass * (speed + constraint.limit_biases[0]);
+                let total = (constraint.limit_impulses[0] + lambda).max(0.0);
+                let lambda = total - constraint.limit_impulses[0];
+                constraint.limit_impulses[0] = total;
+                a.apply_impulse_at(axis * lambda, constraint.offset_a);
+                b.apply_impulse_at(-axis * lambda, constraint.offset_b);
+
+                // Upper limit: `a` may only move backwards along the axis
+                let speed = (a.velocity_at(constraint.offset_a) - b.velocity_at(constraint.offset_b)).dot(axis);
+                let lambda = -constraint.axial_mass * (-speed + constraint.limit_biases[1]);
+                let total = (constraint.limit_impulses[1] + lambda).max(0.0);
+                let lambda = total - constraint.limit_impulses[1];
+                constraint.limit_impulses[1] = total;
+                a.apply_impulse_at(-axis * lambda, constraint.offset_a);
+                b.apply_impulse_at(axis * lambda, constraint.offset_b);
+            }
+        }
+
+        // Stop the bodies rotating relative to each other
+        if constraint.angular_mass > 0.0 {
+            let spin = a.angular_velocity() - b.angular_velocity();
+            let lambda = -constraint.angular_mass * (spin + constraint.angular_bias);
+            constraint.angular_impulse += lambda;
+            a.apply_angular_impulse(lambda);
+            b.apply_angular_impulse(-lambda);
+        }
+
+        // Keep `a` on the axis
+        let speed = (a.velocity_at(constraint.offset_a) - b.velocity_at(constraint.offset_b)).dot(normal);
+        let lambda = -constraint.normal_mass * (speed + constraint.normal_bias);
+        constraint.impulse += lambda;
+        a.apply_impulse_at(normal * lambda, constraint.offset_a);
+        b.apply_impulse_at(-normal * lambda, constraint.offset_b);
+    }
+
+    /// Builds the constraints for the current contacts and applies the warm start impulses.
+    fn prepare(&self, grid: &mut Grid, dt: f32) -> Vec<Constraint> {
+        let mut constraints = Vec::new();
+        for (a_item, b_item, manifold) in grid.find_contacts() {
+            let (surface_a, kick_a) = surface(grid, a_item);
+            let (surface_b, kick_b) = surface(grid, b_item);
+            let touching = self.contacts.get(a_item, b_item).is_some();
+            let Some((a, b)) = grid.body_pair_mut(a_item, b_item) else {
+                continue;
+            };
+            let inv_mass_sum = a.inv_mass() + b.inv_mass();
+            if inv_mass_sum == 0.0 {
+                continue;
+            }
+            let (friction, restitution) = self.materials.combine(&a.material(), &b.material());
+            let normal = manifold.normal;
+            let tangent = normal.perp();
+            // A bumper's kick is shared between the points of the manifold
+            let kick = (kick_a + kick_b) / manifold.points.len() as f32;
+
+            let mut points = Vec::new();
+            for contact in &manifold.points {
+                let offset_a = contact.point - a.position();
+                let offset_b = contact.point - b.position();
+                let normal_mass = inv_mass_sum
+                    + offset_a.cross(normal).powi(2) * a.inv_inertia()
+                    + offset_b.cross(normal).powi(2) * b.inv_inertia();
+                let tangent_mass = inv_mass_sum
+                    + offset_a.cross(tangent).powi(2) * a.inv_inertia()
+                    + offset_b.cross(tangent).powi(2) * b.inv_inertia();
+
+                // Bounce fast approaches and push out penetration beyond the slop
+                let n_vel = (b.velocity_at(offset_b) - a.velocity_at(offset_a)).dot(normal);
+                let bounce = if n_vel < -self.restitution_threshold { -restitution * n_vel } else { 0.0 };
+                let correction = self.baumgarte / dt * (contact.depth - self.slop).max(0.0);
+
+                // Reuse the impulses of the closest point from the previous step
+                let previous = if self.warm_starting {
+                    self.contacts.closest_point(a_item, b_item, contact.point, WARM_START_DISTANCE)
+                } else {
+                    None
+                };
+                let (normal_impulse, tangent_impulse) =
+                    previous.map_or((0.0, 0.0), |old| (old.normal_impulse, old.tangent_impulse));
+
+                points.push(SolverPoint {
+                    point: contact.point,
+                    depth: contact.depth,
+                    offset_a,
+                    offset_b,
+                    normal_mass: 1.0 / normal_mass,
+                    tangent_mass: 1.0 / tangent_mass,
+                    bias: if self.split_impulse { bounce } else { bounce.max(correction) },
+                    // Bumpers kick any body that starts touching them moving in, however
+                    // slow, but not one resting against them
+                    kick: if n_vel < 0.0 && !touching { kick } else { 0.0 },
+                    correction,
+                    normal_impulse,
+                    tangent_impulse,
+                    pseudo_impulse: 0.0,
+                });
+            }
+            constraints.push(Constraint {
+                a: a_item,
+                b: b_item,
+                normal,
+                friction,
+                tangent_speed: (surface_a - surface_b).dot(tangent),
+                inv_mass_a: a.inv_mass(),
+                inv_mass_b: b.inv_mass(),
+                inv_inertia_a: a.inv_inertia(),
+                inv_inertia_b: b.inv_inertia(),
+                points,
+            });
+        }
+
+        // Warm start once every contact's bounce has been measured, so the impulses
+        // of one contact do not look like an approach at the next
+        for constraint in &constraints {
+            let Some((a, b)) = grid.body_pair_mut(constraint.a, constraint.b) else {
+                continue;
+            };
+            let tangent = constraint.normal.perp();
+            for p in &constraint.points {
+                let impulse = constraint.normal * p.normal_impulse + tangent * p.tangent_impulse;
+                a.apply_impulse_at(-impulse, p.offset_a);
+                b.apply_impulse_at(impulse, p.offset_b);
+            }
+        }
+        constraints
+    }
+}
+
+impl Default for ContactSolver {
+    fn default() -> ContactSolver {
+        ContactSolver::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::integrator::Integrator;
+    use crate::items::{Ball, Wall, GRAVITY};
+    use crate::polygon::Polygon;
+
+    const DT: f32 = 1.0 / 60.0;
+
+    /// Moves the bodies under gravity and resolves their contacts, like `World::step`.
+    fn step(grid: &mut Grid, solver: &mut ContactSolver) {
+        grid.integrate(GRAVITY, &[], DT, true, Integrator::default());
+        solver.solve(grid, DT);
+    }
+
+    #[test]
+    fn ball_stack_settles() {
+        let mut grid = Grid::new(50, 50, 800, 800);
+        grid.add_wall(Wall::new(Vec2::new(0.0, 700.0), Vec2::new(800.0, 700.0), None, None, None, None));
+        // Dropped with gaps between them, onto the floor's surface at y = 695
+        for i in 0..4 {
+            grid.add_ball(Ball::new(Vec2::new(400.0, 680.0 - 25.0 * i as f32), None, None, None, None, None, None));
+        }
+        let mut solver = ContactSolver::new();
+        for _ in 0..300 {
+            step(&mut grid, &mut solver);
+        }
+        let mut balls: Vec<&Ball> = grid.balls().collect();
+        balls.sort_by(|a, b| b.position.y.total_cmp(&a.position.y));
+        for (i, ball) in balls.iter().enumerate() {
+            let resting = 685.0 - 20.0 * i as f32;
+            assert!((ball.position.y - resting).abs() < 2.0, "ball {i}: y = {}", ball.position.y);
+            assert!(ball.velocity.length() < 1.0, "ball {i}: v = {:?}", ball.velocity);
+        }
+    }
+
+    #[test]
+    fn resting_contact_is_warm_started_until_it_separates() {
+        let mut grid = Grid::new(50, 50, 800, 800);
+        let floor = grid.add_wall(Wall::new(Vec2::new(0.0, 700.0), Vec2::new(800.0, 700.0), None, None, None, None));
+        let ball = grid.add_ball(Ball::new(Vec2::new(400.0, 685.0), None, None, None, None, None, None));
+        let mass = grid.ball(ball).unwrap().mass;
+        let mut solver = ContactSolver::new();
+        for _ in 0..120 {
+            step(&mut grid, &mut solver);
+        }
+        // The floor carries the ball's weight, and the impulse is kept between steps
+        let contact = solver.contacts().get(PhysItem::Ball(ball), PhysItem::Wall(floor)).unwrap();
+        assert_eq!(contact.age, 120);
+        let weight = mass * GRAVITY.y * DT;
+        assert!((contact.normal_impulse() - weight).abs() < 0.05 * weight, "impulse = {}", contact.normal_impulse());
+
+        // Pulled upwards, the ball lifts off and the pair is forgotten
+        for _ in 0..30 {
+            grid.integrate(-GRAVITY, &[], DT, true, Integrator::default());
+            solver.solve(&mut grid, DT);
+        }
+        assert!(solver.contacts().is_empty());
+    }
+
+    #[test]
+    fn position_correction_moves_balls_to_their_new_section() {
+        let mut grid = Grid::new(50, 50, 800, 800);
+        grid.add_wall(Wall::new(Vec2::new(0.0, 710.0), Vec2::new(800.0, 710.0), None, None, None, None));
+        // Sunk 7 px into the floor, below the section boundary at y = 700
+        let ball = grid.add_ball(Ball::new(Vec2::new(400.0, 702.0), None, None, None, None, None, None));
+        let mut solver = ContactSolver::new();
+        solver.baumgarte = 1.0;
+        solver.solve(&mut grid, DT);
+
+        // Pushed out in a single step, the ball is found in the section above
+        let position = grid.ball(ball).unwrap().position;
+        assert!(position.y < 700.0, "y = {}", position.y);
+        let item = PhysItem::Ball(ball);
+        assert!(grid.get_section_at_position(position.x, position.y).items.contains(&item));
+        assert!(!grid.get_section_at_position(400.0, 702.0).items.contains(&item));
+    }
+
+    #[test]
+    fn position_correction_turns_a_tilted_box() {
+        let mut grid = Grid::new(50, 50, 800, 800);
+        grid.add_wall(Wall::new(Vec2::new(0.0, 700.0), Vec2::new(800.0, 700.0), None, None, None, None));
+        let mut square = Polygon::new(Vec2::new(400.0, 675.0), Polygon::rectangle(40.0, 40.0), None, None, None, None, None);
+        // Tilted so that a single corner is sunk 5 px into the floor
+        square.angle = 0.3;
+        let square = grid.add_polygon(square);
+        ContactSolver::new().solve(&mut grid, DT);
+
+        // Pushing the corner out also turns the box towards lying flat
+        let square = grid.polygon(square).unwrap();
+        assert!(square.angle < 0.3 - 1e-3, "angle = {}", square.angle);
+        assert!(square.position.y < 675.0, "y = {}", square.position.y);
+    }
+
+    #[test]
+    fn bumper_kicks_slow_bodies_by_a_fixed_impulse() {
+        let mut grid = Grid::new(50, 50, 800, 800);
+        let mut bumper = Wall::new(Vec2::new(0.0, 400.0), Vec2::new(800.0, 400.0), None, None, Some(1.0), Some(0.0));
+        bumper.kick = 300.0;
+        grid.add_wall(bumper);
+        // Both weigh 4 and creep into the bumper far below the restitution threshold
+        let ball = Ball::new(Vec2::new(200.0, 385.5), Some(Vec2::new(0.0, 5.0)), None, None, Some(1.0), Some(0.0), Some(4.0));
+        let ball = grid.add_ball(ball);
+        let square = Polygon::rectangle(20.0, 20.0);
+        let velocity = Some(Vec2::new(100.0, 5.0));
+        let square = grid.add_polygon(Polygon::new(Vec2::new(600.0, 385.5), square, velocity, None, Some(1.0), Some(0.0), None));
+        assert_eq!(grid.polygon(square).unwrap().mass, 4.0);
+
+        ContactSolver::new().solve(&mut grid, DT);
+
+        // The kick is shared between the square's two contact points, so both leave at kick / mass
+        let ball = grid.ball(ball).unwrap();
+        let square = grid.polygon(square).unwrap();
+        assert!((ball.velocity.y + 75.0).abs() < 0.5, "ball vy = {}", ball.velocity.y);
+        assert!((square.velocity.y + 75.0).abs() < 0.5, "square vy = {}", square.velocity.y);
+        // Friction is limited by the impulse stopping the approach, not by the kick
+        assert!(square.velocity.x > 90.0, "square vx = {}", square.velocity.x);
+    }
+
+    #[test]
+    fn bumper_only_kicks_on_impact() {
+        let mut grid = Grid::new(50, 50, 800, 800);
+        let mut bumper = Wall::new(Vec2::new(0.0, 700.0), Vec2::new(800.0, 700.0), None, None, None, Some(0.0));
+        bumper.kick = 300.0;
+        let bumper = grid.add_wall(bumper);
+        // Sunk into the bumper it rests on, and too heavy for the kick to lift off it
+        let ball = grid.add_ball(Ball::new(Vec2::new(400.0, 686.0), None, None, None, None, Some(0.0), Some(400.0)));
+        let mut solver = ContactSolver::new();
+        // Touching down is an impact, which gets the kick
+        step(&mut grid, &mut solver);
+        let vy = grid.ball(ball).unwrap().velocity.y;
+        assert!((vy + 0.75).abs() < 0.05, "vy = {vy}");
+        // Resting on the bumper afterwards is not
+        for _ in 0..60 {
+            step(&mut grid, &mut solver);
+            let vy = grid.ball(ball).unwrap().velocity.y;
+            assert!(vy.abs() < 0.05, "vy = {vy}");
+        }
+        assert_eq!(solver.contacts().get(PhysItem::Ball(ball), PhysItem::Wall(bumper)).unwrap().age, 61);
+    }
+
+    #[test]
+    fn conveyor_carries_what_rests_on_it() {
+        let mut grid = Grid::new(50, 50, 800, 800);
+        let mut belt = Wall::new(Vec2::new(0.0, 700.0), Vec2::new(800.0, 700.0), None, None, Some(1.0), Some(0.0));
+        belt.surface_speed = 120.0;
+        grid.add_wall(belt);
+        let ball = grid.add_ball(Ball::new(Vec2::new(200.0, 685.0), None, None, None, Some(1.0), Some(0.0), None));
+        let square = Polygon::new(Vec2::new(500.0, 685.0), Polygon::rectangle(20.0, 20.0), None, None, Some(1.0), Some(0.0), None);
+        let square = grid.add_polygon(square);
+        let mut solver = ContactSolver::new();
+        for _ in 0..60 {
+            step(&mut grid, &mut solver);
+        }
+        // The box rides the belt, while the ball rolls with its bottom at the belt's speed
+        let square = grid.polygon(square).unwrap();
+        assert!((square.velocity - Vec2::new(120.0, 0.0)).length() < 1.0, "square v = {:?}", square.velocity);
+        let ball = grid.ball(ball).unwrap();
+        let bottom = ball.velocity_at(Vec2::new(0.0, 10.0));
+        assert!((bottom - Vec2::new(120.0, 0.0)).length() < 1.0, "ball bottom v = {bottom:?}");
+        assert!((ball.position.y - 685.0).abs() < 1.0, "ball y = {}", ball.position.y);
+    }
+}
@@ -0,0 +1,124 @@
+/// Default number of simulation ticks per second
+pub const TICK_RATE: f32 = 60.0;
+/// Default maximum number of ticks run to catch up in a single frame
+pub const MAX_STEPS: u32 = 5;
+
+/// A fixed-timestep accumulator that decouples the simulation from the frame rate.
+///
+/// Wall-clock frame times are added to an accumulator, which is drained in whole ticks
+/// of `1 / tick_rate` seconds. Every tick advances the simulation by the same amount,
+/// so a run gives the same results however fast it is displayed. The time left in the
+/// accumulator is reported as [`alpha`](FixedTimestep::alpha) for interpolating the
+/// rendered positions between the last two ticks.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FixedTimestep {
+    /// Number of simulation ticks per second
+    pub tick_rate: f32,
+    /// Maximum number of ticks run in one frame; time beyond that is dropped so a
+    /// long hiccup does not make the simulation fall further and further behind
+    pub max_steps: u32,
+    /// Frame time not yet consumed by a tick, in seconds
+    accumulator: f32,
+}
+
+impl FixedTimestep {
+    /// Creates a new fixed-timestep accumulator.
+    ///
+    /// # Arguments
+    ///
+    /// * `tick_rate` - Optional number of ticks per second (default: [`TICK_RATE`])
+    /// * `max_steps` - Optional maximum number of ticks per frame (default: [`MAX_STEPS`])
+    ///
+    /// # Returns
+    ///
+    /// A new FixedTimestep instance with an empty accumulator
+    pub fn new(tick_rate: Option<f32>, max_steps: Option<u32>) -> FixedTimestep {
+        FixedTimestep {
+            tick_rate: tick_rate.unwrap_or(TICK_RATE),
+            max_steps: max_steps.unwrap_or(MAX_STEPS),
+            accumulator: 0.0,
+        }
+    }
+
+    /// Returns the length of one tick in seconds.
+    pub fn dt(&self) -> f32 {
+        1.0 / self.tick_rate
+    }
+
+    /// Adds a frame's duration and returns how many ticks should be run for it.
+    ///
+    /// At most `max_steps` ticks are returned. If more time than that has built up,
+    /// the excess is discarded and the simulation runs slower than real time.
+    ///
+    /// # Arguments
+    ///
+    /// * `frame_dt` - Wall-clock time since the last frame in seconds
+    ///
+    /// # Returns
+    ///
+    /// The number of ticks to advance the simulation by
+    pub fn accumulate(&mut self, frame_dt: f32) -> u32 {
+        let dt = self.dt();
+        self.accumulator += frame_dt.max(0.0);
+        let mut steps = 0;
+        while self.accumulator >= dt && steps < self.max_steps {
+            self.accumulator -= dt;
+            steps += 1;
+        }
+        // Drop the time we could not catch up on, keeping less than one tick
+        if self.accumulator >= dt {
+            self.accumulator %= dt;
+        }
+        steps
+    }
+
+    /// Returns how far the accumulator is into the next tick, in [0, 1).
+    ///
+    /// Renderers blend the previous and current positions of each body by this
+    /// fraction to draw smooth motion between ticks.
+    pub fn alpha(&self) -> f32 {
+        self.accumulator / self.dt()
+    }
+}
+
+impl Default for FixedTimestep {
+    fn default() -> FixedTimestep {
+        FixedTimestep::new(None, None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn remainder_carries_over_between_frames() {
+        let mut timestep = FixedTimestep::new(Some(10.0), None);
+        // 0.25 s is two ticks with 0.05 s left over, and the leftover completes a tick later
+        assert_eq!(timestep.accumulate(0.25), 2);
+        assert!((timestep.alpha() - 0.5).abs() < 1e-4);
+        assert_eq!(timestep.accumulate(0.06), 1);
+        assert!((timestep.alpha() - 0.1).abs() < 1e-4);
+    }
+
+    #[test]
+    fn alpha_stays_below_one() {
+        let mut timestep = FixedTimestep::default();
+        for frame_dt in [0.001, 0.007, 0.016, 0.017, 0.033, 0.05, 0.0] {
+            timestep.accumulate(frame_dt);
+            let alpha = timestep.alpha();
+            assert!((0.0..1.0).contains(&alpha), "alpha = {alpha} after {frame_dt}");
+        }
+    }
+
+    #[test]
+    fn long_frames_are_clamped() {
+        let mut timestep = FixedTimestep::new(Some(60.0), Some(5));
+        // A two second hiccup runs at most five ticks and drops the rest
+        assert_eq!(timestep.accumulate(2.0), 5);
+        assert!(timestep.alpha() < 1.0);
+        assert_eq!(timestep.accumulate(0.0), 0);
+        // Negative frame times are ignored
+        assert_eq!(timestep.accumulate(-1.0), 0);
+    }
+}
@@ -0,0 +1,978 @@
+use crate::arena::{
+    BallHandle, CapsuleHandle, JointHandle, PolygonHandle, PrismaticHandle, RevoluteHandle, SensorHandle, WallHandle,
+};
+use crate::cleanup::CleanupPolicy;
+use crate::grid::Grid;
+use crate::integrator::Integrator;
+use crate::items::{Ball, PhysItem, Wall, GRAVITY};
+use crate::capsule::Capsule;
+use crate::contact::{ContactCache, ContactEvent, ContactEventKind};
+use crate::field::ForceField;
+use crate::joint::{Joint, PrismaticJoint, RevoluteJoint};
+use crate::motion::WallMotion;
+use crate::polygon::Polygon;
+use crate::sensor::{Sensor, SensorEvent};
+use crate::library::Vec2;
+use crate::solver::ContactSolver;
+use crate::timestep::FixedTimestep;
+
+/// A headless physics world that can be stepped without any renderer.
+///
+/// The world owns the spatial [`Grid`] holding every ball and wall, along with the
+/// simulation settings used when advancing it. Rendering is left to the caller, who
+/// can borrow the grid after each step.
+pub struct World {
+    /// Spatial partitioning grid holding all physics objects
+    grid: Grid,
+    /// Acceleration applied to every ball, polygon and capsule each step [x, y]
+    pub gravity: Vec2,
+    /// Force fields adding to gravity where they reach, such as magnets and fans
+    pub fields: Vec<ForceField>,
+    /// Contact solver used to resolve collisions each step
+    pub solver: ContactSolver,
+    /// Whether fast balls are swept against walls and balls to prevent tunneling
+    /// (continuous collision detection)
+    pub ccd: bool,
+    /// Numerical method used to advance positions and velocities
+    pub integrator: Integrator,
+    /// Fixed-timestep accumulator used by [`update`](World::update)
+    pub timestep: FixedTimestep,
+    /// Rules deciding which bodies are despawned at the start of every step
+    pub cleanup: CleanupPolicy,
+    /// Whether a [`Persist`](crate::ContactEventKind::Persist) event is queued every step
+    /// for each pair that stays in contact (off by default)
+    pub persist_events: bool,
+    /// Contact events of the steps run since they were last drained
+    events: Vec<ContactEvent>,
+    /// Sensor events of the steps run since they were last drained
+    sensor_events: Vec<SensorEvent>,
+    /// Bodies despawned by the cleanup policy since they were last drained
+    removed: Vec<PhysItem>,
+}
+
+impl World {
+    /// Creates a new world around an existing grid.
+    ///
+    /// Uses [`GRAVITY`] with no force fields, a default [`ContactSolver`] and a
+    /// [`CleanupPolicy`] that removes bodies leaving the grid as the initial settings,
+    /// with continuous collision detection enabled.
+    ///
+    /// # Arguments
+    ///
+    /// * `grid` - The spatial grid the world simulates
+    ///
+    /// # Returns
+    ///
+    /// A new World instance
+    pub fn new(grid: Grid) -> World {
+        World {
+            grid,
+            gravity: GRAVITY,
+            fields: Vec::new(),
+            solver: ContactSolver::new(),
+            ccd: true,
+            integrator: Integrator::default(),
+            timestep: FixedTimestep::default(),
+            cleanup: CleanupPolicy::default(),
+            persist_events: false,
+            events: Vec::new(),
+            sensor_events: Vec::new(),
+            removed: Vec::new(),
+        }
+    }
+
+    /// Returns a reference to the world's grid.
+    pub fn grid(&self) -> &Grid {
+        &self.grid
+    }
+
+    /// Returns a mutable reference to the world's grid.
+    pub fn grid_mut(&mut self) -> &mut Grid {
+        &mut self.grid
+    }
+
+    /// Adds a ball to the world.
+    ///
+    /// # Arguments
+    ///
+    /// * `ball` - The ball to add
+    ///
+    /// # Returns
+    ///
+    /// The handle of the new ball
+    pub fn add_ball(&mut self, ball: Ball) -> BallHandle {
+        self.grid.add_ball(ball)
+    }
+
+    /// Adds a wall to the world.
+    ///
+    /// # Arguments
+    ///
+    /// * `wall` - The wall to add
+    ///
+    /// # Returns
+    ///
+    /// The handle of the new wall
+    pub fn add_wall(&mut self, wall: Wall) -> WallHandle {
+        self.grid.add_wall(wall)
+    }
+
+    /// Adds a polygon to the world.
+    ///
+    /// # Arguments
+    ///
+    /// * `polygon` - The polygon to add
+    ///
+    /// # Returns
+    ///
+    /// The handle of the new polygon
+    pub fn add_polygon(&mut self, polygon: Polygon) -> PolygonHandle {
+        self.grid.add_polygon(polygon)
+    }
+
+    /// Adds a capsule to the world.
+    ///
+    /// # Arguments
+    ///
+    /// * `capsule` - The capsule to add
+    ///
+    /// # Returns
+    ///
+    /// The handle of the new capsule
+    pub fn add_capsule(&mut self, capsule: Capsule) -> CapsuleHandle {
+        self.grid.add_capsule(capsule)
+    }
+
+    /// Adds a sensor to the world's grid.
+    ///
+    /// # Arguments
+    ///
+    /// * `sensor` - The sensor to add
+    ///
+    /// # Returns
+    ///
+    /// The handle of the new sensor
+    pub fn add_sensor(&mut self, sensor: Sensor) -> SensorHandle {
+        self.grid.add_sensor(sensor)
+    }
+
+    /// Adds a joint between balls to the world's grid.
+    ///
+    /// # Arguments
+    ///
+    /// * `joint` - The joint to add
+    ///
+    /// # Returns
+    ///
+    /// The handle of the new joint, or `None` if either ball was already removed
+    pub fn add_joint(&mut self, joint: Joint) -> Option<JointHandle> {
+        self.grid.add_joint(joint)
+    }
+
+    /// Removes a joint from the world's grid.
+    ///
+    /// # Arguments
+    ///
+    /// * `handle` - The handle of the joint to remove
+    ///
+    /// # Returns
+    ///
+    /// The removed joint, or `None` if it was already removed
+    pub fn remove_joint(&mut self, handle: JointHandle) -> Option<Joint> {
+        self.grid.remove_joint(handle)
+    }
+
+    /// Adds a hinge to the world's grid.
+    ///
+    /// # Arguments
+    ///
+    /// * `joint` - The hinge to add
+    ///
+    /// # Returns
+    ///
+    /// The handle of the new hinge, or `None` if either body was already removed
+    pub fn add_revolute_joint(&mut self, joint: RevoluteJoint) -> Option<RevoluteHandle> {
+        self.grid.add_revolute_joint(joint)
+    }
+
+    /// Removes a hinge from the world's grid.
+    ///
+    /// # Arguments
+    ///
+    /// * `handle` - The handle of the hinge to remove
+    ///
+    /// # Returns
+    ///
+    /// The removed hinge, or `None` if it was already removed
+    pub fn remove_revolute_joint(&mut self, handle: RevoluteHandle) -> Option<RevoluteJoint> {
+        self.grid.remove_revolute_joint(handle)
+    }
+
+    /// Adds a slider to the world's grid.
+    ///
+    /// # Arguments
+    ///
+    /// * `joint` - The slider to add
+    ///
+    /// # Returns
+    ///
+    /// The handle of the new slider, or `None` if either body was already removed
+    pub fn add_prismatic_joint(&mut self, joint: PrismaticJoint) -> Option<PrismaticHandle> {
+        self.grid.add_prismatic_joint(joint)
+    }
+
+    /// Removes a slider from the world's grid.
+    ///
+    /// # Arguments
+    ///
+    /// * `handle` - The handle of the slider to remove
+    ///
+    /// # Returns
+    ///
+    /// The removed slider, or `None` if it was already removed
+    pub fn remove_prismatic_joint(&mut self, handle: PrismaticHandle) -> Option<PrismaticJoint> {
+        self.grid.remove_prismatic_joint(handle)
+    }
+
+    /// Removes a ball from the world's grid, along with the joints, hinges and sliders
+    /// attached to it.
+    ///
+    /// # Arguments
+    ///
+    /// * `handle` - The handle of the ball to remove
+    ///
+    /// # Returns
+    ///
+    /// The removed ball, or `None` if it was already removed
+    pub fn remove_ball(&mut self, handle: BallHandle) -> Option<Ball> {
+        self.grid.remove_ball(handle)
+    }
+
+    /// Removes a wall from the world's grid, along with the hinges and sliders attached to it.
+    ///
+    /// # Arguments
+    ///
+    /// * `handle` - The handle of the wall to remove
+    ///
+    /// # Returns
+    ///
+    /// The removed wall, or `None` if it was already removed
+    pub fn remove_wall(&mut self, handle: WallHandle) -> Option<Wall> {
+        self.grid.remove_wall(handle)
+    }
+
+    /// Removes a polygon from the world's grid, along with the hinges and sliders
+    /// attached to it.
+    ///
+    /// # Arguments
+    ///
+    /// * `handle` - The handle of the polygon to remove
+    ///
+    /// # Returns
+    ///
+    /// The removed polygon, or `None` if it was already removed
+    pub fn remove_polygon(&mut self, handle: PolygonHandle) -> Option<Polygon> {
+        self.grid.remove_polygon(handle)
+    }
+
+    /// Removes a capsule from the world's grid, along with the hinges and sliders
+    /// attached to it.
+    ///
+    /// # Arguments
+    ///
+    /// * `handle` - The handle of the capsule to remove
+    ///
+    /// # Returns
+    ///
+    /// The removed capsule, or `None` if it was already removed
+    pub fn remove_capsule(&mut self, handle: CapsuleHandle) -> Option<Capsule> {
+        self.grid.remove_capsule(handle)
+    }
+
+    /// Removes a sensor from the world's grid.
+    ///
+    /// # Arguments
+    ///
+    /// * `handle` - The handle of the sensor to remove
+    ///
+    /// # Returns
+    ///
+    /// The removed sensor, or `None` if it was already removed
+    pub fn remove_sensor(&mut self, handle: SensorHandle) -> Option<Sensor> {
+        self.grid.remove_sensor(handle)
+    }
+
+    /// Returns a ball.
+    ///
+    /// # Arguments
+    ///
+    /// * `handle` - The handle of the ball
+    ///
+    /// # Returns
+    ///
+    /// The ball, or `None` if it has been removed
+    pub fn ball(&self, handle: BallHandle) -> Option<&Ball> {
+        self.grid.ball(handle)
+    }
+
+    /// Returns a mutable reference to a ball.
+    ///
+    /// Use [`set_ball_position`](World::set_ball_position) to teleport it.
+    ///
+    /// # Arguments
+    ///
+    /// * `handle` - The handle of the ball
+    pub fn ball_mut(&mut self, handle: BallHandle) -> Option<&mut Ball> {
+        self.grid.ball_mut(handle)
+    }
+
+    /// Returns a wall.
+    ///
+    /// Use [`set_wall_endpoints`](World::set_wall_endpoints) to move it.
+    ///
+    /// # Arguments
+    ///
+    /// * `handle` - The handle of the wall
+    pub fn wall(&self, handle: WallHandle) -> Option<&Wall> {
+        self.grid.wall(handle)
+    }
+
+    /// Returns a polygon.
+    ///
+    /// # Arguments
+    ///
+    /// * `handle` - The handle of the polygon
+    pub fn polygon(&self, handle: PolygonHandle) -> Option<&Polygon> {
+        self.grid.polygon(handle)
+    }
+
+    /// Returns a mutable reference to a polygon.
+    ///
+    /// # Arguments
+    ///
+    /// * `handle` - The handle of the polygon
+    pub fn polygon_mut(&mut self, handle: PolygonHandle) -> Option<&mut Polygon> {
+        self.grid.polygon_mut(handle)
+    }
+
+    /// Returns a capsule.
+    ///
+    /// # Arguments
+    ///
+    /// * `handle` - The handle of the capsule
+    pub fn capsule(&self, handle: CapsuleHandle) -> Option<&Capsule> {
+        self.grid.capsule(handle)
+    }
+
+    /// Returns a mutable reference to a capsule.
+    ///
+    /// # Arguments
+    ///
+    /// * `handle` - The handle of the capsule
+    pub fn capsule_mut(&mut self, handle: CapsuleHandle) -> Option<&mut Capsule> {
+        self.grid.capsule_mut(handle)
+    }
+
+    /// Returns a sensor, including the balls currently inside it.
+    ///
+    /// # Arguments
+    ///
+    /// * `handle` - The handle of the sensor
+    pub fn sensor(&self, handle: SensorHandle) -> Option<&Sensor> {
+        self.grid.sensor(handle)
+    }
+
+    /// Teleports a ball to a new position, keeping its velocity.
+    ///
+    /// # Arguments
+    ///
+    /// * `handle` - The handle of the ball to move
+    /// * `position` - New position [x, y]
+    ///
+    /// # Returns
+    ///
+    /// Whether the ball still exists
+    pub fn set_ball_position(&mut self, handle: BallHandle, position: impl Into<Vec2>) -> bool {
+        self.grid.set_ball_position(handle, position)
+    }
+
+    /// Moves a wall's endpoints.
+    ///
+    /// # Arguments
+    ///
+    /// * `handle` - The handle of the wall to move
+    /// * `a` - New starting point of the wall [x, y]
+    /// * `b` - New ending point of the wall [x, y]
+    ///
+    /// # Returns
+    ///
+    /// Whether the wall still exists
+    pub fn set_wall_endpoints(&mut self, handle: WallHandle, a: impl Into<Vec2>, b: impl Into<Vec2>) -> bool {
+        self.grid.set_wall_endpoints(handle, a, b)
+    }
+
+    /// Makes a wall follow a scripted path from where it is now, or stops it.
+    ///
+    /// # Arguments
+    ///
+    /// * `handle` - The handle of the wall
+    /// * `motion` - Path to follow, or `None` to leave the wall static where it is
+    ///
+    /// # Returns
+    ///
+    /// Whether the wall still exists
+    pub fn set_wall_motion(&mut self, handle: WallHandle, motion: Option<WallMotion>) -> bool {
+        self.grid.set_wall_motion(handle, motion)
+    }
+
+    /// Advances the simulation by a frame's worth of fixed-length ticks.
+    ///
+    /// The frame time is fed to the world's [`FixedTimestep`], and [`step`](World::step)
+    /// is called once for every whole tick it yields, always with the same `dt`. This
+    /// keeps results independent of the frame rate. Use [`alpha`](World::alpha) to
+    /// interpolate the rendered positions afterwards.
+    ///
+    /// # Arguments
+    ///
+    /// * `frame_dt` - Wall-clock time since the last frame in seconds
+    ///
+    /// # Returns
+    ///
+    /// The number of ticks that were run
+    pub fn update(&mut self, frame_dt: f32) -> u32 {
+        let steps = self.timestep.accumulate(frame_dt);
+        for _ in 0..steps {
+            self.step(self.timestep.dt());
+        }
+        steps
+    }
+
+    /// Returns how far the world is between its last two ticks, in [0, 1).
+    pub fn alpha(&self) -> f32 {
+        self.timestep.alpha()
+    }
+
+    /// Returns the contacts resolved in the last step.
+    ///
+    /// Each [`Contact`](crate::Contact) records the two bodies, the normal, the
+    /// penetration and the impulses applied at every contact point.
+    pub fn contacts(&self) -> &ContactCache {
+        self.solver.contacts()
+    }
+
+    /// Returns the contact events queued since they were last drained, oldest first.
+    pub fn events(&self) -> &[ContactEvent] {
+        &self.events
+    }
+
+    /// Removes and returns the queued contact events, oldest first.
+    ///
+    /// Every step queues a [`Begin`](crate::ContactEventKind::Begin) event for each pair
+    /// that started touching and an [`End`](crate::ContactEventKind::End) event for each
+    /// pair that separated. With [`persist_events`](World::persist_events) set, it also
+    /// queues a [`Persist`](crate::ContactEventKind::Persist) event for each pair that
+    /// stays in contact, which adds one event per resting body every step. The queue
+    /// grows until it is drained, so call this once per frame (or per tick).
+    pub fn drain_events(&mut self) -> std::vec::Drain<'_, ContactEvent> {
+        self.events.drain(..)
+    }
+
+    /// Returns the sensor events queued since they were last drained, oldest first.
+    pub fn sensor_events(&self) -> &[SensorEvent] {
+        &self.sensor_events
+    }
+
+    /// Removes and returns the queued sensor events, oldest first.
+    ///
+    /// Every step queues an [`Enter`](crate::SensorEventKind::Enter) event for each ball
+    /// that started overlapping a sensor and an [`Exit`](crate::SensorEventKind::Exit)
+    /// event for each ball that left one. The queue grows until it is drained.
+    pub fn drain_sensor_events(&mut self) -> std::vec::Drain<'_, SensorEvent> {
+        self.sensor_events.drain(..)
+    }
+
+    /// Returns the bodies despawned by the cleanup policy since they were last drained.
+    pub fn removed(&self) -> &[PhysItem] {
+        &self.removed
+    }
+
+    /// Removes and returns the handles of the bodies despawned by the cleanup policy.
+    ///
+    /// Bodies removed directly, such as with [`remove_ball`](World::remove_ball), are
+    /// not reported. The queue grows until it is drained.
+    pub fn drain_removed(&mut self) -> std::vec::Drain<'_, PhysItem> {
+        self.removed.drain(..)
+    }
+
+    /// Advances the simulation by one time step.
+    ///
+    /// Despawns the bodies matching the [`CleanupPolicy`] and queues their
+    /// [`handles`](World::removed), integrates ball, polygon and capsule motion under
+    /// gravity and the force fields, then resolves all contacts and joints with the [`ContactSolver`] and
+    /// queues the contact [`events`](World::events). Finally the sensors are updated and their
+    /// [`events`](World::sensor_events) queued.
+    /// Cleanup runs first so callers can inspect bodies that reached a kill zone
+    /// before they are despawned.
+    ///
+    /// # Arguments
+    ///
+    /// * `dt` - Time delta in seconds
+    pub fn step(&mut self, dt: f32) {
+        let removed = self.cleanup.apply(&mut self.grid, dt);
+        self.removed.extend(removed);
+        self.grid.integrate(self.gravity, &self.fields, dt, self.ccd, self.integrator);
+        let events = self.solver.solve(&mut self.grid, dt);
+        let persist_events = self.persist_events;
+        self.events.extend(events.into_iter().filter(|event| persist_events || event.kind != ContactEventKind::Persist));
+        let sensor_events = self.grid.update_sensors();
+        self.sensor_events.extend(sensor_events);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::library::Vec2;
+    use crate::contact::ContactEventKind;
+    use crate::field::WindRegion;
+    use crate::filter::CollisionFilter;
+
+    const DT: f32 = 1.0 / 60.0;
+
+    #[test]
+    fn step_runs_without_a_renderer() {
+        let mut world = World::new(Grid::new(50, 50, 800, 800));
+        world.add_wall(Wall::new(Vec2::new(0.0, 700.0), Vec2::new(800.0, 700.0), None, None, None, None));
+        world.add_ball(Ball::new(Vec2::new(400.0, 100.0), None, None, None, None, None, None));
+        for _ in 0..600 {
+            world.step(DT);
+        }
+        assert_eq!(world.grid().ball_count(), 1);
+    }
+
+    #[test]
+    fn ball_falls_under_gravity() {
+        let mut world = World::new(Grid::new(50, 50, 800, 800));
+        world.add_ball(Ball::new(Vec2::new(400.0, 100.0), None, None, None, None, None, None));
+        for _ in 0..60 {
+            world.step(DT);
+        }
+        // One second of free fall: y = g t² / 2 below the start
+        let ball = world.grid().balls().next().unwrap();
+        assert!((ball.position.y - (100.0 + GRAVITY.y / 2.0)).abs() < 5.0, "y = {}", ball.position.y);
+        assert!((ball.velocity.y - GRAVITY.y).abs() < 1.0, "vy = {}", ball.velocity.y);
+        assert_eq!(ball.position.x, 400.0);
+    }
+
+    #[test]
+    fn ball_comes_to_rest_on_a_floor() {
+        let mut world = World::new(Grid::new(50, 50, 800, 800));
+        world.add_wall(Wall::new(Vec2::new(0.0, 700.0), Vec2::new(800.0, 700.0), None, None, None, None));
+        world.add_ball(Ball::new(Vec2::new(400.0, 600.0), None, None, None, None, None, None));
+        for _ in 0..300 {
+            world.step(DT);
+        }
+        // Resting on top of the 10 px wide floor
+        let ball = world.grid().balls().next().unwrap();
+        assert!((ball.position.y - 685.0).abs() < 1.0, "y = {}", ball.position.y);
+        assert!(ball.velocity.y.abs() < 10.0, "vy = {}", ball.velocity.y);
+    }
+
+    #[test]
+    fn resting_ball_stays_still_on_a_floor() {
+        let mut world = World::new(Grid::new(50, 50, 800, 800));
+        world.add_wall(Wall::new(Vec2::new(0.0, 700.0), Vec2::new(800.0, 700.0), None, None, None, None));
+        world.add_ball(Ball::new(Vec2::new(400.0, 600.0), None, None, None, None, None, None));
+        for _ in 0..300 {
+            world.step(DT);
+        }
+        // The contact solver settles the ball completely rather than letting it jitter
+        let rest = world.grid().balls().next().unwrap().position;
+        for _ in 0..60 {
+            world.step(DT);
+            let ball = world.grid().balls().next().unwrap();
+            assert!(ball.velocity.length() < 1.0, "v = {:?}", ball.velocity);
+            assert!((ball.position - rest).length() < 0.1, "position = {:?}", ball.position);
+        }
+    }
+
+    #[test]
+    fn ball_collision_conserves_momentum() {
+        let mut world = World::new(Grid::new(50, 50, 800, 800));
+        world.gravity = Vec2::ZERO;
+        let heavy = Ball::new(Vec2::new(300.0, 400.0), Some(Vec2::new(100.0, 0.0)), None, None, None, Some(0.5), Some(2.0));
+        let light = Ball::new(Vec2::new(340.0, 400.0), Some(Vec2::new(-100.0, 0.0)), None, None, None, Some(0.5), Some(1.0));
+        world.add_ball(heavy);
+        world.add_ball(light);
+        for _ in 0..30 {
+            world.step(DT);
+        }
+        let momentum = world.grid().balls().fold(Vec2::ZERO, |total, ball| total + ball.velocity * ball.mass);
+        assert!((momentum - Vec2::new(100.0, 0.0)).length() < 1e-3, "momentum = {momentum:?}");
+        // The balls met, and the light one was sent back the way it came
+        let light = world.grid().balls().find(|ball| ball.mass == 1.0).unwrap();
+        assert!(light.velocity.x > 0.0, "vx = {}", light.velocity.x);
+    }
+
+    #[test]
+    fn ball_rolls_down_a_ramp() {
+        let mut world = World::new(Grid::new(50, 50, 800, 800));
+        // A slope of one in two, rough enough for the ball to roll instead of slide
+        world.add_wall(Wall::new(Vec2::new(100.0, 300.0), Vec2::new(700.0, 600.0), None, None, Some(1.0), Some(0.0)));
+        let start = Vec2::new(200.0, 350.0) + Vec2::new(1.0, -2.0).normalize() * 15.0;
+        world.add_ball(Ball::new(start, None, None, None, Some(1.0), Some(0.0), None));
+        for _ in 0..60 {
+            world.step(DT);
+        }
+        let ball = world.grid().balls().next().unwrap();
+        let speed = ball.velocity.length();
+        assert!(speed > 50.0, "speed = {speed}");
+        // Rolling without slipping: the rim turns as fast as the center moves
+        let rim_speed = ball.angular_velocity * ball.radius as f32;
+        assert!((rim_speed - speed).abs() < 0.05 * speed, "rim {rim_speed} vs center {speed}");
+    }
+
+    #[test]
+    fn box_lands_and_settles_on_a_floor() {
+        let mut world = World::new(Grid::new(50, 50, 800, 800));
+        world.add_wall(Wall::new(Vec2::new(0.0, 700.0), Vec2::new(800.0, 700.0), None, None, None, None));
+        let mut block = Polygon::new(Vec2::new(400.0, 500.0), Polygon::rectangle(40.0, 40.0), None, None, None, None, None);
+        // Land on a corner so the box has to tip over onto a face
+        block.angle = 0.3;
+        let block = world.add_polygon(block);
+        for _ in 0..300 {
+            world.step(DT);
+        }
+        // Resting flat on top of the 10 px wide floor
+        let block = world.grid().polygon(block).unwrap();
+        assert!((block.position.y - 675.0).abs() < 1.0, "y = {}", block.position.y);
+        let tilt = block.angle.rem_euclid(std::f32::consts::FRAC_PI_2);
+        assert!(tilt.min(std::f32::consts::FRAC_PI_2 - tilt) < 0.02, "angle = {}", block.angle);
+        assert!(block.velocity.length() < 1.0, "v = {:?}", block.velocity);
+        assert!(block.angular_velocity.abs() < 0.05, "w = {}", block.angular_velocity);
+    }
+
+    #[test]
+    fn capsule_comes_to_rest_on_a_floor() {
+        let mut world = World::new(Grid::new(50, 50, 800, 800));
+        world.add_wall(Wall::new(Vec2::new(0.0, 700.0), Vec2::new(800.0, 700.0), None, None, None, None));
+        // Dropped slightly tilted, so one end lands first
+        let rod = world.add_capsule(Capsule::new(Vec2::new(360.0, 590.0), Vec2::new(440.0, 610.0), 8.0, None, None, None, None, None));
+        for _ in 0..300 {
+            world.step(DT);
+        }
+        // Lying flat on top of the 10 px wide floor
+        let rod = world.grid().capsule(rod).unwrap();
+        assert!((rod.position.y - 687.0).abs() < 1.0, "y = {}", rod.position.y);
+        assert!(rod.angle.sin().abs() < 0.02, "angle = {}", rod.angle);
+        assert!(rod.velocity.length() < 1.0, "v = {:?}", rod.velocity);
+        assert!(rod.angular_velocity.abs() < 0.05, "w = {}", rod.angular_velocity);
+    }
+
+    #[test]
+    fn fast_ball_slides_along_a_wall() {
+        let mut world = World::new(Grid::new(50, 50, 1600, 800));
+        world.add_wall(Wall::new(Vec2::new(0.0, 700.0), Vec2::new(1600.0, 700.0), None, None, Some(0.0), None));
+        world.add_ball(Ball::new(Vec2::new(100.0, 685.0), Some(Vec2::new(1500.0, 0.0)), None, None, Some(0.0), None, None));
+        for _ in 0..30 {
+            world.step(DT);
+        }
+        // Half a second at 1500 px/s, not held back by the floor it slides on
+        let ball = world.grid().balls().next().unwrap();
+        assert!((ball.position.x - 850.0).abs() < 5.0, "x = {}", ball.position.x);
+        assert!((ball.position.y - 685.0).abs() < 1.0, "y = {}", ball.position.y);
+    }
+
+    #[test]
+    fn every_integrator_keeps_a_sliding_ball_on_the_floor() {
+        let integrators = [
+            Integrator::ExplicitEuler,
+            Integrator::SemiImplicitEuler,
+            Integrator::VelocityVerlet,
+            Integrator::RK4,
+        ];
+        for integrator in integrators {
+            let mut world = World::new(Grid::new(50, 50, 1600, 800));
+            world.integrator = integrator;
+            world.add_wall(Wall::new(Vec2::new(0.0, 700.0), Vec2::new(1600.0, 700.0), None, None, Some(0.0), None));
+            world.add_ball(Ball::new(Vec2::new(100.0, 685.0), Some(Vec2::new(1500.0, 0.0)), None, None, Some(0.0), None, None));
+            for _ in 0..30 {
+                world.step(DT);
+            }
+            // Gravity pulling into the floor must not slow the ball down or lift it off
+            let ball = world.grid().balls().next().unwrap();
+            assert!((ball.position.x - 850.0).abs() < 5.0, "{integrator:?}: x = {}", ball.position.x);
+            assert!((ball.position.y - 685.0).abs() < 1.0, "{integrator:?}: y = {}", ball.position.y);
+            assert!(ball.velocity.y.abs() < 10.0, "{integrator:?}: vy = {}", ball.velocity.y);
+        }
+    }
+
+    #[test]
+    fn ccd_stops_tunneling_through_a_thin_wall() {
+        let mut world = World::new(Grid::new(50, 50, 800, 800));
+        world.gravity = Vec2::ZERO;
+        world.add_wall(Wall::new(Vec2::new(0.0, 400.0), Vec2::new(800.0, 400.0), None, None, None, Some(0.0)));
+        // 2000 px/s moves the ball 33 px a step, more than the 30 px a 10 px wall blocks
+        world.add_ball(Ball::new(Vec2::new(400.0, 100.0), Some(Vec2::new(0.0, 2000.0)), None, None, None, Some(0.0), None));
+        for _ in 0..60 {
+            world.step(DT);
+        }
+        let ball = world.grid().balls().next().unwrap();
+        assert!((ball.position.y - 385.0).abs() < 1.0, "y = {}", ball.position.y);
+    }
+
+    fn pile_world() -> World {
+        let mut world = World::new(Grid::new(50, 50, 800, 800));
+        world.add_wall(Wall::new(Vec2::new(0.0, 700.0), Vec2::new(800.0, 700.0), None, None, None, None));
+        world.add_wall(Wall::new(Vec2::new(300.0, 300.0), Vec2::new(300.0, 700.0), None, None, None, None));
+        world.add_wall(Wall::new(Vec2::new(500.0, 300.0), Vec2::new(500.0, 700.0), None, None, None, None));
+        // Packed so tightly that position correction pushes bodies across sections
+        for i in 0..40 {
+            let position = Vec2::new(380.0 + (i % 8) as f32 * 6.0, 620.0 + (i / 8) as f32 * 6.0);
+            if i % 3 == 0 {
+                world.add_polygon(Polygon::new(position, Polygon::rectangle(16.0, 16.0), None, None, None, None, None));
+            } else {
+                world.add_ball(Ball::new(position, None, Some(8), None, None, None, None));
+            }
+        }
+        world
+    }
+
+    #[test]
+    fn identical_worlds_step_identically() {
+        let state = |world: &World| {
+            let balls = world.grid().balls().map(|b| (b.position, b.angle));
+            let polygons = world.grid().polygons().map(|p| (p.position, p.angle));
+            balls.chain(polygons).map(|(p, a)| [p.x.to_bits(), p.y.to_bits(), a.to_bits()]).collect::<Vec<_>>()
+        };
+        let (mut first, mut second) = (pile_world(), pile_world());
+        for _ in 0..120 {
+            first.step(DT);
+            second.step(DT);
+            assert_eq!(state(&first), state(&second));
+        }
+    }
+
+    #[test]
+    fn update_runs_fixed_steps() {
+        let mut world = World::new(Grid::new(50, 50, 800, 800));
+        world.add_ball(Ball::new(Vec2::new(400.0, 100.0), None, None, None, None, None, None));
+        let steps: u32 = (0..10).map(|_| world.update(DT)).sum();
+        assert_eq!(steps, 10);
+    }
+
+    #[test]
+    fn update_is_independent_of_the_frame_rate() {
+        let mut fast = World::new(Grid::new(50, 50, 800, 800));
+        let mut slow = World::new(Grid::new(50, 50, 800, 800));
+        for world in [&mut fast, &mut slow] {
+            // Power-of-two frame times add up exactly
+            world.timestep = FixedTimestep::new(Some(64.0), None);
+            world.add_ball(Ball::new(Vec2::new(400.0, 100.0), Some(Vec2::new(50.0, 0.0)), None, None, None, None, None));
+        }
+        for _ in 0..64 {
+            fast.update(1.0 / 64.0);
+        }
+        for _ in 0..16 {
+            slow.update(1.0 / 16.0);
+        }
+        let (a, b) = (fast.grid().balls().next().unwrap(), slow.grid().balls().next().unwrap());
+        assert!((a.position - b.position).length() < 1e-2, "{:?} vs {:?}", a.position, b.position);
+    }
+
+    #[test]
+    fn step_blows_balls_inside_a_wind_region() {
+        let mut world = World::new(Grid::new(50, 50, 800, 800));
+        world.gravity = Vec2::ZERO;
+        let region = WindRegion::Rectangle { min: Vec2::new(0.0, 0.0), max: Vec2::new(400.0, 800.0) };
+        world.fields.push(ForceField::Wind { region, acceleration: Vec2::new(100.0, 0.0) });
+        let inside = world.add_ball(Ball::new(Vec2::new(200.0, 400.0), None, None, None, None, None, None));
+        let outside = world.add_ball(Ball::new(Vec2::new(600.0, 400.0), None, None, None, None, None, None));
+        for _ in 0..30 {
+            world.step(DT);
+        }
+        let inside = world.ball(inside).unwrap();
+        assert!((inside.velocity - Vec2::new(50.0, 0.0)).length() < 0.1, "v = {:?}", inside.velocity);
+        assert!(inside.position.x > 210.0, "x = {}", inside.position.x);
+        let outside = world.ball(outside).unwrap();
+        assert_eq!(outside.velocity, Vec2::ZERO);
+        assert_eq!(outside.position, Vec2::new(600.0, 400.0));
+    }
+
+    #[test]
+    fn step_pulls_polygons_towards_an_attractor() {
+        let mut world = World::new(Grid::new(50, 50, 800, 800));
+        world.gravity = Vec2::ZERO;
+        // 25 px/s² towards the center from 200 px away
+        world.fields.push(ForceField::Radial { center: Vec2::new(600.0, 400.0), strength: 1.0e6, min_distance: 20.0 });
+        let square = Polygon::new(Vec2::new(400.0, 400.0), Polygon::rectangle(20.0, 20.0), None, None, None, None, None);
+        let square = world.add_polygon(square);
+        for _ in 0..30 {
+            world.step(DT);
+        }
+        let square = world.grid().polygon(square).unwrap();
+        assert!(square.velocity.x > 12.0 && square.velocity.y == 0.0, "v = {:?}", square.velocity);
+        assert!(square.position.x > 400.0, "x = {}", square.position.x);
+        assert_eq!(square.angular_velocity, 0.0);
+    }
+
+    #[test]
+    fn contact_events_begin_persist_and_end() {
+        let mut world = World::new(Grid::new(50, 50, 800, 800));
+        world.persist_events = true;
+        world.add_wall(Wall::new(Vec2::new(0.0, 700.0), Vec2::new(800.0, 700.0), None, None, None, Some(0.0)));
+        world.add_ball(Ball::new(Vec2::new(400.0, 680.0), None, None, None, None, Some(0.0), None));
+        let mut kinds = Vec::new();
+        for _ in 0..60 {
+            world.step(DT);
+            kinds.extend(world.drain_events().map(|event| event.kind));
+        }
+        // Lift the ball off the floor again
+        world.gravity = -GRAVITY;
+        for _ in 0..30 {
+            world.step(DT);
+            kinds.extend(world.drain_events().map(|event| event.kind));
+        }
+        let begin = kinds.iter().position(|kind| *kind == ContactEventKind::Begin).unwrap();
+        assert_eq!(kinds.iter().filter(|kind| **kind == ContactEventKind::Begin).count(), 1);
+        assert_eq!(kinds.iter().filter(|kind| **kind == ContactEventKind::End).count(), 1);
+        assert_eq!(kinds.last(), Some(&ContactEventKind::End));
+        let persisted = &kinds[begin + 1..kinds.len() - 1];
+        assert!(persisted.len() > 30 && persisted.iter().all(|kind| *kind == ContactEventKind::Persist));
+    }
+
+    #[test]
+    fn resting_contacts_queue_no_events_by_default() {
+        let mut world = World::new(Grid::new(50, 50, 800, 800));
+        world.add_wall(Wall::new(Vec2::new(0.0, 700.0), Vec2::new(800.0, 700.0), None, None, None, Some(0.0)));
+        world.add_ball(Ball::new(Vec2::new(400.0, 680.0), None, None, None, None, Some(0.0), None));
+        // Left undrained while the ball rests, the queue only holds the landing
+        for _ in 0..120 {
+            world.step(DT);
+        }
+        let kinds: Vec<ContactEventKind> = world.events().iter().map(|event| event.kind).collect();
+        assert_eq!(kinds, vec![ContactEventKind::Begin]);
+    }
+
+    /// Builds a gravity-free world with a bouncy one-way platform from x = 300 to 500 at
+    /// y = 400 that holds balls up from above.
+    fn one_way_world() -> World {
+        let mut world = World::new(Grid::new(50, 50, 800, 800));
+        world.gravity = Vec2::ZERO;
+        // Given right to left, so `nvec` points up
+        let mut platform = Wall::new(Vec2::new(500.0, 400.0), Vec2::new(300.0, 400.0), None, None, None, Some(1.0));
+        platform.one_way = true;
+        world.add_wall(platform);
+        world
+    }
+
+    #[test]
+    fn one_way_wall_lets_balls_through_from_behind() {
+        let mut world = one_way_world();
+        let ball = world.add_ball(Ball::new(Vec2::new(400.0, 500.0), Some(Vec2::new(0.0, -300.0)), None, None, None, Some(1.0), None));
+        for _ in 0..40 {
+            world.step(DT);
+        }
+        let ball = world.ball(ball).unwrap();
+        assert!(ball.position.y < 350.0, "y = {}", ball.position.y);
+        assert_eq!(ball.velocity, Vec2::new(0.0, -300.0));
+    }
+
+    #[test]
+    fn one_way_wall_bounces_balls_from_the_front() {
+        let mut world = one_way_world();
+        let ball = world.add_ball(Ball::new(Vec2::new(400.0, 300.0), Some(Vec2::new(0.0, 300.0)), None, None, None, Some(1.0), None));
+        for _ in 0..40 {
+            world.step(DT);
+        }
+        let ball = world.ball(ball).unwrap();
+        assert!(ball.position.y < 385.0, "y = {}", ball.position.y);
+        assert!((ball.velocity.y + 300.0).abs() < 1.0, "vy = {}", ball.velocity.y);
+    }
+
+    #[test]
+    fn one_way_wall_stops_fast_balls_from_the_front() {
+        let mut world = one_way_world();
+        // 50 px per step, far more than the ball's radius plus the wall's half width
+        let ball = world.add_ball(Ball::new(Vec2::new(400.0, 200.0), Some(Vec2::new(0.0, 3000.0)), None, None, None, Some(1.0), None));
+        for _ in 0..10 {
+            world.step(DT);
+            let y = world.ball(ball).unwrap().position.y;
+            assert!(y < 390.0, "y = {y}");
+        }
+        assert!(world.ball(ball).unwrap().velocity.y < 0.0);
+    }
+
+    #[test]
+    fn one_way_wall_ends_still_collide() {
+        let mut world = one_way_world();
+        // Rolling along the platform's line into its end, neither in front nor behind
+        let ball = world.add_ball(Ball::new(Vec2::new(600.0, 400.0), Some(Vec2::new(-300.0, 0.0)), None, None, None, Some(1.0), None));
+        for _ in 0..30 {
+            world.step(DT);
+        }
+        let ball = world.ball(ball).unwrap();
+        assert!(ball.position.x > 510.0, "x = {}", ball.position.x);
+        assert!(ball.velocity.x > 0.0, "vx = {}", ball.velocity.x);
+    }
+
+    #[test]
+    fn filtered_balls_pass_through_each_other_but_hit_walls() {
+        let mut world = World::new(Grid::new(50, 50, 800, 800));
+        world.gravity = Vec2::ZERO;
+        world.add_wall(Wall::new(Vec2::new(200.0, 300.0), Vec2::new(200.0, 500.0), None, None, None, Some(1.0)));
+        world.add_wall(Wall::new(Vec2::new(600.0, 300.0), Vec2::new(600.0, 500.0), None, None, None, Some(1.0)));
+        // Both collide with walls (layer 1), but not with each other's layer
+        let mut left = Ball::new(Vec2::new(300.0, 400.0), Some(Vec2::new(200.0, 0.0)), Some(10), None, None, Some(1.0), None);
+        left.filter = CollisionFilter::new(0b010, 0b001, None);
+        let mut right = Ball::new(Vec2::new(500.0, 400.0), Some(Vec2::new(-200.0, 0.0)), Some(12), None, None, Some(1.0), None);
+        right.filter = CollisionFilter::new(0b100, 0b001, None);
+        world.add_ball(left);
+        world.add_ball(right);
+        let by_radius = |world: &World, radius: i32| {
+            let ball = world.grid().balls().find(|b| b.radius == radius).unwrap();
+            (ball.position, ball.velocity)
+        };
+
+        for _ in 0..45 {
+            world.step(DT);
+        }
+        // They met in the middle and kept going
+        let ((left, _), (right, _)) = (by_radius(&world, 10), by_radius(&world, 12));
+        assert!(left.x > right.x + 50.0, "left {left:?}, right {right:?}");
+        for _ in 0..75 {
+            world.step(DT);
+        }
+        // Then bounced off the walls on the far side
+        let ((left, left_vel), (right, right_vel)) = (by_radius(&world, 10), by_radius(&world, 12));
+        assert!(left_vel.x < 0.0 && left.x < 600.0, "left {left:?}");
+        assert!(right_vel.x > 0.0 && right.x > 200.0, "right {right:?}");
+    }
+
+    #[test]
+    fn bodies_are_moved_and_removed_by_handle() {
+        let mut world = World::new(Grid::new(50, 50, 800, 800));
+        let floor = world.add_wall(Wall::new(Vec2::new(0.0, 700.0), Vec2::new(800.0, 700.0), None, None, None, None));
+        let upper = world.add_wall(Wall::new(Vec2::new(0.0, 300.0), Vec2::new(800.0, 300.0), None, None, None, None));
+        let ball = world.add_ball(Ball::new(Vec2::new(100.0, 100.0), None, None, None, None, None, None));
+
+        // Teleport the ball, then swap the floor for the upper wall moved down below it
+        assert!(world.set_ball_position(ball, [400.0, 600.0]));
+        assert!(world.remove_wall(floor).is_some());
+        assert!(world.wall(floor).is_none() && world.remove_wall(floor).is_none());
+        assert!(world.set_wall_endpoints(upper, [0.0, 690.0], [800.0, 690.0]));
+        for _ in 0..120 {
+            world.step(DT);
+        }
+        let resting = world.ball(ball).unwrap();
+        assert!((resting.position.y - 675.0).abs() < 1.0, "y = {}", resting.position.y);
+        assert_eq!(world.wall(upper).unwrap().a, Vec2::new(0.0, 690.0));
+
+        world.ball_mut(ball).unwrap().velocity = Vec2::new(0.0, -300.0);
+        assert!(world.ball(ball).unwrap().velocity.y < 0.0);
+        assert!(world.remove_ball(ball).is_some());
+        assert!(!world.set_ball_position(ball, [0.0, 0.0]));
+        assert_eq!(world.grid().ball_count(), 0);
+        assert_eq!(world.grid().wall_count(), 1);
+    }
+}